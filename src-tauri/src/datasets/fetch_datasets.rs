@@ -1,5 +1,5 @@
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
 use tauri::{Manager, path::BaseDirectory};
 
@@ -16,10 +16,11 @@ pub async fn fetch_datasets(
     let cache_path = get_cache_path(&handle)?;
     let should_invalidate = invalidate_cache.unwrap_or(false);
 
-    if !should_invalidate && is_cache_valid(&cache_path) {
-        if let Ok(cached_datasets) = load_from_cache(&cache_path) {
-            return Ok(cached_datasets);
-        }
+    if !should_invalidate
+        && is_cache_valid(&cache_path)
+        && let Ok(cached_datasets) = load_from_cache(&cache_path)
+    {
+        return Ok(cached_datasets);
     }
 
     let client = reqwest::Client::builder()
@@ -133,7 +134,7 @@ async fn fetch_single_dataset(
 fn get_cache_path(handle: &tauri::AppHandle) -> Result<PathBuf, String> {
     let app_cache_dir = handle
         .path()
-        .resolve("cache".to_string(), BaseDirectory::AppConfig)
+        .resolve("cache", BaseDirectory::AppConfig)
         .map_err(|e| format!("Failed to resolve App Config directory: {}", e))?;
 
     fs::create_dir_all(&app_cache_dir)
@@ -142,25 +143,24 @@ fn get_cache_path(handle: &tauri::AppHandle) -> Result<PathBuf, String> {
     Ok(app_cache_dir.join(CACHE_FILENAME))
 }
 
-fn is_cache_valid(cache_path: &PathBuf) -> bool {
-    if let Ok(metadata) = fs::metadata(cache_path) {
-        if let Ok(modified) = metadata.modified() {
-            if let Ok(elapsed) = SystemTime::now().duration_since(modified) {
-                return elapsed < Duration::from_secs(CACHE_TTL_HOURS * 3600);
-            }
-        }
+fn is_cache_valid(cache_path: &Path) -> bool {
+    if let Ok(metadata) = fs::metadata(cache_path)
+        && let Ok(modified) = metadata.modified()
+        && let Ok(elapsed) = SystemTime::now().duration_since(modified)
+    {
+        return elapsed < Duration::from_secs(CACHE_TTL_HOURS * 3600);
     }
     false
 }
 
-fn load_from_cache(cache_path: &PathBuf) -> Result<Vec<Dataset>, String> {
+fn load_from_cache(cache_path: &Path) -> Result<Vec<Dataset>, String> {
     let data =
         fs::read_to_string(cache_path).map_err(|e| format!("Failed to read cache file: {}", e))?;
 
     serde_json::from_str(&data).map_err(|e| format!("Failed to deserialize cache: {}", e))
 }
 
-fn save_to_cache(cache_path: &PathBuf, datasets: &Vec<Dataset>) -> Result<(), String> {
+fn save_to_cache(cache_path: &Path, datasets: &[Dataset]) -> Result<(), String> {
     let json = serde_json::to_string(datasets)
         .map_err(|e| format!("Failed to serialize datasets: {}", e))?;
 
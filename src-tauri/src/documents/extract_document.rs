@@ -0,0 +1,118 @@
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+use std::path::Path;
+
+use crate::documents::extract_text;
+use crate::library::index;
+use crate::utils::db;
+use crate::utils::path_policy::{Operation, PathPolicy};
+
+/// Stored content is capped so a single document cannot bloat the database. Counts are
+/// always taken over the full text.
+const MAX_CONTENT_BYTES: usize = 1024 * 1024;
+
+/// How far back from the cap a cut may move to land on whitespace.
+const WORD_BOUNDARY_WINDOW: usize = 256;
+
+/// A row of the `documents` table. `id` is set once the extraction has been stored and
+/// linked to the file's `files.file_metadata_id`, which requires the file to be indexed.
+#[derive(Debug, Serialize)]
+pub struct DocumentExtract {
+    pub id: Option<i64>,
+    pub content: String,
+    pub line_count: i64,
+    pub word_count: i64,
+    pub truncated: bool,
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub async fn extract_document(
+    handle: tauri::AppHandle,
+    path: String,
+) -> Result<DocumentExtract, String> {
+    let resolved = PathPolicy::load(&handle)
+        .await
+        .check(Path::new(&path), Operation::Read)?;
+
+    let mut document = tauri::async_runtime::spawn_blocking(move || extract(&resolved))
+        .await
+        .map_err(|e| format!("Failed to extract document: {}", e))??;
+
+    if let Ok(pool) = db::pool(&handle).await {
+        document.id = store(&pool, &path, &document).await?;
+    }
+
+    Ok(document)
+}
+
+pub fn extract(path: &Path) -> Result<DocumentExtract, String> {
+    if !path.is_file() {
+        return Err("Path is not a file".to_string());
+    }
+
+    let text = extract_text(path)?;
+    let line_count = if text.is_empty() {
+        0
+    } else {
+        text.lines().count() as i64
+    };
+    let word_count = text.split_whitespace().count() as i64;
+    let (content, truncated) = truncate(text);
+
+    Ok(DocumentExtract {
+        id: None,
+        content,
+        line_count,
+        word_count,
+        truncated,
+    })
+}
+
+/// Cuts `text` to at most `MAX_CONTENT_BYTES` on a character boundary, preferring the
+/// last whitespace before the cap so no word is split.
+fn truncate(mut text: String) -> (String, bool) {
+    if text.len() <= MAX_CONTENT_BYTES {
+        return (text, false);
+    }
+
+    let limit = text.floor_char_boundary(MAX_CONTENT_BYTES);
+    let cut = text[..limit]
+        .rfind(char::is_whitespace)
+        .filter(|&index| limit - index <= WORD_BOUNDARY_WINDOW)
+        .unwrap_or(limit);
+
+    text.truncate(cut);
+    text.truncate(text.trim_end().len());
+    (text, true)
+}
+
+/// Stores `document` through `index::store_metadata`.
+pub async fn store(
+    pool: &Pool<Sqlite>,
+    path: &str,
+    document: &DocumentExtract,
+) -> Result<Option<i64>, String> {
+    index::store_metadata(
+        pool,
+        path,
+        "document",
+        r#"
+        UPDATE documents SET
+            content = ?, line_count = ?, word_count = ?, updated_at = CURRENT_TIMESTAMP
+        WHERE id = ?
+        RETURNING id
+        "#,
+        r#"
+        INSERT INTO documents (content, line_count, word_count)
+        VALUES (?, ?, ?)
+        RETURNING id
+        "#,
+        |query| {
+            query
+                .bind(&document.content)
+                .bind(document.line_count)
+                .bind(document.word_count)
+        },
+    )
+    .await
+}
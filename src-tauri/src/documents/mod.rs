@@ -0,0 +1,77 @@
+use std::fs;
+use std::path::Path;
+
+use crate::files::sniff::sniff;
+
+pub mod extract_document;
+pub mod office;
+pub mod pdf;
+pub mod plain_text;
+pub mod rtf;
+
+/// Source files above this size are not extracted; the whole file is held in memory.
+const MAX_SOURCE_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Reads the text of a document. The extension picks the extractor, with the file
+/// header as a fallback for files without a known extension.
+pub fn extract_text(path: &Path) -> Result<String, String> {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    let format = match extension.as_str() {
+        "txt" | "text" | "md" | "markdown" | "rtf" | "docx" | "odt" | "pdf" => extension,
+        _ => sniff(path)
+            .map(|sniffed| sniffed.extension)
+            .unwrap_or_default(),
+    };
+
+    let text = match format.as_str() {
+        "txt" | "text" | "md" | "markdown" => plain_text::extract(&read_source(path)?),
+        "rtf" => rtf::extract(&read_source(path)?),
+        "docx" => office::extract_docx(path)?,
+        "odt" => office::extract_odt(path)?,
+        "pdf" => pdf::extract(&read_source(path)?)?,
+        _ => return Err("Unsupported document format".to_string()),
+    };
+
+    Ok(normalize(&text))
+}
+
+fn read_source(path: &Path) -> Result<Vec<u8>, String> {
+    let size = fs::metadata(path)
+        .map_err(|e| format!("Failed to read metadata: {}", e))?
+        .len();
+    if size > MAX_SOURCE_SIZE {
+        return Err("Document is too large to extract".to_string());
+    }
+
+    fs::read(path).map_err(|e| format!("Failed to read document: {}", e))
+}
+
+/// Unifies line endings, drops NUL characters SQLite text handling stops at, and
+/// collapses the runs of blank lines layout-based extractors leave behind.
+fn normalize(text: &str) -> String {
+    let text = text.replace("\r\n", "\n").replace(['\r', '\u{0C}'], "\n");
+
+    let mut normalized = String::with_capacity(text.len());
+    let mut blank_lines = 0;
+    for line in text.lines() {
+        let line = line.replace('\0', "");
+        let line = line.trim_end();
+        if line.is_empty() {
+            blank_lines += 1;
+            if blank_lines > 1 {
+                continue;
+            }
+        } else {
+            blank_lines = 0;
+        }
+
+        normalized.push_str(line);
+        normalized.push('\n');
+    }
+
+    normalized.trim().to_string()
+}
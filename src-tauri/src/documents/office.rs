@@ -0,0 +1,124 @@
+use quick_xml::Reader;
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::{BytesRef, BytesStart, Event};
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+/// Decompressed XML above this size is cut off, so a zip bomb cannot exhaust memory.
+const MAX_XML_SIZE: u64 = 128 * 1024 * 1024;
+
+/// Text of a Word document. Only runs inside `w:t` hold text; paragraphs, breaks and
+/// tabs are separate elements.
+pub fn extract_docx(path: &Path) -> Result<String, String> {
+    let mut in_text = false;
+
+    read_xml(path, "word/document.xml", |event, text| match event {
+        XmlEvent::Start(name) if name == b"t" => in_text = true,
+        XmlEvent::End(name) if name == b"t" => in_text = false,
+        XmlEvent::End(name) if name == b"p" => text.push('\n'),
+        XmlEvent::Empty(name, _) if name == b"tab" => text.push('\t'),
+        XmlEvent::Empty(name, _) if name == b"br" || name == b"cr" => text.push('\n'),
+        XmlEvent::Text(content) if in_text => text.push_str(content),
+        _ => {}
+    })
+}
+
+/// Text of an OpenDocument text file. All character data inside `office:body` is text;
+/// runs of spaces are stored as `text:s` elements with a count.
+pub fn extract_odt(path: &Path) -> Result<String, String> {
+    let mut in_body = false;
+
+    read_xml(path, "content.xml", |event, text| match event {
+        XmlEvent::Start(name) if name == b"body" => in_body = true,
+        XmlEvent::End(name) if name == b"body" => in_body = false,
+        _ if !in_body => {}
+        XmlEvent::End(name) if name == b"p" || name == b"h" => text.push('\n'),
+        XmlEvent::Empty(name, element) if name == b"s" => {
+            let count = element
+                .and_then(|element| element.try_get_attribute("text:c").ok().flatten())
+                .and_then(|count| count.unescape_value().ok()?.parse::<usize>().ok())
+                .unwrap_or(1);
+            text.push_str(&" ".repeat(count.min(1024)));
+        }
+        XmlEvent::Empty(name, _) if name == b"tab" => text.push('\t'),
+        XmlEvent::Empty(name, _) if name == b"line-break" => text.push('\n'),
+        XmlEvent::Text(content) => text.push_str(content),
+        _ => {}
+    })
+}
+
+enum XmlEvent<'a> {
+    Start(&'a [u8]),
+    End(&'a [u8]),
+    Empty(&'a [u8], Option<&'a BytesStart<'a>>),
+    Text(&'a str),
+}
+
+/// Streams one XML entry of a zip container through `handle`, passing element local
+/// names and decoded character data, with entities already resolved.
+fn read_xml(
+    path: &Path,
+    entry_name: &str,
+    mut handle: impl FnMut(XmlEvent, &mut String),
+) -> Result<String, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open document: {}", e))?;
+    let mut archive = zip::ZipArchive::new(BufReader::new(file))
+        .map_err(|e| format!("Failed to read document archive: {}", e))?;
+    let entry = archive
+        .by_name(entry_name)
+        .map_err(|e| format!("Failed to read {}: {}", entry_name, e))?;
+
+    let mut reader = Reader::from_reader(BufReader::new(entry.take(MAX_XML_SIZE)));
+    let mut buffer = Vec::new();
+    let mut text = String::new();
+
+    loop {
+        let event = reader
+            .read_event_into(&mut buffer)
+            .map_err(|e| format!("Failed to parse {}: {}", entry_name, e))?;
+
+        match event {
+            Event::Start(element) => {
+                handle(XmlEvent::Start(element.local_name().as_ref()), &mut text)
+            }
+            Event::End(element) => handle(XmlEvent::End(element.local_name().as_ref()), &mut text),
+            Event::Empty(ref element) => handle(
+                XmlEvent::Empty(element.local_name().as_ref(), Some(element)),
+                &mut text,
+            ),
+            Event::Text(content) => {
+                let content = content
+                    .decode()
+                    .map_err(|e| format!("Failed to decode {}: {}", entry_name, e))?;
+                handle(XmlEvent::Text(&content), &mut text);
+            }
+            Event::CData(content) => {
+                let content = content
+                    .decode()
+                    .map_err(|e| format!("Failed to decode {}: {}", entry_name, e))?;
+                handle(XmlEvent::Text(&content), &mut text);
+            }
+            Event::GeneralRef(reference) => {
+                if let Some(resolved) = resolve_reference(&reference) {
+                    handle(XmlEvent::Text(&resolved), &mut text);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+
+        buffer.clear();
+    }
+
+    Ok(text)
+}
+
+fn resolve_reference(reference: &BytesRef) -> Option<String> {
+    if let Ok(Some(c)) = reference.resolve_char_ref() {
+        return Some(c.to_string());
+    }
+
+    let name = reference.decode().ok()?;
+    resolve_predefined_entity(&name).map(str::to_string)
+}
@@ -0,0 +1,18 @@
+use std::panic::{self, AssertUnwindSafe};
+
+/// Text layer of a PDF. Scanned documents without one are reported as errors rather
+/// than stored as empty content.
+pub fn extract(bytes: &[u8]) -> Result<String, String> {
+    // The extractor panics on some malformed fonts and content streams.
+    let extracted = panic::catch_unwind(AssertUnwindSafe(|| {
+        pdf_extract::extract_text_from_mem(bytes)
+    }))
+    .map_err(|_| "Failed to extract PDF text: malformed document".to_string())?
+    .map_err(|e| format!("Failed to extract PDF text: {}", e))?;
+
+    if extracted.trim().is_empty() {
+        return Err("PDF has no text layer".to_string());
+    }
+
+    Ok(extracted)
+}
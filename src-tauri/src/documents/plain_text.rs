@@ -0,0 +1,18 @@
+use encoding_rs::{Encoding, WINDOWS_1252};
+
+/// Decodes a text file. A byte order mark wins, then UTF-8; anything else is read as
+/// Windows-1252, which maps every byte and matches most legacy Western text.
+pub fn extract(bytes: &[u8]) -> String {
+    if let Some((encoding, bom_length)) = Encoding::for_bom(bytes) {
+        let (text, _) = encoding.decode_without_bom_handling(&bytes[bom_length..]);
+        return text.into_owned();
+    }
+
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => WINDOWS_1252
+            .decode_without_bom_handling(bytes)
+            .0
+            .into_owned(),
+    }
+}
@@ -0,0 +1,239 @@
+use encoding_rs::{Encoding, WINDOWS_1252};
+
+/// Destinations holding tables, metadata or embedded objects rather than body text.
+const SKIPPED_DESTINATIONS: &[&str] = &[
+    "fonttbl",
+    "colortbl",
+    "stylesheet",
+    "info",
+    "pict",
+    "object",
+    "header",
+    "headerl",
+    "headerr",
+    "headerf",
+    "footer",
+    "footerl",
+    "footerr",
+    "footerf",
+    "fldinst",
+    "themedata",
+    "colorschememapping",
+    "latentstyles",
+    "datastore",
+    "listtable",
+    "listoverridetable",
+    "rsidtbl",
+    "xmlnstbl",
+    "generator",
+    "mmathPr",
+];
+
+#[derive(Clone, Copy)]
+struct GroupState {
+    skip: bool,
+    /// Number of fallback characters following each `\u` escape.
+    unicode_skip: usize,
+}
+
+/// Converts RTF to plain text, keeping paragraph breaks and tabs.
+pub fn extract(data: &[u8]) -> String {
+    let mut parser = Parser {
+        data,
+        position: 0,
+        text: String::new(),
+        pending_bytes: Vec::new(),
+        encoding: WINDOWS_1252,
+        fallback_to_skip: 0,
+    };
+    parser.run();
+    parser.text
+}
+
+struct Parser<'a> {
+    data: &'a [u8],
+    position: usize,
+    text: String,
+    /// Bytes from `\'hh` escapes, decoded together so multi-byte code pages work.
+    pending_bytes: Vec<u8>,
+    encoding: &'static Encoding,
+    fallback_to_skip: usize,
+}
+
+impl Parser<'_> {
+    fn run(&mut self) {
+        let mut stack = Vec::new();
+        let mut state = GroupState {
+            skip: false,
+            unicode_skip: 1,
+        };
+
+        while let Some(&byte) = self.data.get(self.position) {
+            self.position += 1;
+
+            match byte {
+                b'{' => {
+                    self.flush();
+                    stack.push(state);
+                }
+                b'}' => {
+                    self.flush();
+                    state = stack.pop().unwrap_or(state);
+                }
+                b'\\' => self.control(&mut state),
+                b'\r' | b'\n' => {}
+                _ => {
+                    if self.consume_fallback() || state.skip {
+                        continue;
+                    }
+                    self.pending_bytes.push(byte);
+                }
+            }
+        }
+
+        self.flush();
+    }
+
+    fn control(&mut self, state: &mut GroupState) {
+        let Some(&next) = self.data.get(self.position) else {
+            return;
+        };
+
+        if !next.is_ascii_alphabetic() {
+            self.position += 1;
+            match next {
+                b'\'' => {
+                    let hex = self.data.get(self.position..self.position + 2);
+                    self.position += 2;
+                    let byte = hex
+                        .and_then(|hex| std::str::from_utf8(hex).ok())
+                        .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+                    if let Some(byte) = byte
+                        && !self.consume_fallback()
+                        && !state.skip
+                    {
+                        self.pending_bytes.push(byte);
+                    }
+                }
+                b'*' => state.skip = true,
+                b'\\' | b'{' | b'}' => self.emit(state, next as char),
+                b'~' => self.emit(state, '\u{A0}'),
+                b'_' => self.emit(state, '-'),
+                b'\r' | b'\n' => self.emit(state, '\n'),
+                _ => {}
+            }
+            return;
+        }
+
+        let start = self.position;
+        while self
+            .data
+            .get(self.position)
+            .is_some_and(|b| b.is_ascii_alphabetic())
+        {
+            self.position += 1;
+        }
+        let word = String::from_utf8_lossy(&self.data[start..self.position]).into_owned();
+
+        let parameter_start = self.position;
+        if self.data.get(self.position) == Some(&b'-') {
+            self.position += 1;
+        }
+        while self
+            .data
+            .get(self.position)
+            .is_some_and(|b| b.is_ascii_digit())
+        {
+            self.position += 1;
+        }
+        let parameter = std::str::from_utf8(&self.data[parameter_start..self.position])
+            .ok()
+            .and_then(|p| p.parse::<i64>().ok());
+
+        // A single space delimits the control word and is not part of the text.
+        if self.data.get(self.position) == Some(&b' ') {
+            self.position += 1;
+        }
+
+        match word.as_str() {
+            "par" | "line" | "sect" | "page" | "row" => self.emit(state, '\n'),
+            "tab" | "cell" => self.emit(state, '\t'),
+            "emdash" => self.emit(state, '\u{2014}'),
+            "endash" => self.emit(state, '\u{2013}'),
+            "bullet" => self.emit(state, '\u{2022}'),
+            "lquote" => self.emit(state, '\u{2018}'),
+            "rquote" => self.emit(state, '\u{2019}'),
+            "ldblquote" => self.emit(state, '\u{201C}'),
+            "rdblquote" => self.emit(state, '\u{201D}'),
+            "emspace" | "enspace" | "qmspace" => self.emit(state, ' '),
+            "u" => {
+                if let Some(code) = parameter {
+                    // Values above 32767 are written as negative signed 16-bit numbers.
+                    let code = if code < 0 { code + 65536 } else { code };
+                    if let Some(c) = char::from_u32(code as u32) {
+                        self.emit(state, c);
+                    }
+                    self.fallback_to_skip = state.unicode_skip;
+                }
+            }
+            "uc" => state.unicode_skip = parameter.unwrap_or(1).max(0) as usize,
+            "ansicpg" => {
+                if let Some(encoding) = parameter.and_then(code_page) {
+                    self.encoding = encoding;
+                }
+            }
+            "bin" => {
+                self.position = self
+                    .position
+                    .saturating_add(parameter.unwrap_or(0).max(0) as usize);
+            }
+            word if SKIPPED_DESTINATIONS.contains(&word) => state.skip = true,
+            _ => {}
+        }
+    }
+
+    fn emit(&mut self, state: &GroupState, c: char) {
+        self.flush();
+        if !state.skip {
+            self.text.push(c);
+        }
+    }
+
+    /// Skips one fallback character left over from a `\u` escape.
+    fn consume_fallback(&mut self) -> bool {
+        if self.fallback_to_skip > 0 {
+            self.fallback_to_skip -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn flush(&mut self) {
+        if self.pending_bytes.is_empty() {
+            return;
+        }
+
+        let (decoded, _) = self
+            .encoding
+            .decode_without_bom_handling(&self.pending_bytes);
+        self.text.push_str(&decoded);
+        self.pending_bytes.clear();
+    }
+}
+
+fn code_page(code_page: i64) -> Option<&'static Encoding> {
+    let label = match code_page {
+        874 => "windows-874".to_string(),
+        932 => "shift_jis".to_string(),
+        936 => "gbk".to_string(),
+        949 => "euc-kr".to_string(),
+        950 => "big5".to_string(),
+        1250..=1258 => format!("windows-{}", code_page),
+        10000 => "macintosh".to_string(),
+        _ => return None,
+    };
+
+    Encoding::for_label(label.as_bytes())
+}
@@ -0,0 +1,112 @@
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+use crate::downloads::checksum::Checksum;
+use crate::downloads::{self, Download, DownloadManager};
+use crate::files::get_downloads_dir::resolve_downloads_dir;
+use crate::files::transfer;
+use crate::utils::db;
+use crate::utils::path_policy::{Operation, PathPolicy};
+
+/// Characters Windows does not allow in file names.
+const INVALID_NAME_CHARS: &[char] = &['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+
+/// Queues a download into `directory`, or the downloads folder when omitted. The file
+/// name defaults to the last segment of the URL. `checksum` takes `sha256:<hex>`,
+/// `md5:<hex>` or `blake3:<hex>` and is checked once the download completes.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn add_download(
+    handle: tauri::AppHandle,
+    url: String,
+    file_name: Option<String>,
+    directory: Option<String>,
+    checksum: Option<String>,
+) -> Result<Download, String> {
+    let parsed = reqwest::Url::parse(url.trim()).map_err(|e| format!("Invalid URL: {}", e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err("Only HTTP and HTTPS downloads are supported".into());
+    }
+
+    let checksum = checksum
+        .filter(|checksum| !checksum.trim().is_empty())
+        .map(|checksum| Checksum::parse(&checksum).map(|_| checksum.trim().to_string()))
+        .transpose()?;
+
+    let directory = match directory {
+        Some(directory) => {
+            let directory = PathPolicy::load(&handle)
+                .await
+                .check(Path::new(&directory), Operation::Write)?;
+            if !directory.is_dir() {
+                return Err("Destination is not a folder".into());
+            }
+            directory
+        }
+        None => resolve_downloads_dir(&handle).await?,
+    };
+
+    let name = file_name
+        .as_deref()
+        .map(sanitize_name)
+        .filter(|name| !name.is_empty())
+        .or_else(|| {
+            parsed
+                .path_segments()
+                .and_then(|mut segments| segments.next_back())
+                .map(sanitize_name)
+                .filter(|name| !name.is_empty())
+        })
+        .unwrap_or_else(|| "download".to_string());
+
+    let pool = db::pool(&handle).await?;
+    let reserved: Vec<String> = sqlx::query_scalar(
+        "SELECT destination FROM downloads WHERE status NOT IN ('completed', 'cancelled')",
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| format!("Failed to read downloads: {}", e))?;
+    let destination = unused_destination(&directory.join(name), &reserved);
+
+    let id: i64 = sqlx::query_scalar(
+        "INSERT INTO downloads (url, destination, checksum) VALUES (?, ?, ?) RETURNING id",
+    )
+    .bind(parsed.as_str())
+    .bind(destination.to_string_lossy().to_string())
+    .bind(&checksum)
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| format!("Failed to queue download: {}", e))?;
+
+    let manager = handle.state::<DownloadManager>();
+    manager.configure(&pool).await?;
+    manager.spawn(id)?;
+
+    downloads::load_download(&pool, id).await
+}
+
+fn sanitize_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if INVALID_NAME_CHARS.contains(&c) || c.is_control() {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect::<String>()
+        .trim()
+        .trim_matches('.')
+        .to_string()
+}
+
+/// Picks `name (1).ext`, `name (2).ext`, ... when the file, its partial download or
+/// another queued download already uses `path`.
+fn unused_destination(path: &Path, reserved: &[String]) -> PathBuf {
+    transfer::available_name(path, |candidate| {
+        transfer::is_taken(candidate)
+            || transfer::is_taken(&downloads::part_path(candidate))
+            || reserved
+                .iter()
+                .any(|reserved| Path::new(reserved) == candidate)
+    })
+}
@@ -0,0 +1,29 @@
+use std::path::Path;
+use tauri::Manager;
+
+use crate::downloads::{self, Download, DownloadManager, DownloadStatus};
+use crate::utils::db;
+
+/// Cancels a download and deletes its partial file.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn cancel_download(handle: tauri::AppHandle, id: i64) -> Result<Download, String> {
+    let pool = db::pool(&handle).await?;
+    let download = downloads::load_download(&pool, id).await?;
+    if matches!(
+        download.status,
+        DownloadStatus::Completed | DownloadStatus::Cancelled
+    ) {
+        return Err("Download has already finished".into());
+    }
+
+    downloads::set_status(&pool, id, DownloadStatus::Cancelled, None).await?;
+    // A running worker removes the partial file once it has stopped writing to it.
+    if !handle
+        .state::<DownloadManager>()
+        .stop(id, DownloadStatus::Cancelled)
+    {
+        let _ = std::fs::remove_file(downloads::part_path(Path::new(&download.destination)));
+    }
+
+    downloads::load_download(&pool, id).await
+}
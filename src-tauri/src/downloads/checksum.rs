@@ -0,0 +1,90 @@
+use md5::Md5;
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+use crate::library::content_hash;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Sha256,
+    Md5,
+    Blake3,
+}
+
+/// An expected digest, written as `sha256:<hex>`, `md5:<hex>` or `blake3:<hex>`. A bare
+/// hex digest is taken as SHA-256 or MD5 by its length.
+#[derive(Debug, Clone)]
+pub struct Checksum {
+    pub algorithm: Algorithm,
+    pub expected: String,
+}
+
+impl Checksum {
+    pub fn parse(value: &str) -> Result<Checksum, String> {
+        let value = value.trim();
+        let (algorithm, digest) = match value.split_once(':') {
+            Some((name, digest)) => {
+                let algorithm = match name.trim().to_lowercase().as_str() {
+                    "sha256" | "sha-256" => Algorithm::Sha256,
+                    "md5" => Algorithm::Md5,
+                    "blake3" => Algorithm::Blake3,
+                    other => return Err(format!("Unsupported checksum algorithm: {}", other)),
+                };
+                (algorithm, digest.trim())
+            }
+            None if value.len() == 32 => (Algorithm::Md5, value),
+            None => (Algorithm::Sha256, value),
+        };
+
+        let expected_length = match algorithm {
+            Algorithm::Md5 => 32,
+            Algorithm::Sha256 | Algorithm::Blake3 => 64,
+        };
+        if digest.len() != expected_length || !digest.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err("Checksum is not a valid hex digest".into());
+        }
+
+        Ok(Checksum {
+            algorithm,
+            expected: digest.to_lowercase(),
+        })
+    }
+
+    /// Hashes the file at `path` and compares the result.
+    pub fn verify(&self, path: &Path) -> Result<(), String> {
+        let actual = match self.algorithm {
+            Algorithm::Sha256 => digest_file::<Sha256>(path),
+            Algorithm::Md5 => digest_file::<Md5>(path),
+            Algorithm::Blake3 => content_hash::full_hash(path),
+        }
+        .map_err(|e| format!("Failed to hash download: {}", e))?;
+
+        if actual != self.expected {
+            return Err(format!(
+                "Checksum mismatch: expected {}, got {}",
+                self.expected, actual
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn digest_file<D: Digest>(path: &Path) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = D::new();
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
@@ -0,0 +1,68 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Token bucket shared by every running download, so `download_speed_limit` caps the
+/// total rate rather than each download's. Bytes are taken before they are written; a
+/// chunk larger than the tokens left puts the bucket in debt, which the next caller
+/// waits off.
+pub struct RateLimiter {
+    bucket: Mutex<Bucket>,
+}
+
+struct Bucket {
+    /// Bytes per second, `0` for no limit. Also the bucket size, allowing one second
+    /// of burst.
+    rate: u64,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RateLimiter {
+    pub fn new() -> RateLimiter {
+        RateLimiter {
+            bucket: Mutex::new(Bucket {
+                rate: 0,
+                tokens: 0.0,
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    pub fn set_rate(&self, rate: u64) {
+        if let Ok(mut bucket) = self.bucket.lock()
+            && bucket.rate != rate
+        {
+            bucket.rate = rate;
+            bucket.tokens = bucket.tokens.min(rate as f64);
+            bucket.refilled_at = Instant::now();
+        }
+    }
+
+    pub async fn acquire(&self, bytes: u64) {
+        let wait = {
+            let Ok(mut bucket) = self.bucket.lock() else {
+                return;
+            };
+            if bucket.rate == 0 {
+                return;
+            }
+
+            let now = Instant::now();
+            let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+            let rate = bucket.rate as f64;
+            bucket.tokens = (bucket.tokens + elapsed * rate).min(rate);
+            bucket.refilled_at = now;
+            bucket.tokens -= bytes as f64;
+
+            if bucket.tokens < 0.0 {
+                Duration::from_secs_f64(-bucket.tokens / rate)
+            } else {
+                Duration::ZERO
+            }
+        };
+
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
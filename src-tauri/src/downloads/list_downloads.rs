@@ -0,0 +1,8 @@
+use crate::downloads::{self, Download};
+use crate::utils::db;
+
+#[tauri::command(rename_all = "snake_case")]
+pub async fn list_downloads(handle: tauri::AppHandle) -> Result<Vec<Download>, String> {
+    let pool = db::pool(&handle).await?;
+    downloads::load_downloads(&pool).await
+}
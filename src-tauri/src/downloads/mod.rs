@@ -0,0 +1,360 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Sqlite};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::sync::Semaphore;
+
+use crate::downloads::limiter::RateLimiter;
+use crate::utils::db;
+
+pub mod add_download;
+pub mod cancel_download;
+mod checksum;
+mod limiter;
+pub mod list_downloads;
+pub mod pause_download;
+pub mod remove_download;
+pub mod resume_download;
+mod worker;
+
+/// How often the speed limit and concurrency are re-read from the settings.
+const SETTINGS_INTERVAL: Duration = Duration::from_secs(5);
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// A stalled connection fails the download, which can then be resumed.
+const READ_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Appended to the destination while the download is incomplete.
+const PART_SUFFIX: &str = ".part";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadStatus {
+    Queued,
+    Downloading,
+    Paused,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl DownloadStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DownloadStatus::Queued => "queued",
+            DownloadStatus::Downloading => "downloading",
+            DownloadStatus::Paused => "paused",
+            DownloadStatus::Completed => "completed",
+            DownloadStatus::Failed => "failed",
+            DownloadStatus::Cancelled => "cancelled",
+        }
+    }
+
+    fn parse(value: &str) -> DownloadStatus {
+        match value {
+            "queued" => DownloadStatus::Queued,
+            "downloading" => DownloadStatus::Downloading,
+            "paused" => DownloadStatus::Paused,
+            "completed" => DownloadStatus::Completed,
+            "cancelled" => DownloadStatus::Cancelled,
+            _ => DownloadStatus::Failed,
+        }
+    }
+
+    /// Whether the download is waiting for or holding a slot.
+    pub fn is_active(self) -> bool {
+        matches!(self, DownloadStatus::Queued | DownloadStatus::Downloading)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Download {
+    pub id: i64,
+    pub url: String,
+    /// Final path of the file. Data is written next to it with a `.part` suffix until
+    /// the download completes.
+    pub destination: String,
+    pub status: DownloadStatus,
+    pub total_bytes: Option<i64>,
+    pub downloaded_bytes: i64,
+    pub checksum: Option<String>,
+    pub error: Option<String>,
+    /// Row of the downloaded file in the `files` table, once completed.
+    pub file_id: Option<i64>,
+    pub created_at: String,
+}
+
+/// Payload of the `download-progress` events.
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadProgress {
+    pub id: i64,
+    pub status: DownloadStatus,
+    pub downloaded_bytes: i64,
+    pub total_bytes: Option<i64>,
+    pub bytes_per_second: u64,
+}
+
+type DownloadRow = (
+    i64,
+    String,
+    String,
+    String,
+    Option<i64>,
+    i64,
+    Option<String>,
+    Option<String>,
+    Option<i64>,
+    String,
+);
+
+const DOWNLOAD_COLUMNS: &str = "id, url, destination, status, total_bytes, downloaded_bytes, checksum, error, file_id, created_at";
+
+fn from_row(
+    (
+        id,
+        url,
+        destination,
+        status,
+        total_bytes,
+        downloaded_bytes,
+        checksum,
+        error,
+        file_id,
+        created_at,
+    ): DownloadRow,
+) -> Download {
+    Download {
+        id,
+        url,
+        destination,
+        status: DownloadStatus::parse(&status),
+        total_bytes,
+        downloaded_bytes,
+        checksum,
+        error,
+        file_id,
+        created_at,
+    }
+}
+
+pub async fn load_download(pool: &Pool<Sqlite>, id: i64) -> Result<Download, String> {
+    let row: Option<DownloadRow> = sqlx::query_as(&format!(
+        "SELECT {} FROM downloads WHERE id = ?",
+        DOWNLOAD_COLUMNS
+    ))
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("Failed to read download: {}", e))?;
+
+    row.map(from_row)
+        .ok_or_else(|| "Download not found".to_string())
+}
+
+pub async fn load_downloads(pool: &Pool<Sqlite>) -> Result<Vec<Download>, String> {
+    let rows: Vec<DownloadRow> = sqlx::query_as(&format!(
+        "SELECT {} FROM downloads ORDER BY id",
+        DOWNLOAD_COLUMNS
+    ))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to read downloads: {}", e))?;
+
+    Ok(rows.into_iter().map(from_row).collect())
+}
+
+pub async fn set_status(
+    pool: &Pool<Sqlite>,
+    id: i64,
+    status: DownloadStatus,
+    error: Option<&str>,
+) -> Result<(), String> {
+    sqlx::query(
+        "UPDATE downloads SET status = ?, error = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+    )
+    .bind(status.as_str())
+    .bind(error)
+    .bind(id)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to update download: {}", e))?;
+    Ok(())
+}
+
+pub fn part_path(destination: &Path) -> PathBuf {
+    let mut name = destination.file_name().unwrap_or_default().to_os_string();
+    name.push(PART_SUFFIX);
+    destination.with_file_name(name)
+}
+
+/// Asks a running download to stop. The command that sets it has already stored the
+/// new status; the worker only has to wind down.
+#[derive(Default)]
+pub struct StopSignal(Mutex<Option<DownloadStatus>>);
+
+impl StopSignal {
+    fn request(&self, status: DownloadStatus) {
+        if let Ok(mut requested) = self.0.lock() {
+            *requested = Some(status);
+        }
+    }
+
+    fn requested(&self) -> Option<DownloadStatus> {
+        self.0.lock().ok().and_then(|requested| *requested)
+    }
+}
+
+/// Runs queued downloads, at most `concurrent_downloads` at a time and together no
+/// faster than `download_speed_limit` MB/s. Downloads live in the `downloads` table, so
+/// the queue survives restarts and interrupted downloads resume where they stopped.
+pub struct DownloadManager {
+    handle: AppHandle,
+    client: reqwest::Client,
+    limiter: RateLimiter,
+    slots: Arc<Semaphore>,
+    slot_count: Mutex<usize>,
+    running: Mutex<HashMap<i64, Arc<StopSignal>>>,
+}
+
+impl DownloadManager {
+    pub fn start(handle: &AppHandle) -> DownloadManager {
+        let client = reqwest::Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .read_timeout(READ_TIMEOUT)
+            .build()
+            .unwrap_or_else(|e| {
+                log::warn!("Failed to create HTTP client: {}", e);
+                reqwest::Client::new()
+            });
+
+        tauri::async_runtime::spawn(monitor(handle.clone()));
+
+        DownloadManager {
+            handle: handle.clone(),
+            client,
+            limiter: RateLimiter::new(),
+            slots: Arc::new(Semaphore::new(1)),
+            slot_count: Mutex::new(1),
+            running: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Applies `download_speed_limit` and `concurrent_downloads`. Running downloads keep
+    /// their slot when the limit shrinks; new ones wait until enough have finished.
+    pub async fn configure(&self, pool: &Pool<Sqlite>) -> Result<(), String> {
+        let row: Option<(Option<i64>, Option<i64>)> = sqlx::query_as(
+            "SELECT download_speed_limit, concurrent_downloads FROM settings ORDER BY id LIMIT 1",
+        )
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Failed to read download settings: {}", e))?;
+
+        let (speed_limit, concurrent) = row.unwrap_or_default();
+        let megabytes = speed_limit.unwrap_or(0).max(0) as u64;
+        self.limiter.set_rate(megabytes.saturating_mul(1024 * 1024));
+
+        let wanted = concurrent.unwrap_or(1).max(1) as usize;
+        let mut count = self
+            .slot_count
+            .lock()
+            .map_err(|_| "Download queue is unavailable")?;
+        if wanted > *count {
+            self.slots.add_permits(wanted - *count);
+        } else if wanted < *count {
+            let slots = Arc::clone(&self.slots);
+            let excess = (*count - wanted) as u32;
+            tauri::async_runtime::spawn(async move {
+                if let Ok(permits) = slots.acquire_many_owned(excess).await {
+                    permits.forget();
+                }
+            });
+        }
+        *count = wanted;
+        Ok(())
+    }
+
+    /// Starts a worker for download `id`, which waits for a free slot.
+    pub fn spawn(&self, id: i64) -> Result<(), String> {
+        let signal = Arc::new(StopSignal::default());
+        {
+            let mut running = self
+                .running
+                .lock()
+                .map_err(|_| "Download queue is unavailable")?;
+            if running.contains_key(&id) {
+                return Err("Download is already running".into());
+            }
+            running.insert(id, Arc::clone(&signal));
+        }
+
+        tauri::async_runtime::spawn(worker::run(self.handle.clone(), id, signal));
+        Ok(())
+    }
+
+    /// Signals the worker of download `id`, if one is running. Returns whether it was.
+    pub fn stop(&self, id: i64, status: DownloadStatus) -> bool {
+        let signal = self
+            .running
+            .lock()
+            .ok()
+            .and_then(|running| running.get(&id).cloned());
+        match signal {
+            Some(signal) => {
+                signal.request(status);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn finished(&self, id: i64) {
+        if let Ok(mut running) = self.running.lock() {
+            running.remove(&id);
+        }
+    }
+}
+
+/// Picks up the settings every few seconds. The database is opened by the frontend
+/// after startup, so downloads left queued or running by the last session are resumed
+/// once its pool shows up.
+async fn monitor(handle: AppHandle) {
+    let mut resumed = false;
+    loop {
+        tokio::time::sleep(SETTINGS_INTERVAL).await;
+
+        let Ok(pool) = db::pool(&handle).await else {
+            continue;
+        };
+        let manager = handle.state::<DownloadManager>();
+        if let Err(e) = manager.configure(&pool).await {
+            log::warn!("{}", e);
+            continue;
+        }
+
+        if !resumed {
+            resumed = true;
+            if let Err(e) = resume_pending(&manager, &pool).await {
+                log::warn!("{}", e);
+            }
+        }
+    }
+}
+
+async fn resume_pending(manager: &DownloadManager, pool: &Pool<Sqlite>) -> Result<(), String> {
+    let ids: Vec<i64> = sqlx::query_scalar(
+        "SELECT id FROM downloads WHERE status IN ('queued', 'downloading') ORDER BY id",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to read pending downloads: {}", e))?;
+
+    for id in ids {
+        if let Err(e) = manager.spawn(id) {
+            log::warn!("Failed to resume download {}: {}", id, e);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,21 @@
+use tauri::Manager;
+
+use crate::downloads::{self, Download, DownloadManager, DownloadStatus};
+use crate::utils::db;
+
+/// Pauses a queued or running download. The partial file is kept for resuming.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn pause_download(handle: tauri::AppHandle, id: i64) -> Result<Download, String> {
+    let pool = db::pool(&handle).await?;
+    let download = downloads::load_download(&pool, id).await?;
+    if !download.status.is_active() {
+        return Err("Only queued or running downloads can be paused".into());
+    }
+
+    downloads::set_status(&pool, id, DownloadStatus::Paused, None).await?;
+    handle
+        .state::<DownloadManager>()
+        .stop(id, DownloadStatus::Paused);
+
+    downloads::load_download(&pool, id).await
+}
@@ -0,0 +1,26 @@
+use std::path::Path;
+
+use crate::downloads::{self, DownloadStatus};
+use crate::utils::db;
+
+/// Removes a finished, failed or paused download from the list. The downloaded file
+/// stays; a partial one is deleted.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn remove_download(handle: tauri::AppHandle, id: i64) -> Result<(), String> {
+    let pool = db::pool(&handle).await?;
+    let download = downloads::load_download(&pool, id).await?;
+    if download.status.is_active() {
+        return Err("Pause or cancel the download first".into());
+    }
+
+    sqlx::query("DELETE FROM downloads WHERE id = ?")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|e| format!("Failed to remove download: {}", e))?;
+
+    if download.status != DownloadStatus::Completed {
+        let _ = std::fs::remove_file(downloads::part_path(Path::new(&download.destination)));
+    }
+    Ok(())
+}
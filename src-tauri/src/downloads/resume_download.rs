@@ -0,0 +1,25 @@
+use tauri::Manager;
+
+use crate::downloads::{self, Download, DownloadManager, DownloadStatus};
+use crate::utils::db;
+
+/// Queues a paused or failed download again. It continues after the bytes already
+/// downloaded when the server supports range requests.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn resume_download(handle: tauri::AppHandle, id: i64) -> Result<Download, String> {
+    let pool = db::pool(&handle).await?;
+    let download = downloads::load_download(&pool, id).await?;
+    if !matches!(
+        download.status,
+        DownloadStatus::Paused | DownloadStatus::Failed
+    ) {
+        return Err("Only paused or failed downloads can be resumed".into());
+    }
+
+    downloads::set_status(&pool, id, DownloadStatus::Queued, None).await?;
+    let manager = handle.state::<DownloadManager>();
+    manager.configure(&pool).await?;
+    manager.spawn(id)?;
+
+    downloads::load_download(&pool, id).await
+}
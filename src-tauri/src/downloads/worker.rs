@@ -0,0 +1,360 @@
+use reqwest::StatusCode;
+use reqwest::header::{CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE};
+use sqlx::{Pool, Sqlite};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::fs::{self, OpenOptions};
+use tokio::io::AsyncWriteExt;
+
+use crate::downloads::checksum::Checksum;
+use crate::downloads::{
+    self, Download, DownloadManager, DownloadProgress, DownloadStatus, StopSignal,
+};
+use crate::files::file_details::get_file_details;
+use crate::files::transfer;
+use crate::library::index;
+use crate::utils::db;
+
+const PROGRESS_EVENT: &str = "download-progress";
+const FINISHED_EVENT: &str = "download-finished";
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+/// How often `downloaded_bytes` is written back while downloading.
+const SAVE_INTERVAL: Duration = Duration::from_secs(2);
+
+enum Outcome {
+    Finished,
+    Stopped,
+}
+
+pub async fn run(handle: AppHandle, id: i64, signal: Arc<StopSignal>) {
+    let manager = handle.state::<DownloadManager>();
+    let slot = Arc::clone(&manager.slots).acquire_owned().await;
+
+    if slot.is_ok()
+        && signal.requested().is_none()
+        && let Err(e) = download(&handle, &manager, id, &signal).await
+    {
+        log::warn!("Download {} failed: {}", id, e);
+    }
+
+    manager.finished(id);
+}
+
+async fn download(
+    handle: &AppHandle,
+    manager: &DownloadManager,
+    id: i64,
+    signal: &StopSignal,
+) -> Result<(), String> {
+    let pool = db::pool(handle).await?;
+    let download = downloads::load_download(&pool, id).await?;
+    if !download.status.is_active() {
+        return Ok(());
+    }
+
+    // A pause or cancel that came in since the row was read wins.
+    let started = sqlx::query(
+        "UPDATE downloads SET status = 'downloading', error = NULL, updated_at = CURRENT_TIMESTAMP WHERE id = ? AND status IN ('queued', 'downloading')",
+    )
+    .bind(id)
+    .execute(&pool)
+    .await
+    .map_err(|e| format!("Failed to update download: {}", e))?;
+    if started.rows_affected() == 0 {
+        return Ok(());
+    }
+    emit_progress(handle, &download, DownloadStatus::Downloading, 0);
+
+    let failure = match transfer_body(handle, manager, &pool, &download, signal).await {
+        Ok(Outcome::Finished) => complete(&pool, &download).await.err(),
+        Ok(Outcome::Stopped) => {
+            if signal.requested() == Some(DownloadStatus::Cancelled) {
+                let part = downloads::part_path(Path::new(&download.destination));
+                let _ = fs::remove_file(part).await;
+            }
+            None
+        }
+        Err(e) => Some(e),
+    };
+    if let Some(e) = failure {
+        downloads::set_status(&pool, id, DownloadStatus::Failed, Some(&e)).await?;
+    }
+
+    let finished = downloads::load_download(&pool, id).await?;
+    let _ = handle.emit(FINISHED_EVENT, &finished);
+    Ok(())
+}
+
+/// Streams the response into the `.part` file, continuing after the bytes already there
+/// when the server honours the range. Without a validator from the first response the
+/// file could have changed in between, so the download starts over instead.
+async fn transfer_body(
+    handle: &AppHandle,
+    manager: &DownloadManager,
+    pool: &Pool<Sqlite>,
+    download: &Download,
+    signal: &StopSignal,
+) -> Result<Outcome, String> {
+    let part = downloads::part_path(Path::new(&download.destination));
+    let offset = fs::metadata(&part)
+        .await
+        .map(|meta| meta.len())
+        .unwrap_or(0);
+
+    let mut request = manager.client.get(&download.url);
+    if offset > 0
+        && let Some(validator) = stored_validator(pool, download.id).await
+    {
+        // When the validator no longer matches, the server sends the whole file again.
+        request = request
+            .header(RANGE, format!("bytes={}-", offset))
+            .header(IF_RANGE, validator);
+    }
+
+    let mut response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to download: {}", e))?;
+
+    let status = response.status();
+    if status == StatusCode::RANGE_NOT_SATISFIABLE
+        && offset > 0
+        && download.total_bytes == Some(offset as i64)
+    {
+        return Ok(Outcome::Finished);
+    }
+    if !status.is_success() {
+        return Err(format!("Failed to download: HTTP {}", status));
+    }
+
+    let resumed = status == StatusCode::PARTIAL_CONTENT;
+    let mut downloaded = if resumed { offset } else { 0 };
+    let total = if resumed {
+        range_total(&response).or(response.content_length().map(|length| offset + length))
+    } else {
+        response.content_length()
+    };
+    // Weak validators cannot be used with If-Range.
+    let etag = response
+        .headers()
+        .get(ETAG)
+        .and_then(|value| value.to_str().ok())
+        .filter(|etag| !etag.starts_with("W/"))
+        .map(str::to_string);
+    let last_modified = response
+        .headers()
+        .get(LAST_MODIFIED)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+
+    sqlx::query(
+        "UPDATE downloads SET total_bytes = ?, downloaded_bytes = ?, etag = ?, last_modified = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+    )
+    .bind(total.map(|total| total as i64))
+    .bind(downloaded as i64)
+    .bind(&etag)
+    .bind(&last_modified)
+    .bind(download.id)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to update download: {}", e))?;
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(&part)
+        .await
+        .map_err(|e| format!("Failed to open {}: {}", part.display(), e))?;
+
+    let mut progress = ProgressState {
+        download: Download {
+            total_bytes: total.map(|total| total as i64),
+            ..download.clone()
+        },
+        last_emit: Instant::now(),
+        last_save: Instant::now(),
+        bytes_since_emit: 0,
+    };
+
+    let outcome = loop {
+        if signal.requested().is_some() {
+            break Outcome::Stopped;
+        }
+
+        let chunk = response
+            .chunk()
+            .await
+            .map_err(|e| format!("Failed to download: {}", e));
+        let chunk = match chunk {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => break Outcome::Finished,
+            Err(e) => {
+                let _ = file.flush().await;
+                save_progress(pool, download.id, downloaded).await;
+                return Err(e);
+            }
+        };
+
+        manager.limiter.acquire(chunk.len() as u64).await;
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| format!("Failed to write {}: {}", part.display(), e))?;
+
+        downloaded += chunk.len() as u64;
+        progress
+            .advance(handle, pool, downloaded, chunk.len() as u64)
+            .await;
+    };
+
+    file.flush()
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", part.display(), e))?;
+    save_progress(pool, download.id, downloaded).await;
+
+    if let (Outcome::Finished, Some(total)) = (&outcome, total)
+        && downloaded < total
+    {
+        return Err("Connection closed before the download finished".into());
+    }
+    Ok(outcome)
+}
+
+struct ProgressState {
+    download: Download,
+    last_emit: Instant,
+    last_save: Instant,
+    bytes_since_emit: u64,
+}
+
+impl ProgressState {
+    async fn advance(
+        &mut self,
+        handle: &AppHandle,
+        pool: &Pool<Sqlite>,
+        downloaded: u64,
+        bytes: u64,
+    ) {
+        self.bytes_since_emit += bytes;
+        self.download.downloaded_bytes = downloaded as i64;
+
+        let elapsed = self.last_emit.elapsed();
+        if elapsed >= PROGRESS_INTERVAL {
+            let speed = (self.bytes_since_emit as f64 / elapsed.as_secs_f64()) as u64;
+            emit_progress(handle, &self.download, DownloadStatus::Downloading, speed);
+            self.last_emit = Instant::now();
+            self.bytes_since_emit = 0;
+        }
+        if self.last_save.elapsed() >= SAVE_INTERVAL {
+            save_progress(pool, self.download.id, downloaded).await;
+            self.last_save = Instant::now();
+        }
+    }
+}
+
+/// Verifies the checksum, moves the `.part` file into place and indexes it with its
+/// source URL.
+async fn complete(pool: &Pool<Sqlite>, download: &Download) -> Result<(), String> {
+    let destination = PathBuf::from(&download.destination);
+    let part = downloads::part_path(&destination);
+
+    if let Some(checksum) = &download.checksum {
+        let checksum = Checksum::parse(checksum)?;
+        let path = part.clone();
+        let verified = tauri::async_runtime::spawn_blocking(move || checksum.verify(&path))
+            .await
+            .map_err(|e| format!("Failed to verify download: {}", e))?;
+        if let Err(e) = verified {
+            // Resuming would only append to the same bad data.
+            let _ = fs::remove_file(&part).await;
+            save_progress(pool, download.id, 0).await;
+            return Err(e);
+        }
+    }
+
+    let destination = transfer::available_name(&destination, transfer::is_taken);
+    fs::rename(&part, &destination)
+        .await
+        .map_err(|e| format!("Failed to move download into place: {}", e))?;
+
+    let file = get_file_details(destination.to_string_lossy().to_string())?;
+    let file_id = index::upsert_file(pool, &file).await?;
+    sqlx::query("UPDATE files SET download_url = ? WHERE id = ?")
+        .bind(&download.url)
+        .bind(file_id)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to record download URL: {}", e))?;
+
+    sqlx::query(
+        r#"
+        UPDATE downloads
+        SET status = 'completed', destination = ?, downloaded_bytes = ?, file_id = ?, error = NULL,
+            updated_at = CURRENT_TIMESTAMP
+        WHERE id = ?
+        "#,
+    )
+    .bind(&file.path)
+    .bind(file.size as i64)
+    .bind(file_id)
+    .bind(download.id)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to update download: {}", e))?;
+
+    Ok(())
+}
+
+/// The strong ETag of the download, or its `Last-Modified` date when the server sent
+/// no ETag. Either works as the `If-Range` of a resumed request.
+async fn stored_validator(pool: &Pool<Sqlite>, id: i64) -> Option<String> {
+    sqlx::query_scalar("SELECT COALESCE(etag, last_modified) FROM downloads WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .ok()
+        .flatten()
+        .flatten()
+}
+
+/// The full size from `Content-Range: bytes 100-999/1000`.
+fn range_total(response: &reqwest::Response) -> Option<u64> {
+    response
+        .headers()
+        .get(CONTENT_RANGE)?
+        .to_str()
+        .ok()?
+        .rsplit_once('/')?
+        .1
+        .parse()
+        .ok()
+}
+
+async fn save_progress(pool: &Pool<Sqlite>, id: i64, downloaded: u64) {
+    let saved = sqlx::query(
+        "UPDATE downloads SET downloaded_bytes = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+    )
+    .bind(downloaded as i64)
+    .bind(id)
+    .execute(pool)
+    .await;
+    if let Err(e) = saved {
+        log::warn!("Failed to save download progress: {}", e);
+    }
+}
+
+fn emit_progress(handle: &AppHandle, download: &Download, status: DownloadStatus, speed: u64) {
+    let _ = handle.emit(
+        PROGRESS_EVENT,
+        &DownloadProgress {
+            id: download.id,
+            status,
+            downloaded_bytes: download.downloaded_bytes,
+            total_bytes: download.total_bytes,
+            bytes_per_second: speed,
+        },
+    );
+}
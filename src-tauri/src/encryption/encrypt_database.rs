@@ -0,0 +1,16 @@
+use crate::encryption;
+use crate::utils::db;
+
+/// Encrypts the plaintext database in place with a key derived from `password` by
+/// SQLCipher, and marks it as encrypted in the settings.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn encrypt_database(handle: tauri::AppHandle, password: String) -> Result<(), String> {
+    encryption::validate_key(&password)?;
+
+    let path = db::file_path(&handle)?;
+    if encryption::is_encrypted(&path)? {
+        return Err("Database is already encrypted".into());
+    }
+
+    encryption::reencrypt(&handle, Some(&password)).await
+}
@@ -0,0 +1,24 @@
+use serde::Serialize;
+
+use crate::encryption;
+use crate::utils::db;
+
+#[derive(Serialize)]
+pub struct DatabaseStatus {
+    pub encrypted: bool,
+    /// Whether the passphrase has been given in this session.
+    pub unlocked: bool,
+}
+
+/// Tells the frontend whether it must ask for the database password before loading
+/// the database. Read from the file itself, since the settings row is inside it.
+#[tauri::command(rename_all = "snake_case")]
+pub fn get_database_status(handle: tauri::AppHandle) -> Result<DatabaseStatus, String> {
+    let path = db::file_path(&handle)?;
+    let encrypted = encryption::is_encrypted(&path)?;
+
+    Ok(DatabaseStatus {
+        encrypted,
+        unlocked: !encrypted || encryption::current_key().is_some(),
+    })
+}
@@ -0,0 +1,253 @@
+use libsqlite3_sys::{
+    SQLITE_OK, sqlite3, sqlite3_api_routines, sqlite3_auto_extension, sqlite3_db_filename,
+    sqlite3_exec,
+};
+use sqlx::Connection;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteConnection, SqlitePool};
+use std::ffi::{CStr, CString, c_char, c_int};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, Once};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_sql::{DbInstances, DbPool};
+
+use crate::utils::db;
+
+pub mod encrypt_database;
+pub mod get_database_status;
+pub mod rekey_database;
+pub mod unlock_database;
+
+/// Stored in `settings.db_encryption_method` for databases encrypted by this module.
+pub const ENCRYPTION_METHOD: &str = "sqlcipher4";
+
+pub const MIN_KEY_LENGTH: usize = 8;
+
+/// Every plaintext SQLite file starts with this. SQLCipher encrypts the whole file,
+/// header included, so its absence marks an encrypted database.
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// The passphrase of the open database. Kept for the lifetime of the process, so every
+/// connection the SQL plugin opens can be keyed.
+static KEY: Mutex<Option<String>> = Mutex::new(None);
+static KEY_HOOK: Once = Once::new();
+
+/// Registers a SQLite auto-extension that keys each new connection to the app database
+/// with the current passphrase. The SQL plugin opens its pool from a plain URL, which
+/// has no way to carry `PRAGMA key`, and that pragma must run before anything else.
+pub fn register_key_hook() {
+    KEY_HOOK.call_once(|| {
+        let result = unsafe { sqlite3_auto_extension(Some(apply_key)) };
+        if result != SQLITE_OK {
+            log::error!("Failed to register database key hook: {}", result);
+        }
+    });
+}
+
+unsafe extern "C" fn apply_key(
+    connection: *mut sqlite3,
+    _error: *mut *mut c_char,
+    _api: *const sqlite3_api_routines,
+) -> c_int {
+    let Some(key) = current_key() else {
+        return SQLITE_OK;
+    };
+
+    let filename = unsafe { sqlite3_db_filename(connection, c"main".as_ptr()) };
+    if filename.is_null() {
+        return SQLITE_OK;
+    }
+    let filename = unsafe { CStr::from_ptr(filename) }.to_string_lossy();
+    if !is_app_database(Path::new(filename.as_ref())) {
+        return SQLITE_OK;
+    }
+
+    let Ok(pragma) = CString::new(format!("PRAGMA key = {};", quote(&key))) else {
+        return SQLITE_OK;
+    };
+    unsafe {
+        sqlite3_exec(
+            connection,
+            pragma.as_ptr(),
+            None,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    }
+}
+
+fn is_app_database(path: &Path) -> bool {
+    db::DB_URL
+        .split_once(':')
+        .is_some_and(|(_, name)| path.ends_with(name))
+}
+
+pub fn current_key() -> Option<String> {
+    KEY.lock().ok().and_then(|key| key.clone())
+}
+
+fn set_key(key: Option<String>) {
+    if let Ok(mut current) = KEY.lock() {
+        *current = key;
+    }
+}
+
+/// Quotes `value` as a SQL string literal.
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+pub fn validate_key(key: &str) -> Result<(), String> {
+    if key.chars().count() < MIN_KEY_LENGTH {
+        return Err(format!(
+            "Password must be at least {} characters",
+            MIN_KEY_LENGTH
+        ));
+    }
+    if key.contains('\0') {
+        return Err("Password cannot contain NUL characters".into());
+    }
+    Ok(())
+}
+
+/// Whether the file at `path` is encrypted. A missing or empty file is not.
+pub fn is_encrypted(path: &Path) -> Result<bool, String> {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(format!("Failed to open database: {}", e)),
+    };
+
+    let mut header = [0; 16];
+    match file.read_exact(&mut header) {
+        Ok(()) => Ok(&header != SQLITE_HEADER),
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(e) => Err(format!("Failed to read database: {}", e)),
+    }
+}
+
+/// Opens a single connection to `path`, keyed with `key` when given, and reads the
+/// schema so a wrong key fails here instead of on the first query.
+async fn open_keyed(path: &Path, key: Option<&str>) -> Result<SqliteConnection, String> {
+    let mut options = SqliteConnectOptions::new().filename(path);
+    if let Some(key) = key {
+        options = options.pragma("key", quote(key));
+    }
+
+    let mut connection = SqliteConnection::connect_with(&options)
+        .await
+        .map_err(|e| format!("Failed to open database: {}", e))?;
+
+    sqlx::query("SELECT count(*) FROM sqlite_master")
+        .execute(&mut connection)
+        .await
+        .map_err(|_| "Incorrect database password".to_string())?;
+
+    Ok(connection)
+}
+
+/// Checks `key` against the database file and, when it opens it, keeps it for the
+/// connections the SQL plugin opens next.
+pub async fn unlock(path: &Path, key: &str) -> Result<(), String> {
+    let connection = open_keyed(path, Some(key)).await?;
+    let _ = connection.close().await;
+    set_key(Some(key.to_string()));
+    Ok(())
+}
+
+/// Rewrites the database under `to`, or as plaintext when `to` is `None`. The SQL
+/// plugin's pool is closed while the file is swapped and reopened afterwards, so the
+/// frontend keeps using the same database name. Queries issued meanwhile wait.
+pub async fn reencrypt(handle: &AppHandle, to: Option<&str>) -> Result<(), String> {
+    let path = db::file_path(handle)?;
+    let instances = handle
+        .try_state::<DbInstances>()
+        .ok_or("Database plugin is not initialized")?;
+    let mut instances = instances.0.write().await;
+
+    if let Some(DbPool::Sqlite(pool)) = instances.remove(db::DB_URL) {
+        pool.close().await;
+    }
+
+    let staged = staging_path(&path);
+    let result = match export(&path, &staged, to).await {
+        Ok(()) => replace(&staged, &path),
+        Err(e) => Err(e),
+    };
+    if result.is_ok() {
+        set_key(to.map(str::to_string));
+    } else {
+        let _ = fs::remove_file(&staged);
+    }
+
+    // Reopened with whichever key now matches the file, keyed by the hook.
+    let pool = SqlitePool::connect_with(SqliteConnectOptions::new().filename(&path))
+        .await
+        .map_err(|e| format!("Failed to reopen database: {}", e))?;
+    instances.insert(db::DB_URL.to_string(), DbPool::Sqlite(pool));
+
+    result
+}
+
+fn staging_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".rekey");
+    path.with_file_name(name)
+}
+
+/// Copies every table of the database at `path` into a new file at `staged` with
+/// `sqlcipher_export`, and records the new state in its settings row.
+async fn export(path: &Path, staged: &Path, to: Option<&str>) -> Result<(), String> {
+    // ATTACH opens with the flags of the main connection, which cannot create files.
+    File::create(staged).map_err(|e| format!("Failed to create database copy: {}", e))?;
+
+    // The hook keys this connection with the current passphrase, if any.
+    let mut connection = open_keyed(path, None).await?;
+
+    let exported = async {
+        sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
+            .execute(&mut connection)
+            .await?;
+        sqlx::query("ATTACH DATABASE ? AS target KEY ?")
+            .bind(staged.to_string_lossy().to_string())
+            .bind(to.unwrap_or(""))
+            .execute(&mut connection)
+            .await?;
+        sqlx::query("SELECT sqlcipher_export('target')")
+            .execute(&mut connection)
+            .await?;
+        sqlx::query(
+            "UPDATE target.settings SET is_db_encrypted = ?, db_encryption_method = ?, updated_at = CURRENT_TIMESTAMP",
+        )
+        .bind(to.is_some())
+        .bind(to.map(|_| ENCRYPTION_METHOD))
+        .execute(&mut connection)
+        .await?;
+        sqlx::query("DETACH DATABASE target")
+            .execute(&mut connection)
+            .await
+    }
+    .await;
+
+    let _ = connection.close().await;
+    exported
+        .map(|_| ())
+        .map_err(|e| format!("Failed to export database: {}", e))
+}
+
+/// Moves the exported copy over the database. The old write-ahead log is dropped
+/// first, since it was checkpointed and would not match the new file.
+fn replace(staged: &Path, path: &Path) -> Result<(), String> {
+    for suffix in ["-wal", "-shm"] {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(suffix);
+        let sidecar = path.with_file_name(name);
+        if sidecar.exists() {
+            fs::remove_file(&sidecar)
+                .map_err(|e| format!("Failed to remove {}: {}", sidecar.display(), e))?;
+        }
+    }
+
+    fs::rename(staged, path).map_err(|e| format!("Failed to replace database: {}", e))
+}
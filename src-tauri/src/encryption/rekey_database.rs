@@ -0,0 +1,29 @@
+use crate::encryption;
+use crate::security::constant_time_eq;
+use crate::utils::db;
+
+/// Re-encrypts the database under `new_password`, or decrypts it back to plaintext
+/// when `new_password` is empty. Requires the current password.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn rekey_database(
+    handle: tauri::AppHandle,
+    current_password: String,
+    new_password: Option<String>,
+) -> Result<(), String> {
+    let path = db::file_path(&handle)?;
+    if !encryption::is_encrypted(&path)? {
+        return Err("Database is not encrypted".into());
+    }
+
+    let key = encryption::current_key().ok_or("Database is locked")?;
+    if !constant_time_eq(key.as_bytes(), current_password.as_bytes()) {
+        return Err("Current password is incorrect".into());
+    }
+
+    let new_password = new_password.filter(|password| !password.is_empty());
+    if let Some(password) = &new_password {
+        encryption::validate_key(password)?;
+    }
+
+    encryption::reencrypt(&handle, new_password.as_deref()).await
+}
@@ -0,0 +1,32 @@
+use tauri::Manager;
+
+use crate::encryption;
+use crate::security::{LockManager, constant_time_eq};
+use crate::utils::db;
+
+/// Checks the database password at startup. Must succeed before the frontend loads the
+/// database, since migrations cannot read an encrypted file without the key. Repeated
+/// failures are refused for a growing amount of time, as with the lock password.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn unlock_database(handle: tauri::AppHandle, password: String) -> Result<(), String> {
+    let path = db::file_path(&handle)?;
+    if !encryption::is_encrypted(&path)? {
+        return Ok(());
+    }
+
+    let manager = handle.state::<LockManager>();
+    let attempts = manager.database_attempts();
+    let _attempt = attempts.begin().await?;
+
+    let result = match encryption::current_key() {
+        Some(key) if constant_time_eq(key.as_bytes(), password.as_bytes()) => Ok(()),
+        Some(_) => Err("Incorrect database password".into()),
+        None => encryption::unlock(&path, &password).await,
+    };
+
+    match result {
+        Ok(()) => attempts.record_success(),
+        Err(_) => attempts.record_failure(),
+    }
+    result
+}
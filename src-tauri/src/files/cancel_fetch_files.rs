@@ -0,0 +1,13 @@
+use tauri::Manager;
+
+use crate::utils::cancellation::CancelRegistry;
+
+/// Stops a `fetch_files` listing, e.g. when the user navigates away. Returns whether a
+/// listing with that id was still running.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn cancel_fetch_files(
+    handle: tauri::AppHandle,
+    fetch_id: String,
+) -> Result<bool, String> {
+    Ok(handle.state::<CancelRegistry>().cancel(&fetch_id))
+}
@@ -0,0 +1,12 @@
+use tauri::Manager;
+
+use crate::utils::cancellation::CancelRegistry;
+
+/// Stops a `search_files` walk. Returns whether a search with that id was running.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn cancel_search_files(
+    handle: tauri::AppHandle,
+    search_id: String,
+) -> Result<bool, String> {
+    Ok(handle.state::<CancelRegistry>().cancel(&search_id))
+}
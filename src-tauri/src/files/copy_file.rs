@@ -3,10 +3,11 @@ use std::path::Path;
 use tauri::Manager;
 use tauri::path::BaseDirectory;
 
-use crate::utils::protected_paths::PROTECTED_PATHS;
+use crate::journal::{self, OperationKind};
+use crate::utils::path_policy::{Operation, PathPolicy};
 
 #[tauri::command(rename_all = "snake_case")]
-pub fn copy_file(
+pub async fn copy_file(
     handle: tauri::AppHandle,
     file_path: String,
     output_folder: String,
@@ -17,15 +18,8 @@ pub fn copy_file(
         return Err("Copy File: Invalid file path".into());
     }
 
-    let canonical = file_path
-        .canonicalize()
-        .map_err(|_| "Copy File: Invalid file path")?;
-    for protected in PROTECTED_PATHS {
-        let protected_path = Path::new(protected);
-        if canonical.starts_with(protected_path) {
-            return Err("Refusing to copy a protected system path.".into());
-        }
-    }
+    let policy = PathPolicy::load(&handle).await;
+    let canonical = policy.check(file_path, Operation::Copy)?;
 
     let allowed_dir = handle
         .path()
@@ -37,28 +31,28 @@ pub fn copy_file(
         return Err("Output path must be absolute".into());
     }
 
-    let output_path_abs = output_path
-        .canonicalize()
-        .unwrap_or_else(|_| output_path.clone());
+    let output_path_abs = policy.check(&output_path, Operation::Write)?;
 
-    for protected in PROTECTED_PATHS {
-        let protected_path = Path::new(protected);
-        if output_path_abs.starts_with(protected_path) {
-            return Err("Refusing to copy to a protected system path.".into());
+    if let Some(parent) = output_path_abs.parent()
+        && !parent.exists()
+    {
+        if !parent.starts_with(&allowed_dir) {
+            return Err("Output path does not exist and is not inside allowed directory".into());
         }
+        fs::create_dir_all(parent).map_err(|_| "Failed to create output directory")?;
     }
 
-    if let Some(parent) = output_path_abs.parent() {
-        if !parent.exists() {
-            if !parent.starts_with(&allowed_dir) {
-                return Err(
-                    "Output path does not exist and is not inside allowed directory".into(),
-                );
-            }
-            fs::create_dir_all(parent).map_err(|_| "Failed to create output directory")?;
-        }
-    }
+    fs::copy(&canonical, &output_path_abs).map_err(|_| "Failed to copy file")?;
+
+    let output = output_path_abs.to_string_lossy().to_string();
+    journal::log_operation(
+        &handle,
+        &journal::new_batch_id(),
+        OperationKind::Copy,
+        &canonical.to_string_lossy(),
+        Some(&output),
+    )
+    .await;
 
-    fs::copy(file_path, &output_path_abs).map_err(|_| "Failed to copy file")?;
-    Ok(output_path_abs.to_string_lossy().to_string())
+    Ok(output)
 }
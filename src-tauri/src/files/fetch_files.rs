@@ -1,41 +1,244 @@
+use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::UNIX_EPOCH;
+use tauri::Manager;
+use tauri::ipc::Channel;
 
 use crate::files::IFile;
-use crate::files::file_details::file_details;
+use crate::files::file_details::get_file_details;
+use crate::utils::cancellation::CancelRegistry;
+use crate::utils::natural_sort::natural_cmp;
+use crate::utils::path_policy::{Operation, PathPolicy};
 
+const DEFAULT_PAGE_SIZE: usize = 200;
+
+#[derive(Deserialize, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SortKey {
+    #[default]
+    Name,
+    Size,
+    Mtime,
+    Type,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct FetchOptions {
+    /// Extensions to keep, with or without the leading dot. Empty keeps everything.
+    pub extensions: Vec<String>,
+    pub sort: SortKey,
+    pub descending: bool,
+    pub page_size: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub struct SkippedEntry {
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Serialize)]
+pub struct FetchPage {
+    pub files: Vec<IFile>,
+    pub skipped: Vec<SkippedEntry>,
+    /// Set on the last page, which may be empty.
+    pub done: bool,
+}
+
+#[derive(Serialize)]
+pub struct FetchSummary {
+    pub total: usize,
+    pub skipped: usize,
+    pub cancelled: bool,
+}
+
+struct Entry {
+    path: PathBuf,
+    name: String,
+    extension: String,
+    size: u64,
+    mtime: u64,
+}
+
+/// Lists a folder and streams the sorted entries through `on_page`, so large folders
+/// render while their details are still being read. Entries that cannot be read are
+/// reported in the pages instead of failing the listing. `cancel_fetch_files` with the
+/// same `fetch_id` stops the listing after the current page.
 #[tauri::command(rename_all = "snake_case")]
-pub fn fetch_files(
+pub async fn fetch_files(
+    handle: tauri::AppHandle,
+    fetch_id: String,
     folder_path: String,
-    extension_filter: Option<String>,
-) -> Result<Vec<IFile>, String> {
+    options: Option<FetchOptions>,
+    on_page: Channel<FetchPage>,
+) -> Result<FetchSummary, String> {
     let path = Path::new(&folder_path);
     if !path.exists() || !path.is_dir() {
         return Err("Invalid folder path".into());
     }
 
-    let mut files = Vec::new();
-    for entry in fs::read_dir(path).map_err(|e| e.to_string())? {
-        let entry = entry.map_err(|e| e.to_string())?;
-
-        let filename = entry
-            .file_name()
-            .into_string()
-            .map_err(|e| format!("Could not parse filename: {:?}", e))?;
-        if let Some(ref ext_filter) = extension_filter {
-            if !filename
-                .to_lowercase()
-                .ends_with(&ext_filter.to_lowercase())
-            {
+    let policy = PathPolicy::load(&handle).await;
+    policy.check(path, Operation::Read)?;
+
+    let registry = handle.state::<CancelRegistry>();
+    let cancelled = registry.register(&fetch_id);
+
+    let folder = path.to_path_buf();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        list(
+            &folder,
+            &options.unwrap_or_default(),
+            &policy,
+            &cancelled,
+            &on_page,
+        )
+    })
+    .await
+    .map_err(|e| format!("Failed to list folder: {}", e));
+
+    registry.remove(&fetch_id);
+    result?
+}
+
+fn list(
+    folder: &Path,
+    options: &FetchOptions,
+    policy: &PathPolicy,
+    cancelled: &AtomicBool,
+    on_page: &Channel<FetchPage>,
+) -> Result<FetchSummary, String> {
+    let extensions: Vec<String> = options
+        .extensions
+        .iter()
+        .map(|ext| ext.trim().trim_start_matches('.').to_lowercase())
+        .filter(|ext| !ext.is_empty())
+        .map(|ext| format!(".{}", ext))
+        .collect();
+
+    let mut entries = Vec::new();
+    let mut skipped = Vec::new();
+
+    for entry in fs::read_dir(folder).map_err(|e| format!("Failed to read folder: {}", e))? {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                skipped.push(SkippedEntry {
+                    path: folder.to_string_lossy().to_string(),
+                    error: e.to_string(),
+                });
                 continue;
             }
+        };
+
+        let path = entry.path();
+        let Ok(name) = entry.file_name().into_string() else {
+            skipped.push(SkippedEntry {
+                path: path.to_string_lossy().to_string(),
+                error: "Name is not valid UTF-8".into(),
+            });
+            continue;
+        };
+
+        let lowercase = name.to_lowercase();
+        if !extensions.is_empty() && !extensions.iter().any(|ext| lowercase.ends_with(ext)) {
+            continue;
+        }
+        if policy.check(&path, Operation::Read).is_err() {
+            continue;
         }
 
-        let entry_details =
-            file_details(entry.path().to_string_lossy().to_string()).map_err(|e| e.to_string())?;
+        let metadata = entry.metadata().ok();
+        entries.push(Entry {
+            extension: Path::new(&lowercase)
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            size: metadata.as_ref().map(|m| m.len()).unwrap_or(0),
+            mtime: metadata
+                .and_then(|m| m.modified().ok())
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|elapsed| elapsed.as_secs())
+                .unwrap_or(0),
+            path,
+            name,
+        });
+    }
+
+    sort(&mut entries, options.sort, options.descending);
 
-        files.push(entry_details);
+    let page_size = options.page_size.unwrap_or(DEFAULT_PAGE_SIZE).max(1);
+    let mut summary = FetchSummary {
+        total: 0,
+        skipped: skipped.len(),
+        cancelled: false,
+    };
+
+    for chunk in entries.chunks(page_size) {
+        if cancelled.load(Ordering::Relaxed) {
+            summary.cancelled = true;
+            break;
+        }
+
+        let mut files = Vec::with_capacity(chunk.len());
+        for entry in chunk {
+            match get_file_details(entry.path.to_string_lossy().to_string()) {
+                Ok(file) => files.push(file),
+                Err(e) => {
+                    summary.skipped += 1;
+                    skipped.push(SkippedEntry {
+                        path: entry.path.to_string_lossy().to_string(),
+                        error: e,
+                    });
+                }
+            }
+        }
+
+        summary.total += files.len();
+        send(
+            on_page,
+            FetchPage {
+                files,
+                skipped: std::mem::take(&mut skipped),
+                done: false,
+            },
+        )?;
     }
 
-    Ok(files)
+    send(
+        on_page,
+        FetchPage {
+            files: Vec::new(),
+            skipped,
+            done: true,
+        },
+    )?;
+
+    Ok(summary)
+}
+
+fn sort(entries: &mut [Entry], key: SortKey, descending: bool) {
+    entries.sort_by(|a, b| {
+        let ordering = match key {
+            SortKey::Name => natural_cmp(&a.name, &b.name),
+            SortKey::Size => a.size.cmp(&b.size),
+            SortKey::Mtime => a.mtime.cmp(&b.mtime),
+            SortKey::Type => a.extension.cmp(&b.extension),
+        }
+        .then_with(|| natural_cmp(&a.name, &b.name));
+
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+}
+
+fn send(on_page: &Channel<FetchPage>, page: FetchPage) -> Result<(), String> {
+    on_page
+        .send(page)
+        .map_err(|e| format!("Failed to send files: {}", e))
 }
@@ -1,9 +1,19 @@
 use std::{fs::metadata, path::Path};
 
 use crate::files::IFile;
+use crate::files::sniff::{extensions_agree, sniff};
+use crate::utils::path_policy::{Operation, PathPolicy};
 
 #[tauri::command(rename_all = "snake_case")]
-pub fn file_details(path: String) -> Result<IFile, String> {
+pub async fn file_details(handle: tauri::AppHandle, path: String) -> Result<IFile, String> {
+    PathPolicy::load(&handle)
+        .await
+        .check(Path::new(&path), Operation::Read)?;
+
+    get_file_details(path)
+}
+
+pub fn get_file_details(path: String) -> Result<IFile, String> {
     let filepath = Path::new(&path);
     let filename = match filepath.file_name() {
         Some(name) => name.to_string_lossy().to_string(),
@@ -20,12 +30,13 @@ pub fn file_details(path: String) -> Result<IFile, String> {
         metadata(file_path_str).map_err(|e| format!("Failed to read metadata: {}", e))?;
 
     let is_directory = metadata.is_dir();
-    let (extension, mime, file_type, size) = if is_directory {
+    let (extension, mime, file_type, size, extension_mismatch) = if is_directory {
         (
             "".to_string(),
             "directory".to_string(),
             "directory".to_string(),
             0u64,
+            false,
         )
     } else {
         let ext = Path::new(&filename)
@@ -34,12 +45,28 @@ pub fn file_details(path: String) -> Result<IFile, String> {
             .unwrap_or("")
             .to_lowercase()
             .to_owned();
-        (
-            ext.clone(),
-            get_mime_type_from_extension(&ext),
-            get_file_type_from_extension(&ext),
-            metadata.len(),
-        )
+
+        // The file header wins over the extension; text formats without a signature
+        // (txt, svg, ...) fall back to the extension.
+        match sniff(filepath) {
+            Some(sniffed) => {
+                let mismatch = !ext.is_empty() && !extensions_agree(&ext, &sniffed.extension);
+                (
+                    ext,
+                    sniffed.mime,
+                    sniffed.file_type,
+                    metadata.len(),
+                    mismatch,
+                )
+            }
+            None => (
+                ext.clone(),
+                get_mime_type_from_extension(&ext),
+                get_file_type_from_extension(&ext),
+                metadata.len(),
+                false,
+            ),
+        }
     };
 
     let ctime = metadata
@@ -76,12 +103,13 @@ pub fn file_details(path: String) -> Result<IFile, String> {
         scrape_url: None,
         download_url: None,
         is_directory: if is_directory { 1 } else { 0 },
+        extension_mismatch: if extension_mismatch { 1 } else { 0 },
     };
 
     Ok(file_details)
 }
 
-fn get_file_type_from_extension(extension: &str) -> String {
+pub fn get_file_type_from_extension(extension: &str) -> String {
     match extension.to_lowercase().as_str() {
         "mp4" | "avi" | "mkv" | "mov" | "wmv" | "flv" | "webm" | "m4v" | "3gp" => {
             "video".to_string()
@@ -90,9 +118,8 @@ fn get_file_type_from_extension(extension: &str) -> String {
             "image".to_string()
         }
         "mp3" | "wav" | "flac" | "aac" | "ogg" | "wma" | "m4a" => "audio".to_string(),
-        "pdf" | "doc" | "docx" | "txt" | "rtf" | "odt" | "xls" | "xlsx" | "ppt" | "pptx" => {
-            "document".to_string()
-        }
+        "pdf" | "doc" | "docx" | "txt" | "md" | "markdown" | "rtf" | "odt" | "xls" | "xlsx"
+        | "ppt" | "pptx" => "document".to_string(),
         _ => "other".to_string(),
     }
 }
@@ -130,6 +157,7 @@ fn get_mime_type_from_extension(extension: &str) -> String {
             "application/vnd.openxmlformats-officedocument.wordprocessingml.document".to_string()
         }
         "txt" => "text/plain".to_string(),
+        "md" | "markdown" => "text/markdown".to_string(),
         "rtf" => "application/rtf".to_string(),
         "odt" => "application/vnd.oasis.opendocument.text".to_string(),
         "xls" => "application/vnd.ms-excel".to_string(),
@@ -1,23 +1,344 @@
-use std::{os::windows::process::CommandExt, process::Command};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct Disk {
+    pub mount_point: String,
+    pub label: Option<String>,
+    pub filesystem: String,
+    pub total_bytes: u64,
+    pub free_bytes: u64,
+    pub is_removable: bool,
+}
 
 #[tauri::command(rename_all = "snake_case")]
-pub async fn get_available_disks() -> Result<Vec<String>, String> {
-    let available_disks = get_windows_drives()?;
+pub async fn get_available_disks() -> Result<Vec<Disk>, String> {
+    let available_disks = platform_disks()?;
     Ok(available_disks)
 }
 
-pub fn get_windows_drives() -> Result<Vec<String>, String> {
-    let output = Command::new("powershell")
-        .creation_flags(0x08000000)
-        .args([
-            "-Command",
-            "Get-PSDrive -PSProvider FileSystem | Select-Object -ExpandProperty Root",
-        ])
-        .output()
-        .map_err(|e| format!("Failed to get Windows drives: {}", e))?;
-
-    Ok(String::from_utf8_lossy(&output.stdout)
-        .lines()
-        .map(|s| s.trim().to_string())
-        .collect())
+#[cfg(target_os = "windows")]
+fn platform_disks() -> Result<Vec<Disk>, String> {
+    windows::get_drives()
+}
+
+#[cfg(target_os = "linux")]
+fn platform_disks() -> Result<Vec<Disk>, String> {
+    linux::get_mounts()
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn platform_disks() -> Result<Vec<Disk>, String> {
+    Err("Disk enumeration is not supported on this platform".to_string())
+}
+
+#[cfg(target_os = "windows")]
+mod windows {
+    use serde::Deserialize;
+    use std::{os::windows::process::CommandExt, process::Command};
+
+    use super::Disk;
+
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+    const DRIVE_REMOVABLE: u32 = 2;
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct LogicalDisk {
+        #[serde(rename = "DeviceID")]
+        device_id: String,
+        volume_name: Option<String>,
+        file_system: Option<String>,
+        size: Option<u64>,
+        free_space: Option<u64>,
+        drive_type: u32,
+    }
+
+    pub fn get_drives() -> Result<Vec<Disk>, String> {
+        let output = Command::new("powershell")
+            .creation_flags(CREATE_NO_WINDOW)
+            .args([
+                "-NoProfile",
+                "-Command",
+                // Piping would unwrap a single disk into a bare object; -InputObject
+                // keeps the array.
+                "ConvertTo-Json -Compress -InputObject @(Get-CimInstance Win32_LogicalDisk | Select-Object DeviceID, VolumeName, FileSystem, Size, FreeSpace, DriveType)",
+            ])
+            .output()
+            .map_err(|e| format!("Failed to get Windows drives: {}", e))?;
+
+        if !output.status.success() {
+            return Err(format!(
+                "Failed to get Windows drives: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stdout = stdout.trim();
+        if stdout.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let logical_disks: Vec<LogicalDisk> = serde_json::from_str(stdout)
+            .map_err(|e| format!("Failed to parse Windows drives: {}", e))?;
+
+        Ok(logical_disks
+            .into_iter()
+            // Drives without a filesystem are empty card readers or optical drives.
+            .filter(|disk| disk.file_system.is_some())
+            .map(|disk| Disk {
+                mount_point: format!("{}\\", disk.device_id),
+                label: disk.volume_name.filter(|name| !name.is_empty()),
+                filesystem: disk.file_system.unwrap_or_default(),
+                total_bytes: disk.size.unwrap_or(0),
+                free_bytes: disk.free_space.unwrap_or(0),
+                is_removable: disk.drive_type == DRIVE_REMOVABLE,
+            })
+            .collect())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::collections::{HashMap, HashSet};
+    use std::ffi::CString;
+    use std::fs;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::{Path, PathBuf};
+
+    use super::Disk;
+
+    const MOUNTINFO_PATH: &str = "/proc/self/mountinfo";
+    const LABELS_DIR: &str = "/dev/disk/by-label";
+
+    const PSEUDO_FILESYSTEMS: &[&str] = &[
+        "autofs",
+        "binfmt_misc",
+        "bpf",
+        "cgroup",
+        "cgroup2",
+        "configfs",
+        "debugfs",
+        "devpts",
+        "devtmpfs",
+        "efivarfs",
+        "fusectl",
+        "hugetlbfs",
+        "mqueue",
+        "nsfs",
+        "overlay",
+        "proc",
+        "pstore",
+        "ramfs",
+        "rpc_pipefs",
+        "securityfs",
+        "selinuxfs",
+        "squashfs",
+        "sysfs",
+        "tmpfs",
+        "tracefs",
+        "fuse.gvfsd-fuse",
+        "fuse.portal",
+        "fuse.snapfuse",
+    ];
+
+    const PSEUDO_MOUNT_PREFIXES: &[&str] = &["/proc", "/sys", "/dev", "/run", "/snap", "/boot/efi"];
+
+    struct MountEntry {
+        device_id: String,
+        root: String,
+        mount_point: String,
+        filesystem: String,
+        source: String,
+    }
+
+    pub fn get_mounts() -> Result<Vec<Disk>, String> {
+        let mountinfo = fs::read_to_string(MOUNTINFO_PATH)
+            .map_err(|e| format!("Failed to read mount table: {}", e))?;
+
+        let labels = read_labels();
+        let mut seen_mount_points = HashSet::new();
+        let mut seen_subtrees = HashSet::new();
+        let mut disks = Vec::new();
+
+        for entry in mountinfo.lines().filter_map(parse_mountinfo_line) {
+            if !is_physical_mount(&entry) || !seen_mount_points.insert(entry.mount_point.clone()) {
+                continue;
+            }
+
+            // Bind mounts expose the same subtree of a filesystem that is already listed.
+            if !seen_subtrees.insert((entry.device_id.clone(), entry.root.clone())) {
+                continue;
+            }
+
+            let Some((total_bytes, free_bytes)) = filesystem_usage(&entry.mount_point) else {
+                continue;
+            };
+            if total_bytes == 0 {
+                continue;
+            }
+
+            let device = canonical_device(&entry.source);
+            disks.push(Disk {
+                label: device.as_ref().and_then(|dev| labels.get(dev).cloned()),
+                is_removable: device.as_deref().is_some_and(is_removable_device),
+                mount_point: entry.mount_point,
+                filesystem: entry.filesystem,
+                total_bytes,
+                free_bytes,
+            });
+        }
+
+        Ok(disks)
+    }
+
+    /// Parses one line of `/proc/self/mountinfo`, see proc(5) for the layout:
+    /// `id parent major:minor root mount_point options [optional...] - fstype source super_options`
+    fn parse_mountinfo_line(line: &str) -> Option<MountEntry> {
+        let (mount_fields, fs_fields) = line.split_once(" - ")?;
+
+        let mut mount_fields = mount_fields.split(' ').skip(2);
+        let device_id = mount_fields.next()?;
+        let root = mount_fields.next()?;
+        let mount_point = mount_fields.next()?;
+
+        let mut fs_fields = fs_fields.split(' ');
+        let filesystem = fs_fields.next()?;
+        let source = fs_fields.next()?;
+
+        Some(MountEntry {
+            device_id: device_id.to_string(),
+            root: unescape_octal(root),
+            mount_point: unescape_octal(mount_point),
+            filesystem: filesystem.to_string(),
+            source: unescape_octal(source),
+        })
+    }
+
+    /// The kernel escapes space, tab, newline and backslash as `\ooo` in mountinfo.
+    fn unescape_octal(field: &str) -> String {
+        let bytes = field.as_bytes();
+        let mut unescaped = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+
+        while i < bytes.len() {
+            if let Some(&[b'\\', a, b, c]) = bytes.get(i..i + 4)
+                && [a, b, c].iter().all(|digit| (b'0'..=b'7').contains(digit))
+            {
+                unescaped.push(((a - b'0') << 6) | ((b - b'0') << 3) | (c - b'0'));
+                i += 4;
+                continue;
+            }
+            unescaped.push(bytes[i]);
+            i += 1;
+        }
+
+        String::from_utf8_lossy(&unescaped).into_owned()
+    }
+
+    fn is_physical_mount(entry: &MountEntry) -> bool {
+        if PSEUDO_FILESYSTEMS.contains(&entry.filesystem.as_str()) {
+            return false;
+        }
+
+        if is_under(&entry.mount_point, "/run/media") {
+            return true;
+        }
+
+        !PSEUDO_MOUNT_PREFIXES
+            .iter()
+            .any(|prefix| is_under(&entry.mount_point, prefix))
+    }
+
+    fn is_under(path: &str, prefix: &str) -> bool {
+        Path::new(path).starts_with(prefix)
+    }
+
+    fn filesystem_usage(mount_point: &str) -> Option<(u64, u64)> {
+        let c_path = CString::new(Path::new(mount_point).as_os_str().as_bytes()).ok()?;
+        let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+
+        // SAFETY: `c_path` is a valid NUL-terminated string and `stat` is a writable statvfs.
+        if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+            return None;
+        }
+
+        let block_size = stat.f_frsize as u64;
+        Some((
+            stat.f_blocks as u64 * block_size,
+            stat.f_bavail as u64 * block_size,
+        ))
+    }
+
+    fn canonical_device(source: &str) -> Option<PathBuf> {
+        if !source.starts_with("/dev/") {
+            return None;
+        }
+        fs::canonicalize(source).ok()
+    }
+
+    fn read_labels() -> HashMap<PathBuf, String> {
+        let Ok(entries) = fs::read_dir(LABELS_DIR) else {
+            return HashMap::new();
+        };
+
+        entries
+            .flatten()
+            .filter_map(|entry| {
+                let device = fs::canonicalize(entry.path()).ok()?;
+                let label = unescape_udev_label(&entry.file_name().to_string_lossy());
+                Some((device, label))
+            })
+            .collect()
+    }
+
+    /// udev encodes unsafe characters in by-label links as `\xHH`.
+    fn unescape_udev_label(label: &str) -> String {
+        let bytes = label.as_bytes();
+        let mut unescaped = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+
+        while i < bytes.len() {
+            if let Some(&[b'\\', b'x', high, low]) = bytes.get(i..i + 4)
+                && let (Some(high), Some(low)) = (hex_value(high), hex_value(low))
+            {
+                unescaped.push((high << 4) | low);
+                i += 4;
+                continue;
+            }
+            unescaped.push(bytes[i]);
+            i += 1;
+        }
+
+        String::from_utf8_lossy(&unescaped).into_owned()
+    }
+
+    fn hex_value(digit: u8) -> Option<u8> {
+        (digit as char).to_digit(16).map(|value| value as u8)
+    }
+
+    /// Partitions inherit the `removable` flag from their parent block device. USB and
+    /// SD card disks often report `removable=0`, so the sysfs bus path is checked too.
+    fn is_removable_device(device: &Path) -> bool {
+        let Some(name) = device.file_name() else {
+            return false;
+        };
+
+        let Ok(sys_path) = fs::canonicalize(Path::new("/sys/class/block").join(name)) else {
+            return false;
+        };
+
+        let sys_path_str = sys_path.to_string_lossy();
+        if sys_path_str.contains("/usb") || sys_path_str.contains("/mmc") {
+            return true;
+        }
+
+        [sys_path.join("removable"), sys_path.join("../removable")]
+            .iter()
+            .any(|flag| {
+                fs::read_to_string(flag)
+                    .map(|value| value.trim() == "1")
+                    .unwrap_or(false)
+            })
+    }
 }
@@ -0,0 +1,69 @@
+use std::fs;
+use std::path::PathBuf;
+use tauri::Manager;
+
+use crate::utils::db;
+use crate::utils::path_policy::{Operation, PathPolicy};
+
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_downloads_dir(handle: tauri::AppHandle) -> Result<String, String> {
+    let downloads_dir = resolve_downloads_dir(&handle).await?;
+    Ok(downloads_dir.to_string_lossy().to_string())
+}
+
+/// Resolves `settings.downloads_path`, falling back to the platform downloads folder
+/// (XDG user dirs on Linux, the Downloads Known Folder on Windows), and creates it.
+pub async fn resolve_downloads_dir(handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let downloads_dir = match configured_downloads_path(handle).await {
+        Some(path) => path,
+        None => platform_downloads_dir(handle)?,
+    };
+
+    if !downloads_dir.is_absolute() {
+        return Err("Downloads path must be absolute".into());
+    }
+
+    let downloads_dir = PathPolicy::load(handle)
+        .await
+        .check(&downloads_dir, Operation::Write)?;
+
+    if !downloads_dir.exists() {
+        fs::create_dir_all(&downloads_dir)
+            .map_err(|e| format!("Failed to create downloads directory: {}", e))?;
+    } else if !downloads_dir.is_dir() {
+        return Err("Downloads path is not a directory".into());
+    }
+
+    Ok(downloads_dir)
+}
+
+async fn configured_downloads_path(handle: &tauri::AppHandle) -> Option<PathBuf> {
+    let pool = db::pool(handle).await.ok()?;
+
+    let downloads_path: Option<String> =
+        sqlx::query_scalar("SELECT downloads_path FROM settings ORDER BY id LIMIT 1")
+            .fetch_optional(&pool)
+            .await
+            .unwrap_or_else(|e| {
+                log::warn!("Failed to read downloads path from settings: {}", e);
+                None
+            })
+            .flatten();
+
+    downloads_path
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+}
+
+fn platform_downloads_dir(handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    if let Ok(dir) = handle.path().download_dir() {
+        return Ok(dir);
+    }
+
+    handle
+        .path()
+        .home_dir()
+        .map(|home| home.join("Downloads"))
+        .map_err(|e| format!("Failed to resolve downloads directory: {}", e))
+}
@@ -2,6 +2,8 @@ use serde::Serialize;
 use std::fs;
 use std::path::Path;
 
+use crate::utils::path_policy::{Operation, PathPolicy};
+
 #[derive(Debug, Serialize)]
 pub struct FolderData {
     folders: Vec<String>,
@@ -38,6 +40,7 @@ fn is_special_folder(name: &str) -> bool {
 
 #[tauri::command(rename_all = "snake_case")]
 pub async fn get_folders(
+    handle: tauri::AppHandle,
     path: String,
     page: Option<usize>,
     per_page: Option<usize>,
@@ -52,6 +55,9 @@ pub async fn get_folders(
         return Err("Path is not a directory".to_string());
     }
 
+    let policy = PathPolicy::load(&handle).await;
+    policy.check(path, Operation::Read)?;
+
     let page = page.unwrap_or(1).max(1);
     let per_page = per_page.unwrap_or(50).clamp(1, 200);
 
@@ -65,12 +71,13 @@ pub async fn get_folders(
     for entry in entries.flatten() {
         let path = entry.path();
 
-        if path.is_dir() {
-            if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                if !name.starts_with('.') && !is_special_folder(name) {
-                    folders.push(path.to_string_lossy().to_string());
-                }
-            }
+        if path.is_dir()
+            && let Some(name) = path.file_name().and_then(|n| n.to_str())
+            && !name.starts_with('.')
+            && !is_special_folder(name)
+            && policy.check(&path, Operation::Read).is_ok()
+        {
+            folders.push(path.to_string_lossy().to_string());
         }
     }
 
@@ -1,13 +1,22 @@
 use std::path::Path;
 
+use crate::utils::path_policy::{Operation, PathPolicy};
+
 #[tauri::command(rename_all = "snake_case")]
-pub async fn get_parent_path(path: String) -> Result<Option<String>, String> {
+pub async fn get_parent_path(
+    handle: tauri::AppHandle,
+    path: String,
+) -> Result<Option<String>, String> {
     let path = Path::new(&path);
 
     if !path.exists() {
         return Err("Path does not exist".to_string());
     }
 
+    PathPolicy::load(&handle)
+        .await
+        .check(path, Operation::Read)?;
+
     match path.parent() {
         Some(parent) => {
             let parent_str = parent.to_string_lossy().to_string();
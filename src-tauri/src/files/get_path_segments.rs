@@ -1,24 +1,89 @@
-use std::path::Path;
+use serde::Serialize;
+use std::path::{Component, Path, PathBuf, Prefix};
+
+use crate::utils::path_policy::{Operation, PathPolicy};
+
+#[derive(Debug, Serialize)]
+pub struct PathSegment {
+    pub name: String,
+    pub path: String,
+}
 
 #[tauri::command(rename_all = "snake_case")]
-pub async fn get_path_segments(path: String) -> Result<Vec<String>, String> {
+pub async fn get_path_segments(
+    handle: tauri::AppHandle,
+    path: String,
+) -> Result<Vec<PathSegment>, String> {
     let path = Path::new(&path);
 
     if !path.exists() {
         return Err("Path does not exist".to_string());
     }
 
-    let mut segments = Vec::new();
-    let path_str = path.to_string_lossy();
+    PathPolicy::load(&handle)
+        .await
+        .check(path, Operation::Read)?;
 
-    let components: Vec<&str> = path_str.split('\\').filter(|s| !s.is_empty()).collect();
-    for component in components {
-        segments.push(component.to_string());
-    }
+    Ok(split_segments(path))
+}
 
-    if segments.is_empty() {
-        segments.push(path.to_string_lossy().to_string());
+/// Splits a path into breadcrumb segments, each carrying the path it points at. The
+/// prefix and root are merged into a single segment (`C:`, `\\server\share`, `/`).
+fn split_segments(path: &Path) -> Vec<PathSegment> {
+    let mut segments: Vec<PathSegment> = Vec::new();
+    let mut current = PathBuf::new();
+    let mut has_root_segment = false;
+
+    for component in path.components() {
+        match component {
+            Component::Prefix(prefix) => {
+                current.push(prefix.as_os_str());
+                has_root_segment = true;
+                segments.push(PathSegment {
+                    name: prefix_name(prefix.kind()),
+                    path: current.to_string_lossy().to_string(),
+                });
+            }
+            Component::RootDir => {
+                current.push(component.as_os_str());
+                let path = current.to_string_lossy().to_string();
+                has_root_segment = true;
+                match segments.last_mut() {
+                    Some(prefix) => prefix.path = path,
+                    None => segments.push(PathSegment {
+                        name: path.clone(),
+                        path,
+                    }),
+                }
+            }
+            Component::CurDir => {}
+            Component::ParentDir => {
+                let root_segments = usize::from(has_root_segment);
+                if current.pop() && segments.len() > root_segments {
+                    segments.pop();
+                }
+            }
+            Component::Normal(name) => {
+                current.push(name);
+                segments.push(PathSegment {
+                    name: name.to_string_lossy().to_string(),
+                    path: current.to_string_lossy().to_string(),
+                });
+            }
+        }
     }
 
-    Ok(segments)
+    segments
+}
+
+fn prefix_name(prefix: Prefix<'_>) -> String {
+    match prefix {
+        Prefix::Disk(drive) | Prefix::VerbatimDisk(drive) => format!("{}:", drive as char),
+        Prefix::UNC(server, share) | Prefix::VerbatimUNC(server, share) => format!(
+            "\\\\{}\\{}",
+            server.to_string_lossy(),
+            share.to_string_lossy()
+        ),
+        Prefix::Verbatim(name) | Prefix::DeviceNS(name) => name.to_string_lossy().to_string(),
+    }
 }
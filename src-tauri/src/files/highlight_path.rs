@@ -1,37 +1,298 @@
+use std::ffi::OsString;
+use std::fmt;
 use std::fs::canonicalize;
-use std::path::Path;
-use std::process::Command;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::utils::path_policy::{Operation, PathPolicy};
 
 #[tauri::command(rename_all = "snake_case")]
-pub fn highlight_path(file_path: String) -> Result<(), String> {
+pub async fn highlight_path(handle: tauri::AppHandle, file_path: String) -> Result<(), String> {
     let absolute_path = sanitize_and_resolve_path(&file_path)?;
-    let path = Path::new(&absolute_path);
+    PathPolicy::load(&handle)
+        .await
+        .check(&absolute_path, Operation::Read)?;
+    reveal(&absolute_path, &SystemLauncher).map_err(|e| e.to_string())
+}
+
+#[derive(Debug)]
+pub enum RevealError {
+    InvalidPath,
+    NoFileManager,
+    LaunchFailed(String),
+}
+
+impl fmt::Display for RevealError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RevealError::InvalidPath => write!(f, "The provided path is not valid."),
+            RevealError::NoFileManager => write!(f, "No file manager could be found."),
+            RevealError::LaunchFailed(e) => write!(f, "Failed to open file manager: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for RevealError {}
+
+/// Starts external programs on behalf of `reveal`, so the platform backends can be
+/// exercised without a desktop session.
+pub trait Launcher {
+    /// Runs the program to completion and reports whether it exited successfully.
+    fn run(&self, program: &str, args: &[OsString]) -> io::Result<bool>;
 
-    let mut command = Command::new("explorer");
-    if path.is_file() {
-        command.arg("/select,").arg(absolute_path);
-    } else if path.is_dir() {
-        command.arg(absolute_path);
+    /// Starts the program without waiting for it.
+    fn spawn(&self, program: &str, args: &[OsString]) -> io::Result<()>;
+}
+
+pub struct SystemLauncher;
+
+impl Launcher for SystemLauncher {
+    fn run(&self, program: &str, args: &[OsString]) -> io::Result<bool> {
+        let status = Command::new(program)
+            .args(args)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()?;
+        Ok(status.success())
+    }
+
+    fn spawn(&self, program: &str, args: &[OsString]) -> io::Result<()> {
+        Command::new(program).args(args).spawn().map(|_| ())
+    }
+}
+
+/// Shows `path` in the platform file manager, selecting it where the file manager supports it.
+pub fn reveal(path: &Path, launcher: &dyn Launcher) -> Result<(), RevealError> {
+    if !path.is_file() && !path.is_dir() {
+        return Err(RevealError::InvalidPath);
+    }
+
+    platform_reveal(path, launcher)
+}
+
+#[cfg(target_os = "windows")]
+fn platform_reveal(path: &Path, launcher: &dyn Launcher) -> Result<(), RevealError> {
+    let args = if path.is_file() {
+        vec![OsString::from("/select,"), path.as_os_str().to_owned()]
     } else {
-        return Err("The provided path is not valid.".to_string());
+        vec![path.as_os_str().to_owned()]
+    };
+
+    launcher.spawn("explorer", &args).map_err(launch_error)
+}
+
+#[cfg(target_os = "macos")]
+fn platform_reveal(path: &Path, launcher: &dyn Launcher) -> Result<(), RevealError> {
+    let args = [OsString::from("-R"), path.as_os_str().to_owned()];
+    launcher.spawn("open", &args).map_err(launch_error)
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn platform_reveal(path: &Path, launcher: &dyn Launcher) -> Result<(), RevealError> {
+    // org.freedesktop.FileManager1 is implemented by Nautilus, Dolphin, Nemo, Thunar and
+    // others; it opens the parent folder with the item selected.
+    let show_items_args = [
+        "--session",
+        "--print-reply",
+        "--dest=org.freedesktop.FileManager1",
+        "--type=method_call",
+        "/org/freedesktop/FileManager1",
+        "org.freedesktop.FileManager1.ShowItems",
+    ]
+    .into_iter()
+    .map(OsString::from)
+    .chain([
+        OsString::from(format!("array:string:{}", file_uri(path))),
+        OsString::from("string:"),
+    ])
+    .collect::<Vec<_>>();
+
+    match launcher.run("dbus-send", &show_items_args) {
+        Ok(true) => return Ok(()),
+        Ok(false) => {}
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(RevealError::LaunchFailed(e.to_string())),
     }
 
-    command
-        .spawn()
-        .map_err(|e| format!("Failed to open explorer: {}", e))?;
+    let folder = path.parent().unwrap_or(path);
+    launcher
+        .spawn("xdg-open", &[folder.as_os_str().to_owned()])
+        .map_err(launch_error)
+}
+
+fn launch_error(e: io::Error) -> RevealError {
+    if e.kind() == io::ErrorKind::NotFound {
+        RevealError::NoFileManager
+    } else {
+        RevealError::LaunchFailed(e.to_string())
+    }
+}
+
+/// Builds a `file://` URI, percent-encoding everything outside the RFC 3986 unreserved set.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn file_uri(path: &Path) -> String {
+    use std::os::unix::ffi::OsStrExt;
 
-    Ok(())
+    let mut uri = String::from("file://");
+    for &byte in path.as_os_str().as_bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-._~".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    uri
 }
 
-fn sanitize_and_resolve_path(file_path: &str) -> Result<String, String> {
+fn sanitize_and_resolve_path(file_path: &str) -> Result<PathBuf, String> {
     let path = Path::new(file_path);
 
     let absolute_path =
         canonicalize(path).map_err(|_| "Invalid or non-existent file path.".to_string())?;
 
-    let path_str = absolute_path
-        .to_str()
-        .ok_or_else(|| "Failed to convert path to valid UTF-8.".to_string())?;
+    if absolute_path.to_str().is_none() {
+        return Err("Failed to convert path to valid UTF-8.".to_string());
+    }
+
+    Ok(absolute_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// Records every program `reveal` starts and answers with canned results.
+    struct FakeLauncher {
+        run: fn() -> io::Result<bool>,
+        spawn: fn() -> io::Result<()>,
+        calls: RefCell<Vec<(String, Vec<OsString>)>>,
+    }
+
+    impl FakeLauncher {
+        fn new(run: fn() -> io::Result<bool>, spawn: fn() -> io::Result<()>) -> FakeLauncher {
+            FakeLauncher {
+                run,
+                spawn,
+                calls: RefCell::new(Vec::new()),
+            }
+        }
+
+        fn programs(&self) -> Vec<String> {
+            self.calls
+                .borrow()
+                .iter()
+                .map(|(program, _)| program.clone())
+                .collect()
+        }
+    }
+
+    impl Launcher for FakeLauncher {
+        fn run(&self, program: &str, args: &[OsString]) -> io::Result<bool> {
+            self.calls
+                .borrow_mut()
+                .push((program.to_string(), args.to_vec()));
+            (self.run)()
+        }
+
+        fn spawn(&self, program: &str, args: &[OsString]) -> io::Result<()> {
+            self.calls
+                .borrow_mut()
+                .push((program.to_string(), args.to_vec()));
+            (self.spawn)()
+        }
+    }
+
+    fn not_found<T>() -> io::Result<T> {
+        Err(io::Error::from(io::ErrorKind::NotFound))
+    }
+
+    /// A file in its own temporary folder, removed with it.
+    struct TempFile {
+        dir: TempDir,
+        path: PathBuf,
+    }
+
+    impl TempFile {
+        fn new(name: &str) -> TempFile {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join(name);
+            fs::write(&path, b"").unwrap();
+            TempFile { dir, path }
+        }
+    }
+
+    #[test]
+    fn rejects_missing_paths_without_launching() {
+        let launcher = FakeLauncher::new(|| Ok(true), || Ok(()));
+        let result = reveal(Path::new("/definitely/not/here.txt"), &launcher);
 
-    Ok(path_str.to_string())
+        assert!(matches!(result, Err(RevealError::InvalidPath)));
+        assert!(launcher.programs().is_empty());
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    #[test]
+    fn selects_the_item_through_show_items() {
+        let file = TempFile::new("a b%.txt");
+        let launcher = FakeLauncher::new(|| Ok(true), || Ok(()));
+
+        reveal(&file.path, &launcher).unwrap();
+
+        let calls = launcher.calls.borrow();
+        assert_eq!(calls.len(), 1);
+        let (program, args) = &calls[0];
+        assert_eq!(program, "dbus-send");
+        assert!(args.contains(&OsString::from("org.freedesktop.FileManager1.ShowItems")));
+        assert_eq!(
+            args[args.len() - 2],
+            OsString::from(format!("array:string:{}", file_uri(&file.path)))
+        );
+        assert!(file_uri(&file.path).ends_with("/a%20b%25.txt"));
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    #[test]
+    fn falls_back_to_xdg_open() {
+        for run in [|| Ok(false), not_found::<bool>] {
+            let file = TempFile::new("a.txt");
+            let launcher = FakeLauncher::new(run, || Ok(()));
+
+            reveal(&file.path, &launcher).unwrap();
+
+            let calls = launcher.calls.borrow();
+            assert_eq!(launcher.programs(), ["dbus-send", "xdg-open"]);
+            assert_eq!(calls[1].1, [file.dir.path().as_os_str().to_owned()]);
+        }
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    #[test]
+    fn reports_a_missing_file_manager() {
+        let file = TempFile::new("a.txt");
+        let launcher = FakeLauncher::new(not_found, not_found);
+
+        let result = reveal(&file.path, &launcher);
+
+        assert!(matches!(result, Err(RevealError::NoFileManager)));
+        assert_eq!(launcher.programs(), ["dbus-send", "xdg-open"]);
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    #[test]
+    fn stops_when_dbus_send_cannot_start() {
+        let file = TempFile::new("a.txt");
+        let launcher = FakeLauncher::new(
+            || Err(io::Error::from(io::ErrorKind::PermissionDenied)),
+            || Ok(()),
+        );
+
+        let result = reveal(&file.path, &launcher);
+
+        assert!(matches!(result, Err(RevealError::LaunchFailed(_))));
+        assert_eq!(launcher.programs(), ["dbus-send"]);
+    }
 }
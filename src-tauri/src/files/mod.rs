@@ -1,13 +1,22 @@
 use serde::Serialize;
 
+pub mod cancel_fetch_files;
+pub mod cancel_search_files;
 pub mod copy_file;
 pub mod fetch_files;
 pub mod file_details;
 pub mod get_available_disks;
+pub mod get_downloads_dir;
 pub mod get_folders;
 pub mod get_parent_path;
 pub mod get_path_segments;
 pub mod highlight_path;
+pub mod move_path;
+pub mod open_external_url;
+pub mod rename_path;
+pub mod search_files;
+pub mod sniff;
+pub mod transfer;
 pub mod trash;
 
 #[derive(Serialize)]
@@ -27,4 +36,5 @@ pub struct IFile {
     pub scrape_url: Option<String>,
     pub download_url: Option<String>,
     pub is_directory: u8,
+    pub extension_mismatch: u8,
 }
@@ -0,0 +1,130 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::files::transfer;
+use crate::journal::{self, OperationKind};
+use crate::library::index;
+use crate::utils::db;
+use crate::utils::path_policy::{Operation, PathPolicy};
+
+/// Moves a file or folder into `target_folder` and returns its new path. Indexed rows
+/// for the item, and for everything below it, follow the move.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn move_path(
+    handle: tauri::AppHandle,
+    path: String,
+    target_folder: String,
+) -> Result<String, String> {
+    let policy = PathPolicy::load(&handle).await;
+    let (source, target) = move_target(&policy, Path::new(&path), Path::new(&target_folder))?;
+    if target == source {
+        return Ok(target.to_string_lossy().to_string());
+    }
+
+    transfer::check_not_nested(&source, &target)?;
+    if fs::symlink_metadata(&target).is_ok() {
+        return Err("An item with that name already exists in the target folder".into());
+    }
+
+    let from = source.to_string_lossy().to_string();
+    let moved = relocate(&handle, source, target).await?;
+    journal::log_operation(
+        &handle,
+        &journal::new_batch_id(),
+        OperationKind::Move,
+        &from,
+        Some(&moved),
+    )
+    .await;
+
+    Ok(moved)
+}
+
+/// Where `path` ends up when moved into `folder`. A symlink is moved itself, not the
+/// entry it points to.
+fn move_target(
+    policy: &PathPolicy,
+    path: &Path,
+    folder: &Path,
+) -> Result<(PathBuf, PathBuf), String> {
+    if fs::symlink_metadata(path).is_err() {
+        return Err("Move: Invalid path".into());
+    }
+
+    let source = policy.check_entry(path, Operation::Delete)?;
+    let folder = policy.check(folder, Operation::Write)?;
+    if !folder.is_dir() {
+        return Err("Target folder does not exist".into());
+    }
+
+    let name = source.file_name().ok_or("Move: Invalid path")?;
+    let target = folder.join(name);
+    Ok((source, target))
+}
+
+/// Moves `from` to `to`, copying and deleting when they are on different filesystems,
+/// then updates the `files` table. If the index cannot be updated the move is undone,
+/// so the database never points at paths that are gone.
+pub async fn relocate(
+    handle: &tauri::AppHandle,
+    from: PathBuf,
+    to: PathBuf,
+) -> Result<String, String> {
+    let (source, target) = (from.clone(), to.clone());
+    tauri::async_runtime::spawn_blocking(move || {
+        transfer::move_tree(&source, &target, &mut |_| true)
+    })
+    .await
+    .map_err(|e| format!("Failed to move: {}", e))?
+    .map_err(|e| format!("Failed to move: {}", e))?;
+
+    let from_str = from.to_string_lossy().to_string();
+    let to_str = to.to_string_lossy().to_string();
+
+    if let Ok(pool) = db::pool(handle).await
+        && let Err(e) = index::record_move(&pool, &from_str, &to_str).await
+    {
+        let restored = tauri::async_runtime::spawn_blocking(move || {
+            transfer::move_tree(&to, &from, &mut |_| true)
+        })
+        .await;
+        if !matches!(restored, Ok(Ok(()))) {
+            log::error!("Failed to undo move of {} after: {}", from_str, e);
+        }
+        return Err(e);
+    }
+
+    Ok(to_str)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::symlink;
+
+    #[test]
+    fn moves_a_symlink_instead_of_its_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let policy = PathPolicy::platform_default(None)
+            .with_user_roots(Vec::new(), vec![dir.path().to_path_buf()]);
+        let (from, to, real) = (
+            dir.path().join("a"),
+            dir.path().join("b"),
+            dir.path().join("real"),
+        );
+        for folder in [&from, &to, &real] {
+            fs::create_dir(folder).unwrap();
+        }
+        fs::write(real.join("file.txt"), b"data").unwrap();
+        symlink(real.join("file.txt"), from.join("link")).unwrap();
+
+        let (source, target) = move_target(&policy, &from.join("link"), &to).unwrap();
+        assert_eq!(source.file_name().unwrap(), "link");
+        assert_eq!(target, to.canonicalize().unwrap().join("link"));
+
+        transfer::move_tree(&source, &target, &mut |_| true).unwrap();
+        assert!(fs::symlink_metadata(&target).unwrap().is_symlink());
+        assert!(fs::symlink_metadata(from.join("link")).is_err());
+        assert_eq!(fs::read(real.join("file.txt")).unwrap(), b"data");
+    }
+}
@@ -0,0 +1,38 @@
+use reqwest::Url;
+
+const ALLOWED_SCHEMES: &[&str] = &["http", "https", "mailto"];
+const MAX_URL_LENGTH: usize = 2048;
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn open_external_url(url: String) -> Result<(), String> {
+    let url = validate_url(&url)?;
+
+    open::that_detached(url.as_str()).map_err(|e| format!("Failed to open URL: {}", e))?;
+
+    Ok(())
+}
+
+fn validate_url(url: &str) -> Result<Url, String> {
+    let url = url.trim();
+    if url.is_empty() || url.len() > MAX_URL_LENGTH {
+        return Err("Invalid URL".to_string());
+    }
+
+    if url.chars().any(char::is_control) {
+        return Err("URL contains control characters".to_string());
+    }
+
+    let parsed = Url::parse(url).map_err(|e| format!("Invalid URL: {}", e))?;
+
+    if !ALLOWED_SCHEMES.contains(&parsed.scheme()) {
+        return Err(format!("Refusing to open URL scheme '{}'", parsed.scheme()));
+    }
+
+    match parsed.scheme() {
+        "mailto" if parsed.path().is_empty() => Err("Mail link has no recipient".to_string()),
+        "http" | "https" if parsed.host_str().is_none_or(str::is_empty) => {
+            Err("URL has no host".to_string())
+        }
+        _ => Ok(parsed),
+    }
+}
@@ -0,0 +1,113 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::files::move_path::relocate;
+use crate::journal::{self, OperationKind};
+use crate::utils::path_policy::{Operation, PathPolicy};
+
+/// Renames a file or folder in place and returns its new path. Changing only the case
+/// of a name is allowed on case-insensitive filesystems.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn rename_path(
+    handle: tauri::AppHandle,
+    path: String,
+    new_name: String,
+) -> Result<String, String> {
+    let policy = PathPolicy::load(&handle).await;
+    let (source, target) = rename_target(&policy, Path::new(&path), &new_name)?;
+
+    if target == source {
+        return Ok(target.to_string_lossy().to_string());
+    }
+    if fs::symlink_metadata(&target).is_ok() && !is_same_file(&source, &target) {
+        return Err("An item with that name already exists".into());
+    }
+
+    let from = source.to_string_lossy().to_string();
+    let moved = relocate(&handle, source, target).await?;
+    journal::log_operation(
+        &handle,
+        &journal::new_batch_id(),
+        OperationKind::Rename,
+        &from,
+        Some(&moved),
+    )
+    .await;
+
+    Ok(moved)
+}
+
+/// The current and new path of a rename. A symlink is renamed itself, next to where
+/// it is, not the entry it points to.
+fn rename_target(
+    policy: &PathPolicy,
+    path: &Path,
+    new_name: &str,
+) -> Result<(PathBuf, PathBuf), String> {
+    let new_name = new_name.trim();
+    if new_name.is_empty() || new_name == "." || new_name == ".." || new_name.contains(['/', '\\'])
+    {
+        return Err("Invalid name".into());
+    }
+
+    if fs::symlink_metadata(path).is_err() {
+        return Err("Rename: Invalid path".into());
+    }
+
+    let source = policy.check_entry(path, Operation::Delete)?;
+    let parent = source.parent().ok_or("Rename: Invalid path")?;
+    // Keep the name as typed; resolving it would fold a case-only rename back onto the
+    // existing name on case-insensitive filesystems.
+    let target = parent.join(new_name);
+    policy.check_entry(&target, Operation::Write)?;
+    Ok((source, target))
+}
+
+/// Whether two paths name the same entry, as a case-only rename does on a
+/// case-insensitive filesystem.
+fn is_same_file(a: &Path, b: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        match (fs::symlink_metadata(a), fs::symlink_metadata(b)) {
+            (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+            _ => false,
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        match (fs::canonicalize(a), fs::canonicalize(b)) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::files::transfer;
+    use std::os::unix::fs::symlink;
+
+    #[test]
+    fn renames_a_symlink_in_its_own_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        let policy = PathPolicy::platform_default(None)
+            .with_user_roots(Vec::new(), vec![dir.path().to_path_buf()]);
+        let (links, real) = (dir.path().join("links"), dir.path().join("real"));
+        fs::create_dir(&links).unwrap();
+        fs::create_dir(&real).unwrap();
+        fs::write(real.join("file.txt"), b"data").unwrap();
+        symlink(real.join("file.txt"), links.join("link")).unwrap();
+
+        let (source, target) = rename_target(&policy, &links.join("link"), "renamed").unwrap();
+        assert_eq!(source.file_name().unwrap(), "link");
+        assert_eq!(target, links.canonicalize().unwrap().join("renamed"));
+
+        transfer::move_tree(&source, &target, &mut |_| true).unwrap();
+        assert!(fs::symlink_metadata(&target).unwrap().is_symlink());
+        assert!(real.join("file.txt").is_file());
+        assert!(fs::symlink_metadata(real.join("renamed")).is_err());
+    }
+}
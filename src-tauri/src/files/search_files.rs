@@ -0,0 +1,403 @@
+use globset::{GlobBuilder, GlobMatcher};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::{self, DirEntry};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, UNIX_EPOCH};
+use tauri::Manager;
+use tauri::ipc::Channel;
+
+use crate::files::file_details::get_file_type_from_extension;
+use crate::utils::cancellation::CancelRegistry;
+use crate::utils::path_policy::{Operation, PathPolicy};
+
+const DEFAULT_LIMIT: usize = 1000;
+const MAX_WORKERS: usize = 8;
+const BATCH_SIZE: usize = 100;
+const BATCH_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Deserialize, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PatternMode {
+    #[default]
+    Substring,
+    Glob,
+    Regex,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct SearchOptions {
+    pub mode: PatternMode,
+    pub case_sensitive: bool,
+    /// Match against the path relative to the root instead of the bare name.
+    pub match_path: bool,
+    pub include_hidden: bool,
+    pub include_directories: bool,
+    pub min_size: Option<u64>,
+    pub max_size: Option<u64>,
+    /// Unix timestamps in seconds.
+    pub modified_after: Option<u64>,
+    pub modified_before: Option<u64>,
+    /// `video`, `image`, `audio`, `document` or `other`, as derived from the extension.
+    pub file_types: Vec<String>,
+    pub max_depth: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub struct SearchHit {
+    pub path: String,
+    pub name: String,
+    pub size: u64,
+    pub mtime: u64,
+    pub file_type: String,
+    pub is_directory: u8,
+}
+
+#[derive(Serialize)]
+pub struct SearchBatch {
+    pub hits: Vec<SearchHit>,
+    /// Set on the last batch, which may be empty.
+    pub done: bool,
+}
+
+#[derive(Serialize, Default)]
+pub struct SearchSummary {
+    pub matched: usize,
+    pub scanned: u64,
+    /// The result limit was reached before the walk finished.
+    pub truncated: bool,
+    pub cancelled: bool,
+}
+
+enum Pattern {
+    Substring(String),
+    Glob(GlobMatcher),
+    Regex(Regex),
+}
+
+impl Pattern {
+    fn new(query: &str, options: &SearchOptions) -> Result<Pattern, String> {
+        match options.mode {
+            PatternMode::Substring if options.case_sensitive => {
+                Ok(Pattern::Substring(query.to_string()))
+            }
+            PatternMode::Substring => Ok(Pattern::Substring(query.to_lowercase())),
+            PatternMode::Glob => GlobBuilder::new(query)
+                .case_insensitive(!options.case_sensitive)
+                .literal_separator(options.match_path)
+                .build()
+                .map(|glob| Pattern::Glob(glob.compile_matcher()))
+                .map_err(|e| format!("Invalid glob pattern: {}", e)),
+            PatternMode::Regex => RegexBuilder::new(query)
+                .case_insensitive(!options.case_sensitive)
+                .build()
+                .map(Pattern::Regex)
+                .map_err(|e| format!("Invalid regular expression: {}", e)),
+        }
+    }
+
+    fn matches(&self, text: &str, case_sensitive: bool) -> bool {
+        match self {
+            Pattern::Substring(needle) if case_sensitive => text.contains(needle.as_str()),
+            Pattern::Substring(needle) => text.to_lowercase().contains(needle.as_str()),
+            Pattern::Glob(glob) => glob.is_match(text),
+            Pattern::Regex(regex) => regex.is_match(text),
+        }
+    }
+}
+
+/// Searches everything below `root` for names matching `query`, walking folders on a
+/// pool of threads. Matches stream through `on_batch` as they are found; the walk stops
+/// at the result limit or when `cancel_search_files` is called with `search_id`.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn search_files(
+    handle: tauri::AppHandle,
+    search_id: String,
+    root: String,
+    query: String,
+    options: Option<SearchOptions>,
+    on_batch: Channel<SearchBatch>,
+) -> Result<SearchSummary, String> {
+    let root_path = Path::new(&root);
+    if !root_path.is_absolute() || !root_path.is_dir() {
+        return Err("Invalid search folder".into());
+    }
+    if query.trim().is_empty() {
+        return Err("Search query is empty".into());
+    }
+
+    let policy = PathPolicy::load(&handle).await;
+    let root_path = policy.check(root_path, Operation::Read)?;
+
+    let options = options.unwrap_or_default();
+    let pattern = Pattern::new(query.trim(), &options)?;
+
+    let registry = handle.state::<CancelRegistry>();
+    let cancelled = registry.register(&search_id);
+
+    let search = Search {
+        root: root_path,
+        pattern,
+        options,
+        policy,
+    };
+    let flag = cancelled.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || search.run(&flag, &on_batch))
+        .await
+        .map_err(|e| format!("Search failed: {}", e));
+
+    registry.remove(&search_id);
+    result?
+}
+
+struct Search {
+    root: PathBuf,
+    pattern: Pattern,
+    options: SearchOptions,
+    policy: PathPolicy,
+}
+
+/// Folders waiting to be read, and how many workers are reading one right now. The
+/// walk is over once both are zero.
+struct Queue {
+    state: Mutex<(VecDeque<(PathBuf, usize)>, usize)>,
+    changed: Condvar,
+}
+
+impl Search {
+    fn run(
+        &self,
+        cancelled: &AtomicBool,
+        on_batch: &Channel<SearchBatch>,
+    ) -> Result<SearchSummary, String> {
+        let limit = self.options.limit.unwrap_or(DEFAULT_LIMIT).max(1);
+        let workers = thread::available_parallelism()
+            .map(|count| count.get())
+            .unwrap_or(1)
+            .min(MAX_WORKERS);
+
+        let queue = Queue {
+            state: Mutex::new((VecDeque::from([(self.root.clone(), 0)]), 0)),
+            changed: Condvar::new(),
+        };
+        let stop = AtomicBool::new(false);
+        let scanned = AtomicU64::new(0);
+        let (sender, receiver) = mpsc::channel::<SearchHit>();
+
+        let mut summary = SearchSummary::default();
+
+        thread::scope(|scope| -> Result<(), String> {
+            for _ in 0..workers {
+                let sender = sender.clone();
+                let (queue, stop, scanned) = (&queue, &stop, &scanned);
+                scope.spawn(move || self.work(queue, stop, scanned, &sender));
+            }
+            drop(sender);
+
+            let result = stream(&receiver, cancelled, limit, on_batch, &mut summary);
+
+            // Workers notice the flag after their current entry; whatever they still
+            // send is dropped with the receiver.
+            stop.store(true, Ordering::Relaxed);
+            queue.changed.notify_all();
+            result
+        })?;
+
+        summary.scanned = scanned.load(Ordering::Relaxed);
+        Ok(summary)
+    }
+
+    fn work(
+        &self,
+        queue: &Queue,
+        stop: &AtomicBool,
+        scanned: &AtomicU64,
+        sender: &mpsc::Sender<SearchHit>,
+    ) {
+        while let Some((folder, depth)) = next_folder(queue, stop) {
+            if let Ok(entries) = fs::read_dir(&folder) {
+                for entry in entries.flatten() {
+                    if stop.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    scanned.fetch_add(1, Ordering::Relaxed);
+
+                    if let Some(subfolder) = self.visit(&entry, depth, sender) {
+                        let mut state = queue.state.lock().unwrap_or_else(|e| e.into_inner());
+                        state.0.push_back((subfolder, depth + 1));
+                        queue.changed.notify_one();
+                    }
+                }
+            }
+
+            let mut state = queue.state.lock().unwrap_or_else(|e| e.into_inner());
+            state.1 -= 1;
+            queue.changed.notify_all();
+        }
+    }
+
+    /// Reports `entry` when it matches, and returns it when it is a folder to descend
+    /// into.
+    fn visit(
+        &self,
+        entry: &DirEntry,
+        depth: usize,
+        sender: &mpsc::Sender<SearchHit>,
+    ) -> Option<PathBuf> {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !self.options.include_hidden && name.starts_with('.') {
+            return None;
+        }
+
+        let file_type = entry.file_type().ok()?;
+        let path = entry.path();
+        let is_directory = file_type.is_dir();
+
+        let descend = is_directory
+            && self.options.max_depth.is_none_or(|max| depth < max)
+            && self.policy.check(&path, Operation::Read).is_ok();
+
+        if (!is_directory || self.options.include_directories)
+            && let Some(hit) = self.check(entry, &path, name, is_directory)
+        {
+            let _ = sender.send(hit);
+        }
+
+        descend.then_some(path)
+    }
+
+    fn check(
+        &self,
+        entry: &DirEntry,
+        path: &Path,
+        name: String,
+        is_directory: bool,
+    ) -> Option<SearchHit> {
+        let options = &self.options;
+
+        let target = if options.match_path {
+            path.strip_prefix(&self.root)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .to_string()
+        } else {
+            name.clone()
+        };
+        if !self.pattern.matches(&target, options.case_sensitive) {
+            return None;
+        }
+
+        let file_type = if is_directory {
+            "directory".to_string()
+        } else {
+            let extension = Path::new(&name)
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_string())
+                .unwrap_or_default();
+            get_file_type_from_extension(&extension)
+        };
+        if !options.file_types.is_empty() && !options.file_types.contains(&file_type) {
+            return None;
+        }
+
+        let metadata = entry.metadata().ok()?;
+        let size = if is_directory { 0 } else { metadata.len() };
+        let mtime = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0);
+
+        let in_range = |value: u64, min: Option<u64>, max: Option<u64>| {
+            min.is_none_or(|min| value >= min) && max.is_none_or(|max| value <= max)
+        };
+        if !in_range(size, options.min_size, options.max_size)
+            || !in_range(mtime, options.modified_after, options.modified_before)
+        {
+            return None;
+        }
+
+        Some(SearchHit {
+            path: path.to_string_lossy().to_string(),
+            name,
+            size,
+            mtime,
+            file_type,
+            is_directory: is_directory as u8,
+        })
+    }
+}
+
+/// Forwards hits to the frontend in batches until the walk ends, the limit is reached
+/// or the search is cancelled.
+fn stream(
+    receiver: &mpsc::Receiver<SearchHit>,
+    cancelled: &AtomicBool,
+    limit: usize,
+    on_batch: &Channel<SearchBatch>,
+    summary: &mut SearchSummary,
+) -> Result<(), String> {
+    let mut batch = Vec::new();
+    let mut last_send = Instant::now();
+
+    loop {
+        if cancelled.load(Ordering::Relaxed) {
+            summary.cancelled = true;
+            break;
+        }
+
+        match receiver.recv_timeout(BATCH_INTERVAL) {
+            Ok(hit) => {
+                batch.push(hit);
+                summary.matched += 1;
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+
+        if summary.matched >= limit {
+            summary.truncated = true;
+            break;
+        }
+        if batch.len() >= BATCH_SIZE || (!batch.is_empty() && last_send.elapsed() >= BATCH_INTERVAL)
+        {
+            send(on_batch, std::mem::take(&mut batch), false)?;
+            last_send = Instant::now();
+        }
+    }
+
+    send(on_batch, batch, true)
+}
+
+/// Takes the next folder to read, waiting while other workers may still queue more.
+/// Returns `None` once the walk is over or stopped.
+fn next_folder(queue: &Queue, stop: &AtomicBool) -> Option<(PathBuf, usize)> {
+    let mut state = queue.state.lock().unwrap_or_else(|e| e.into_inner());
+    loop {
+        if stop.load(Ordering::Relaxed) {
+            return None;
+        }
+        if let Some(folder) = state.0.pop_front() {
+            state.1 += 1;
+            return Some(folder);
+        }
+        if state.1 == 0 {
+            return None;
+        }
+        state = queue.changed.wait(state).unwrap_or_else(|e| e.into_inner());
+    }
+}
+
+fn send(on_batch: &Channel<SearchBatch>, hits: Vec<SearchHit>, done: bool) -> Result<(), String> {
+    on_batch
+        .send(SearchBatch { hits, done })
+        .map_err(|e| format!("Failed to send search results: {}", e))
+}
@@ -0,0 +1,53 @@
+use infer::MatcherType;
+use std::path::Path;
+
+pub struct SniffedType {
+    pub mime: String,
+    pub extension: String,
+    pub file_type: String,
+}
+
+/// Extensions that name the same format, or a container whose header cannot tell them
+/// apart (ZIP-based office documents, OLE compound files, Matroska and WebM).
+const EQUIVALENT_EXTENSIONS: &[&[&str]] = &[
+    &["jpg", "jpeg", "jpe", "jfif"],
+    &["tif", "tiff"],
+    &["mp4", "m4v", "m4p"],
+    &["mkv", "webm", "mka"],
+    &["ogg", "oga", "ogv", "opus"],
+    &["mpg", "mpeg", "mpe"],
+    &["mid", "midi"],
+    &["aif", "aiff"],
+    &["heif", "heic"],
+    &[
+        "zip", "docx", "xlsx", "pptx", "odt", "ods", "odp", "epub", "jar", "apk",
+    ],
+    &["doc", "xls", "ppt", "msi"],
+];
+
+/// Detects the real type of a file from its leading bytes.
+pub fn sniff(path: &Path) -> Option<SniffedType> {
+    let kind = infer::get_from_path(path).ok().flatten()?;
+
+    let file_type = match kind.matcher_type() {
+        MatcherType::Image => "image",
+        MatcherType::Video => "video",
+        MatcherType::Audio => "audio",
+        MatcherType::Doc | MatcherType::Book => "document",
+        MatcherType::Archive if matches!(kind.extension(), "pdf" | "rtf") => "document",
+        _ => "other",
+    };
+
+    Some(SniffedType {
+        mime: kind.mime_type().to_string(),
+        extension: kind.extension().to_string(),
+        file_type: file_type.to_string(),
+    })
+}
+
+pub fn extensions_agree(extension: &str, sniffed_extension: &str) -> bool {
+    extension == sniffed_extension
+        || EQUIVALENT_EXTENSIONS
+            .iter()
+            .any(|group| group.contains(&extension) && group.contains(&sniffed_extension))
+}
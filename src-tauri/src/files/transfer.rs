@@ -0,0 +1,199 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Called with the number of bytes written since the last call. Returning `false`
+/// stops the transfer with an `Interrupted` error.
+pub type OnProgress<'a> = dyn FnMut(u64) -> bool + 'a;
+
+/// What to do when the destination of a transfer already exists.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    #[default]
+    Skip,
+    Overwrite,
+    Rename,
+}
+
+pub fn interrupted() -> io::Error {
+    io::Error::new(io::ErrorKind::Interrupted, "Cancelled")
+}
+
+/// Total size of a file, or of every file below a directory.
+pub fn tree_size(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .flatten()
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| entry.metadata().ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+/// Applies `policy` to `destination`. Returns the path to write to, or `None` when the
+/// item should be skipped. Overwriting leaves the existing entry alone; callers write
+/// to `staging_path` and swap the result in once the transfer has succeeded.
+pub fn resolve_conflict(destination: &Path, policy: ConflictPolicy) -> Option<PathBuf> {
+    if !is_taken(destination) {
+        return Some(destination.to_path_buf());
+    }
+
+    match policy {
+        ConflictPolicy::Skip => None,
+        ConflictPolicy::Overwrite => Some(destination.to_path_buf()),
+        ConflictPolicy::Rename => Some(available_name(destination, is_taken)),
+    }
+}
+
+/// Hidden sibling of `destination` that a replacing transfer writes to, so the entry
+/// it replaces survives a failed or cancelled transfer.
+pub fn staging_path(destination: &Path) -> PathBuf {
+    let name = destination
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy();
+    destination.with_file_name(format!(".{}.{}.partial", name, Uuid::new_v4()))
+}
+
+/// Whether anything, including a broken symlink, exists at `path`.
+pub fn is_taken(path: &Path) -> bool {
+    fs::symlink_metadata(path).is_ok()
+}
+
+/// `path` itself when `taken` rejects it, otherwise the first of `name (1).ext`,
+/// `name (2).ext`, … that is not. Folders keep their whole name as the stem.
+pub fn available_name(path: &Path, taken: impl Fn(&Path) -> bool) -> PathBuf {
+    if !taken(path) {
+        return path.to_path_buf();
+    }
+
+    let (stem, extension) = match path.extension() {
+        Some(ext) if !path.is_dir() => (
+            path.file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+            format!(".{}", ext.to_string_lossy()),
+        ),
+        _ => (
+            path.file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+            String::new(),
+        ),
+    };
+
+    (1..)
+        .map(|n| path.with_file_name(format!("{} ({}){}", stem, n, extension)))
+        .find(|candidate| !taken(candidate))
+        .expect("unbounded range always yields a free name")
+}
+
+/// Refuses to copy or move a directory into itself, which would never finish.
+pub fn check_not_nested(source: &Path, destination: &Path) -> Result<(), String> {
+    if source.is_dir() && destination.starts_with(source) {
+        return Err("Cannot copy or move a folder into itself".into());
+    }
+    Ok(())
+}
+
+/// Copies a file or a whole directory tree, reporting progress per chunk. A failed or
+/// cancelled copy removes what it wrote.
+pub fn copy_tree(
+    source: &Path,
+    destination: &Path,
+    on_progress: &mut OnProgress,
+) -> io::Result<()> {
+    if fs::symlink_metadata(destination).is_ok() {
+        return Err(io::Error::from(io::ErrorKind::AlreadyExists));
+    }
+
+    let result = if source.is_dir() {
+        copy_dir(source, destination, on_progress)
+    } else {
+        copy_file(source, destination, on_progress)
+    };
+
+    if result.is_err() {
+        let _ = remove(destination);
+    }
+    result
+}
+
+/// Moves a file or directory with a rename, falling back to copy and delete when the
+/// destination is on another filesystem.
+pub fn move_tree(
+    source: &Path,
+    destination: &Path,
+    on_progress: &mut OnProgress,
+) -> io::Result<()> {
+    match fs::rename(source, destination) {
+        Ok(()) => {
+            on_progress(tree_size(destination));
+            Ok(())
+        }
+        Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {
+            copy_tree(source, destination, on_progress)?;
+            remove(source)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+pub fn remove(path: &Path) -> io::Result<()> {
+    let metadata = fs::symlink_metadata(path)?;
+    if metadata.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+fn copy_dir(source: &Path, destination: &Path, on_progress: &mut OnProgress) -> io::Result<()> {
+    for entry in WalkDir::new(source) {
+        let entry = entry.map_err(io::Error::other)?;
+        let relative = entry
+            .path()
+            .strip_prefix(source)
+            .map_err(io::Error::other)?;
+        let target = destination.join(relative);
+
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target)?;
+        } else {
+            copy_file(entry.path(), &target, on_progress)?;
+        }
+    }
+    Ok(())
+}
+
+fn copy_file(source: &Path, destination: &Path, on_progress: &mut OnProgress) -> io::Result<()> {
+    let mut reader = File::open(source)?;
+    let metadata = reader.metadata()?;
+    let mut writer = File::create(destination)?;
+    let mut buffer = vec![0; CHUNK_SIZE];
+
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        writer.write_all(&buffer[..read])?;
+        if !on_progress(read as u64) {
+            return Err(interrupted());
+        }
+    }
+
+    writer.set_permissions(metadata.permissions())?;
+    if let Ok(modified) = metadata.modified() {
+        let _ = writer.set_modified(modified);
+    }
+    Ok(())
+}
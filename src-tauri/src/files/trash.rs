@@ -1,26 +1,33 @@
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 use trash::delete;
 
-use crate::utils::protected_paths::PROTECTED_PATHS;
+use crate::journal::{self, OperationKind};
+use crate::utils::path_policy::{Operation, PathPolicy};
 
 #[tauri::command(rename_all = "snake_case")]
-pub async fn trash(path: String) -> Result<bool, String> {
+pub async fn trash(handle: tauri::AppHandle, path: String) -> Result<bool, String> {
     let file_path = PathBuf::from(&path);
-    let canonical = file_path
+    file_path
         .canonicalize()
         .map_err(|_| "Trash: Invalid file path")?;
 
-    for protected in PROTECTED_PATHS {
-        let protected_path = Path::new(protected);
-        if canonical.starts_with(protected_path) {
-            return Err("Refusing to trash a protected system path.".into());
-        }
-    }
+    let canonical = PathPolicy::load(&handle)
+        .await
+        .check(&file_path, Operation::Delete)?;
 
     if !canonical.exists() {
         return Err("File or directory does not exist".into());
     }
 
     delete(&canonical).map_err(|e| format!("Failed to trash: {}", e))?;
+    journal::log_operation(
+        &handle,
+        &journal::new_batch_id(),
+        OperationKind::Trash,
+        &canonical.to_string_lossy(),
+        None,
+    )
+    .await;
+
     Ok(true)
 }
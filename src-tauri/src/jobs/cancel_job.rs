@@ -0,0 +1,9 @@
+use tauri::Manager;
+
+use crate::jobs::JobManager;
+
+/// Cancels a job. The item in progress is rolled back, the rest are reported as cancelled.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn cancel_job(handle: tauri::AppHandle, job_id: String) -> Result<(), String> {
+    handle.state::<JobManager>().cancel(&job_id)
+}
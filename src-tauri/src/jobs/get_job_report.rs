@@ -0,0 +1,8 @@
+use tauri::Manager;
+
+use crate::jobs::{JobManager, JobReport};
+
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_job_report(handle: tauri::AppHandle, job_id: String) -> Result<JobReport, String> {
+    Ok(handle.state::<JobManager>().get(&job_id)?.report())
+}
@@ -0,0 +1,8 @@
+use tauri::Manager;
+
+use crate::jobs::{JobManager, JobProgress};
+
+#[tauri::command(rename_all = "snake_case")]
+pub async fn list_jobs(handle: tauri::AppHandle) -> Result<Vec<JobProgress>, String> {
+    Ok(handle.state::<JobManager>().list())
+}
@@ -0,0 +1,385 @@
+use serde::{Deserialize, Serialize};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tauri::AppHandle;
+
+use crate::files::transfer::ConflictPolicy;
+use crate::utils::path_policy::PathPolicy;
+
+pub mod cancel_job;
+pub mod get_job_report;
+pub mod list_jobs;
+pub mod pause_job;
+pub mod resume_job;
+pub mod start_job;
+mod worker;
+
+/// Jobs running at the same time. Further jobs wait in the queue.
+const WORKER_COUNT: usize = 2;
+
+/// Finished jobs kept around for `get_job_report`, oldest dropped first.
+const FINISHED_JOBS_KEPT: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobKind {
+    Copy,
+    Move,
+    Trash,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    Queued,
+    Running,
+    Paused,
+    Cancelled,
+    Completed,
+    Failed,
+}
+
+impl JobState {
+    pub fn is_finished(self) -> bool {
+        matches!(
+            self,
+            JobState::Cancelled | JobState::Completed | JobState::Failed
+        )
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct JobRequest {
+    pub kind: JobKind,
+    pub sources: Vec<String>,
+    /// Folder the sources are copied or moved into. Unused for trash jobs.
+    pub destination: Option<String>,
+    #[serde(default)]
+    pub conflict: ConflictPolicy,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemStatus {
+    Done,
+    Skipped,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ItemReport {
+    pub source: String,
+    pub destination: Option<String>,
+    pub status: ItemStatus,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JobProgress {
+    pub job_id: String,
+    pub kind: JobKind,
+    pub state: JobState,
+    pub total_items: u64,
+    pub processed_items: u64,
+    pub total_bytes: u64,
+    pub processed_bytes: u64,
+    pub current_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JobReport {
+    #[serde(flatten)]
+    pub progress: JobProgress,
+    pub items: Vec<ItemReport>,
+}
+
+pub struct Job {
+    pub id: String,
+    pub request: JobRequest,
+    pub policy: PathPolicy,
+    control: Mutex<Control>,
+    report: Mutex<JobReport>,
+}
+
+struct Control {
+    state: JobState,
+    started: bool,
+    /// Whether the job is in the worker queue or held by a worker. A paused job gives
+    /// its worker back and is sent to the queue again when resumed or cancelled.
+    scheduled: bool,
+    /// Whether a worker is running the job right now.
+    held: bool,
+    /// Index of the first source not processed yet.
+    next_item: usize,
+}
+
+/// What the worker holding a job does before its next item.
+enum Step {
+    Continue,
+    Pause,
+    Cancel,
+}
+
+impl Job {
+    fn new(id: String, request: JobRequest, policy: PathPolicy) -> Job {
+        let report = JobReport {
+            progress: JobProgress {
+                job_id: id.clone(),
+                kind: request.kind,
+                state: JobState::Queued,
+                total_items: request.sources.len() as u64,
+                processed_items: 0,
+                total_bytes: 0,
+                processed_bytes: 0,
+                current_path: None,
+            },
+            items: Vec::new(),
+        };
+
+        Job {
+            id,
+            request,
+            policy,
+            control: Mutex::new(Control {
+                state: JobState::Queued,
+                started: false,
+                scheduled: true,
+                held: false,
+                next_item: 0,
+            }),
+            report: Mutex::new(report),
+        }
+    }
+
+    pub fn state(&self) -> JobState {
+        self.control
+            .lock()
+            .map(|control| control.state)
+            .unwrap_or(JobState::Failed)
+    }
+
+    pub fn progress(&self) -> JobProgress {
+        let mut progress = match self.report.lock() {
+            Ok(report) => report.progress.clone(),
+            Err(poisoned) => poisoned.into_inner().progress.clone(),
+        };
+        progress.state = self.state();
+        progress
+    }
+
+    pub fn report(&self) -> JobReport {
+        let mut report = self
+            .report
+            .lock()
+            .map(|report| report.clone())
+            .unwrap_or_else(|poisoned| poisoned.into_inner().clone());
+        report.progress.state = self.state();
+        report
+    }
+
+    pub fn pause(&self) -> Result<(), String> {
+        let mut control = self.control.lock().map_err(|_| "Job is unavailable")?;
+        match control.state {
+            JobState::Queued | JobState::Running => {
+                control.state = JobState::Paused;
+                Ok(())
+            }
+            state => Err(format!("Job cannot change state while {:?}", state)),
+        }
+    }
+
+    /// Returns whether the job has to be sent to the worker queue again.
+    fn resume(&self) -> Result<bool, String> {
+        let mut control = self.control.lock().map_err(|_| "Job is unavailable")?;
+        if control.state != JobState::Paused {
+            return Err(format!("Job cannot change state while {:?}", control.state));
+        }
+        // A worker that has not noticed the pause yet simply carries on.
+        control.state = if control.held {
+            JobState::Running
+        } else {
+            JobState::Queued
+        };
+        Ok(!std::mem::replace(&mut control.scheduled, true))
+    }
+
+    /// Returns whether the job has to be sent to the worker queue again, so a worker
+    /// reports its remaining items as cancelled.
+    fn cancel(&self) -> Result<bool, String> {
+        let mut control = self.control.lock().map_err(|_| "Job is unavailable")?;
+        if control.state.is_finished() {
+            return Err(format!("Job cannot change state while {:?}", control.state));
+        }
+        control.state = JobState::Cancelled;
+        Ok(!std::mem::replace(&mut control.scheduled, true))
+    }
+
+    /// Marks the job as started. Returns false when a worker already ran it before it
+    /// was paused.
+    fn start(&self) -> bool {
+        self.control
+            .lock()
+            .map(|mut control| !std::mem::replace(&mut control.started, true))
+            .unwrap_or(false)
+    }
+
+    /// Decides how the worker goes on. A paused job is released, so the worker can pick
+    /// up the next one from the queue.
+    fn step(&self) -> Step {
+        let Ok(mut control) = self.control.lock() else {
+            return Step::Cancel;
+        };
+        match control.state {
+            JobState::Queued | JobState::Running => {
+                control.state = JobState::Running;
+                control.held = true;
+                Step::Continue
+            }
+            JobState::Paused => {
+                control.scheduled = false;
+                control.held = false;
+                Step::Pause
+            }
+            _ => Step::Cancel,
+        }
+    }
+
+    /// Whether the transfer in progress may go on. Pausing or cancelling stops it after
+    /// the chunk it is writing.
+    fn checkpoint(&self) -> bool {
+        self.state() == JobState::Running
+    }
+
+    fn next_item(&self) -> usize {
+        self.control
+            .lock()
+            .map(|control| control.next_item)
+            .unwrap_or(usize::MAX)
+    }
+
+    fn advance(&self) {
+        if let Ok(mut control) = self.control.lock() {
+            control.next_item += 1;
+        }
+    }
+
+    fn finish(&self, state: JobState) {
+        if let Ok(mut control) = self.control.lock()
+            && control.state != JobState::Cancelled
+        {
+            control.state = state;
+        }
+    }
+}
+
+/// Runs copy, move and trash jobs on a small pool of worker threads, so long
+/// transfers neither block the UI nor each other beyond `WORKER_COUNT`.
+pub struct JobManager {
+    jobs: Mutex<Vec<Arc<Job>>>,
+    queue: Sender<Arc<Job>>,
+}
+
+impl JobManager {
+    pub fn start(handle: &AppHandle) -> JobManager {
+        let (queue, receiver) = mpsc::channel::<Arc<Job>>();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        for index in 0..WORKER_COUNT {
+            let handle = handle.clone();
+            let receiver = Arc::clone(&receiver);
+            let spawned = thread::Builder::new()
+                .name(format!("job-worker-{}", index))
+                .spawn(move || run_worker(handle, receiver));
+
+            if let Err(e) = spawned {
+                log::error!("Failed to start job worker: {}", e);
+            }
+        }
+
+        JobManager {
+            jobs: Mutex::new(Vec::new()),
+            queue,
+        }
+    }
+
+    pub fn enqueue(
+        &self,
+        id: String,
+        request: JobRequest,
+        policy: PathPolicy,
+    ) -> Result<(), String> {
+        let job = Arc::new(Job::new(id, request, policy));
+
+        let mut jobs = self.jobs.lock().map_err(|_| "Job queue is unavailable")?;
+        jobs.push(Arc::clone(&job));
+
+        let finished = jobs.iter().filter(|job| job.state().is_finished()).count();
+        if finished > FINISHED_JOBS_KEPT {
+            let mut excess = finished - FINISHED_JOBS_KEPT;
+            jobs.retain(|job| {
+                let drop = excess > 0 && job.state().is_finished();
+                if drop {
+                    excess -= 1;
+                }
+                !drop
+            });
+        }
+
+        self.send(job)
+    }
+
+    pub fn get(&self, id: &str) -> Result<Arc<Job>, String> {
+        self.jobs
+            .lock()
+            .map_err(|_| "Job queue is unavailable")?
+            .iter()
+            .find(|job| job.id == id)
+            .cloned()
+            .ok_or_else(|| "Job not found".to_string())
+    }
+
+    pub fn list(&self) -> Vec<JobProgress> {
+        self.jobs
+            .lock()
+            .map(|jobs| jobs.iter().map(|job| job.progress()).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn resume(&self, id: &str) -> Result<(), String> {
+        let job = self.get(id)?;
+        if job.resume()? {
+            self.send(job)?;
+        }
+        Ok(())
+    }
+
+    pub fn cancel(&self, id: &str) -> Result<(), String> {
+        let job = self.get(id)?;
+        if job.cancel()? {
+            self.send(job)?;
+        }
+        Ok(())
+    }
+
+    fn send(&self, job: Arc<Job>) -> Result<(), String> {
+        self.queue
+            .send(job)
+            .map_err(|_| "Job queue is unavailable".to_string())
+    }
+}
+
+fn run_worker(handle: AppHandle, receiver: Arc<Mutex<Receiver<Arc<Job>>>>) {
+    loop {
+        let job = match receiver.lock() {
+            Ok(receiver) => receiver.recv(),
+            Err(_) => return,
+        };
+        let Ok(job) = job else {
+            return;
+        };
+
+        worker::run(&handle, &job);
+    }
+}
@@ -0,0 +1,10 @@
+use tauri::Manager;
+
+use crate::jobs::JobManager;
+
+/// Pauses a job after the chunk it is writing. The item in progress is rolled back and
+/// the worker moves on to other jobs; resuming queues the job again from that item.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn pause_job(handle: tauri::AppHandle, job_id: String) -> Result<(), String> {
+    handle.state::<JobManager>().get(&job_id)?.pause()
+}
@@ -0,0 +1,8 @@
+use tauri::Manager;
+
+use crate::jobs::JobManager;
+
+#[tauri::command(rename_all = "snake_case")]
+pub async fn resume_job(handle: tauri::AppHandle, job_id: String) -> Result<(), String> {
+    handle.state::<JobManager>().resume(&job_id)
+}
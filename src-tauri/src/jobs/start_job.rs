@@ -0,0 +1,26 @@
+use tauri::Manager;
+use uuid::Uuid;
+
+use crate::jobs::{JobKind, JobManager, JobRequest};
+use crate::utils::path_policy::PathPolicy;
+
+/// Queues a copy, move or trash job and returns its id. Progress arrives through
+/// `job-progress` events and the per-item report through `job-finished`.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn start_job(handle: tauri::AppHandle, request: JobRequest) -> Result<String, String> {
+    if request.sources.is_empty() {
+        return Err("No files selected".into());
+    }
+    if request.kind != JobKind::Trash && request.destination.is_none() {
+        return Err("No destination folder".into());
+    }
+
+    let policy = PathPolicy::load(&handle).await;
+    let job_id = Uuid::new_v4().to_string();
+
+    handle
+        .state::<JobManager>()
+        .enqueue(job_id.clone(), request, policy)?;
+
+    Ok(job_id)
+}
@@ -0,0 +1,330 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+use crate::files::transfer::{self, ConflictPolicy};
+use crate::jobs::{ItemReport, ItemStatus, Job, JobKind, JobReport, JobState, Step};
+use crate::journal::{self, OperationKind};
+use crate::library::index;
+use crate::utils::db;
+use crate::utils::path_policy::Operation;
+
+const PROGRESS_EVENT: &str = "job-progress";
+const FINISHED_EVENT: &str = "job-finished";
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+pub fn run(handle: &AppHandle, job: &Job) {
+    let sources = job.request.sources.clone();
+
+    if job.start() {
+        let total_bytes = sources
+            .iter()
+            .map(|source| transfer::tree_size(Path::new(source)))
+            .sum();
+        update(job, |report| report.progress.total_bytes = total_bytes);
+    }
+
+    let mut last_emit = Instant::now();
+
+    loop {
+        let index = job.next_item();
+        let Some(source) = sources.get(index) else {
+            break;
+        };
+        match job.step() {
+            Step::Continue => {}
+            // Picked up again from `next_item` once resumed.
+            Step::Pause => {
+                emit_progress(handle, job);
+                return;
+            }
+            Step::Cancel => break,
+        }
+        emit_progress(handle, job);
+
+        let item_size = transfer::tree_size(Path::new(source));
+        let base = job.progress().processed_bytes;
+        update(job, |report| {
+            report.progress.current_path = Some(source.clone());
+        });
+
+        let mut on_progress = |bytes: u64| {
+            update(job, |report| report.progress.processed_bytes += bytes);
+            if last_emit.elapsed() >= PROGRESS_INTERVAL {
+                emit_progress(handle, job);
+                last_emit = Instant::now();
+            }
+            job.checkpoint()
+        };
+
+        let item = match job.request.kind {
+            JobKind::Copy | JobKind::Move => transfer_item(handle, job, source, &mut on_progress),
+            JobKind::Trash => trash_item(handle, job, source),
+        };
+
+        // An item interrupted by a pause was rolled back and is transferred again.
+        if item.status == ItemStatus::Cancelled && job.state() != JobState::Cancelled {
+            update(job, |report| report.progress.processed_bytes = base);
+            continue;
+        }
+
+        update(job, |report| {
+            report.progress.processed_items += 1;
+            report.progress.processed_bytes = base + item_size;
+        });
+        record(job, item);
+        job.advance();
+    }
+
+    if job.state() == JobState::Cancelled {
+        for source in sources.iter().skip(job.next_item()) {
+            record(job, cancelled(source));
+        }
+        finish(handle, job, JobState::Cancelled);
+        return;
+    }
+
+    let failed = job
+        .report()
+        .items
+        .iter()
+        .any(|item| item.status == ItemStatus::Failed);
+    let state = if failed {
+        JobState::Failed
+    } else {
+        JobState::Completed
+    };
+    finish(handle, job, state);
+}
+
+fn transfer_item(
+    handle: &AppHandle,
+    job: &Job,
+    source: &str,
+    on_progress: &mut transfer::OnProgress,
+) -> ItemReport {
+    let operation = match job.request.kind {
+        JobKind::Move => Operation::Delete,
+        _ => Operation::Copy,
+    };
+
+    let prepared = prepare_transfer(job, source, operation);
+    let (source_path, target) = match prepared {
+        Ok(Some(paths)) => paths,
+        Ok(None) => return item(source, None, ItemStatus::Skipped, None),
+        Err(e) => return item(source, None, ItemStatus::Failed, Some(e)),
+    };
+
+    // An existing entry is only replaced once the transfer into its staging path has
+    // succeeded, so a failed or cancelled overwrite leaves it untouched.
+    let replaces = fs::symlink_metadata(&target).is_ok();
+    let written = if replaces {
+        transfer::staging_path(&target)
+    } else {
+        target.clone()
+    };
+
+    let result = match job.request.kind {
+        JobKind::Move => transfer::move_tree(&source_path, &written, on_progress),
+        _ => transfer::copy_tree(&source_path, &written, on_progress),
+    };
+    let result = match result {
+        Ok(()) if replaces => replace_existing(handle, job, &source_path, &written, &target),
+        result => result,
+    };
+    let destination = Some(target.to_string_lossy().to_string());
+
+    match result {
+        Ok(()) => {
+            record_done(
+                handle,
+                job,
+                &source_path.to_string_lossy(),
+                destination.as_deref(),
+            );
+            item(source, destination, ItemStatus::Done, None)
+        }
+        Err(e) if e.kind() == io::ErrorKind::Interrupted => {
+            item(source, None, ItemStatus::Cancelled, None)
+        }
+        Err(e) => item(
+            source,
+            destination,
+            ItemStatus::Failed,
+            Some(format!("Failed to transfer: {}", e)),
+        ),
+    }
+}
+
+/// Sends the entry at `target` to the trash, journaled under the job so an undo brings
+/// it back, and moves the transferred item from `staged` into its place. On failure
+/// the transfer is rolled back: a moved item goes back to `source`, a copy is removed.
+fn replace_existing(
+    handle: &AppHandle,
+    job: &Job,
+    source: &Path,
+    staged: &Path,
+    target: &Path,
+) -> io::Result<()> {
+    let swapped = trash::delete(target)
+        .map_err(|e| io::Error::other(format!("Failed to trash the replaced item: {}", e)))
+        .and_then(|()| {
+            let replaced = target.to_string_lossy();
+            tauri::async_runtime::block_on(async {
+                if let Ok(pool) = db::pool(handle).await
+                    && let Err(e) =
+                        journal::record(&pool, &job.id, OperationKind::Trash, &replaced, None).await
+                {
+                    log::warn!("{}", e);
+                }
+            });
+            fs::rename(staged, target)
+        });
+
+    if swapped.is_err() {
+        let rolled_back = match job.request.kind {
+            JobKind::Move => transfer::move_tree(staged, source, &mut |_| true),
+            _ => transfer::remove(staged),
+        };
+        if let Err(e) = rolled_back {
+            log::warn!("Failed to roll back {}: {}", staged.display(), e);
+        }
+    }
+    swapped
+}
+
+/// Checks both ends of a transfer against the path policy and applies the conflict
+/// policy. Returns `None` when the item is skipped.
+fn prepare_transfer(
+    job: &Job,
+    source: &str,
+    operation: Operation,
+) -> Result<Option<(PathBuf, PathBuf)>, String> {
+    let destination = job
+        .request
+        .destination
+        .as_deref()
+        .ok_or("No destination folder")?;
+
+    // A symlink is moved or named as itself; a copy reads what it points to, so that
+    // is what the policy checks.
+    let source_path = job.policy.check_entry(Path::new(source), operation)?;
+    if operation == Operation::Copy {
+        job.policy.check(&source_path, operation)?;
+    }
+    if fs::symlink_metadata(&source_path).is_err() {
+        return Err("File or directory does not exist".into());
+    }
+    let name = source_path.file_name().ok_or("Invalid source path")?;
+
+    let destination = job.policy.check(Path::new(destination), Operation::Write)?;
+    if !destination.is_dir() {
+        return Err("Destination is not a folder".into());
+    }
+
+    let target = destination.join(name);
+    transfer::check_not_nested(&source_path, &target)?;
+
+    // Copying into the source's own folder must never overwrite the source itself.
+    let conflict = job.request.conflict;
+    if target == source_path
+        && (conflict != ConflictPolicy::Rename || job.request.kind != JobKind::Copy)
+    {
+        return Ok(None);
+    }
+
+    Ok(transfer::resolve_conflict(&target, conflict).map(|target| (source_path, target)))
+}
+
+fn trash_item(handle: &AppHandle, job: &Job, source: &str) -> ItemReport {
+    let trashed = job
+        .policy
+        .check_entry(Path::new(source), Operation::Delete)
+        .and_then(|path| {
+            if fs::symlink_metadata(&path).is_err() {
+                return Err("File or directory does not exist".to_string());
+            }
+            trash::delete(&path).map_err(|e| format!("Failed to trash: {}", e))?;
+            Ok(path)
+        });
+
+    match trashed {
+        Ok(path) => {
+            record_done(handle, job, &path.to_string_lossy(), None);
+            item(source, None, ItemStatus::Done, None)
+        }
+        Err(e) => item(source, None, ItemStatus::Failed, Some(e)),
+    }
+}
+
+/// Journals a finished item under the job's id, and follows moves and trashes in the
+/// `files` table so indexed paths do not go stale.
+fn record_done(handle: &AppHandle, job: &Job, from: &str, to: Option<&str>) {
+    let kind = match job.request.kind {
+        JobKind::Copy => OperationKind::Copy,
+        JobKind::Move => OperationKind::Move,
+        JobKind::Trash => OperationKind::Trash,
+    };
+
+    tauri::async_runtime::block_on(async {
+        let Ok(pool) = db::pool(handle).await else {
+            return;
+        };
+
+        let indexed = match (job.request.kind, to) {
+            (JobKind::Move, Some(to)) => index::record_move(&pool, from, to).await,
+            (JobKind::Trash, _) => index::mark_missing_under(&pool, from).await.map(|_| ()),
+            _ => Ok(()),
+        };
+        if let Err(e) = indexed {
+            log::warn!("{}", e);
+        }
+
+        if let Err(e) = journal::record(&pool, &job.id, kind, from, to).await {
+            log::warn!("{}", e);
+        }
+    });
+}
+
+fn item(
+    source: &str,
+    destination: Option<String>,
+    status: ItemStatus,
+    error: Option<String>,
+) -> ItemReport {
+    ItemReport {
+        source: source.to_string(),
+        destination,
+        status,
+        error,
+    }
+}
+
+fn cancelled(source: &str) -> ItemReport {
+    item(source, None, ItemStatus::Cancelled, None)
+}
+
+fn update(job: &Job, change: impl FnOnce(&mut JobReport)) {
+    if let Ok(mut report) = job.report.lock() {
+        change(&mut report);
+    }
+}
+
+fn record(job: &Job, item: ItemReport) {
+    update(job, |report| report.items.push(item));
+}
+
+fn finish(handle: &AppHandle, job: &Job, state: JobState) {
+    job.finish(state);
+    update(job, |report| report.progress.current_path = None);
+
+    let report = job.report();
+    let _ = handle.emit(PROGRESS_EVENT, &report.progress);
+    let _ = handle.emit(FINISHED_EVENT, &report);
+}
+
+fn emit_progress(handle: &AppHandle, job: &Job) {
+    let _ = handle.emit(PROGRESS_EVENT, &job.progress());
+}
@@ -0,0 +1,28 @@
+use crate::journal::{OperationRecord, OperationRow, from_row};
+use crate::utils::db;
+
+const DEFAULT_LIMIT: i64 = 100;
+
+/// Returns the most recent journal entries, newest first.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn list_operations(
+    handle: tauri::AppHandle,
+    limit: Option<i64>,
+) -> Result<Vec<OperationRecord>, String> {
+    let pool = db::pool(&handle).await?;
+
+    let rows: Vec<OperationRow> = sqlx::query_as(
+        r#"
+        SELECT id, batch_id, kind, source_path, target_path, undone, undo_error, created_at
+        FROM operations
+        ORDER BY id DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(limit.unwrap_or(DEFAULT_LIMIT).max(1))
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| format!("Failed to read operations: {}", e))?;
+
+    Ok(rows.into_iter().filter_map(from_row).collect())
+}
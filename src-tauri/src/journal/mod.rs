@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Sqlite};
+use uuid::Uuid;
+
+use crate::utils::db;
+
+pub mod list_operations;
+pub mod undo_last_operation;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationKind {
+    Trash,
+    Move,
+    Rename,
+    Copy,
+}
+
+impl OperationKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OperationKind::Trash => "trash",
+            OperationKind::Move => "move",
+            OperationKind::Rename => "rename",
+            OperationKind::Copy => "copy",
+        }
+    }
+
+    fn parse(kind: &str) -> Option<OperationKind> {
+        match kind {
+            "trash" => Some(OperationKind::Trash),
+            "move" => Some(OperationKind::Move),
+            "rename" => Some(OperationKind::Rename),
+            "copy" => Some(OperationKind::Copy),
+            _ => None,
+        }
+    }
+}
+
+/// A row of the `operations` table. Entries sharing a `batch_id` were made by one
+/// command or job and are undone together.
+#[derive(Debug, Clone, Serialize)]
+pub struct OperationRecord {
+    pub id: i64,
+    pub batch_id: String,
+    pub kind: OperationKind,
+    pub source_path: String,
+    pub target_path: Option<String>,
+    pub undone: u8,
+    /// Why the entry was skipped instead of undone, when it can never be undone, such
+    /// as a trashed item that was purged from the trash since.
+    pub undo_error: Option<String>,
+    pub created_at: String,
+}
+
+pub fn new_batch_id() -> String {
+    Uuid::new_v4().to_string()
+}
+
+pub async fn record(
+    pool: &Pool<Sqlite>,
+    batch_id: &str,
+    kind: OperationKind,
+    source: &str,
+    target: Option<&str>,
+) -> Result<(), String> {
+    sqlx::query(
+        "INSERT INTO operations (batch_id, kind, source_path, target_path) VALUES (?, ?, ?, ?)",
+    )
+    .bind(batch_id)
+    .bind(kind.as_str())
+    .bind(source)
+    .bind(target)
+    .execute(pool)
+    .await
+    .map(|_| ())
+    .map_err(|e| format!("Failed to record operation: {}", e))
+}
+
+/// Journals an operation that already happened on disk. A failure only costs the
+/// ability to undo it, so it is logged instead of failing the command.
+pub async fn log_operation(
+    handle: &tauri::AppHandle,
+    batch_id: &str,
+    kind: OperationKind,
+    source: &str,
+    target: Option<&str>,
+) {
+    let Ok(pool) = db::pool(handle).await else {
+        return;
+    };
+
+    if let Err(e) = record(&pool, batch_id, kind, source, target).await {
+        log::warn!("{}", e);
+    }
+}
+
+type OperationRow = (
+    i64,
+    String,
+    String,
+    String,
+    Option<String>,
+    i64,
+    Option<String>,
+    String,
+);
+
+fn from_row(row: OperationRow) -> Option<OperationRecord> {
+    let (id, batch_id, kind, source_path, target_path, undone, undo_error, created_at) = row;
+    Some(OperationRecord {
+        id,
+        batch_id,
+        kind: OperationKind::parse(&kind)?,
+        source_path,
+        target_path,
+        undone: undone as u8,
+        undo_error,
+        created_at,
+    })
+}
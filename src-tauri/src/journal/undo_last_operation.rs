@@ -0,0 +1,217 @@
+use sqlx::{Pool, Sqlite};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::files::move_path::relocate;
+use crate::journal::{OperationKind, OperationRecord, OperationRow, from_row};
+use crate::library::index;
+use crate::utils::db;
+use crate::utils::path_policy::{Operation, PathPolicy};
+
+/// An `operations` row followed by its time as a Unix timestamp.
+type PendingRow = (
+    i64,
+    String,
+    String,
+    String,
+    Option<String>,
+    i64,
+    Option<String>,
+    String,
+    i64,
+);
+
+enum UndoError {
+    /// Stops the undo, which can be retried once the cause is dealt with, such as a
+    /// file in the way of a restore.
+    Blocked(String),
+    /// The entry can never be undone. It is skipped so older entries stay reachable.
+    Lost(String),
+}
+
+impl From<String> for UndoError {
+    fn from(e: String) -> UndoError {
+        UndoError::Blocked(e)
+    }
+}
+
+impl From<&str> for UndoError {
+    fn from(e: &str) -> UndoError {
+        UndoError::Blocked(e.to_string())
+    }
+}
+
+/// Undoes the most recent batch that is not undone yet: trashed items are restored
+/// from the system trash, moves and renames are reversed and copies are sent to the
+/// trash. Entries are undone newest first and marked one at a time, so a batch that
+/// fails halfway can be retried. Entries that can never be undone, such as items purged
+/// from the trash, are marked with an `undo_error` and skipped. Returns the entries
+/// that were undone or skipped.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn undo_last_operation(handle: tauri::AppHandle) -> Result<Vec<OperationRecord>, String> {
+    let pool = db::pool(&handle).await?;
+
+    let batch_id: Option<String> = sqlx::query_scalar(
+        "SELECT batch_id FROM operations WHERE undone = 0 ORDER BY id DESC LIMIT 1",
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| format!("Failed to read operations: {}", e))?;
+    let batch_id = batch_id.ok_or("Nothing to undo")?;
+
+    let rows: Vec<PendingRow> = sqlx::query_as(
+        r#"
+        SELECT id, batch_id, kind, source_path, target_path, undone, undo_error, created_at,
+            CAST(strftime('%s', created_at) AS INTEGER)
+        FROM operations
+        WHERE batch_id = ? AND undone = 0
+        ORDER BY id DESC
+        "#,
+    )
+    .bind(&batch_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| format!("Failed to read operations: {}", e))?;
+
+    let policy = PathPolicy::load(&handle).await;
+    let mut undone = Vec::new();
+
+    for (
+        id,
+        batch_id,
+        kind,
+        source_path,
+        target_path,
+        flag,
+        undo_error,
+        created_at,
+        performed_at,
+    ) in rows
+    {
+        let row: OperationRow = (
+            id,
+            batch_id,
+            kind,
+            source_path,
+            target_path,
+            flag,
+            undo_error,
+            created_at,
+        );
+        let Some(mut record) = from_row(row) else {
+            continue;
+        };
+
+        match undo(&handle, &pool, &policy, &record, performed_at).await {
+            Ok(()) => {}
+            Err(UndoError::Blocked(e)) => return Err(e),
+            Err(UndoError::Lost(e)) => record.undo_error = Some(e),
+        }
+
+        sqlx::query(
+            "UPDATE operations SET undone = 1, undo_error = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+        )
+        .bind(&record.undo_error)
+        .bind(record.id)
+        .execute(&pool)
+        .await
+        .map_err(|e| format!("Failed to update operation: {}", e))?;
+
+        record.undone = 1;
+        undone.push(record);
+    }
+
+    Ok(undone)
+}
+
+async fn undo(
+    handle: &tauri::AppHandle,
+    pool: &Pool<Sqlite>,
+    policy: &PathPolicy,
+    record: &OperationRecord,
+    performed_at: i64,
+) -> Result<(), UndoError> {
+    let source = PathBuf::from(&record.source_path);
+
+    match record.kind {
+        OperationKind::Trash => {
+            policy.check(&source, Operation::Write)?;
+            if fs::symlink_metadata(&source).is_ok() {
+                return Err(format!("{} already exists", record.source_path).into());
+            }
+
+            let original = source.clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                restore_from_trash(&original, performed_at)
+            })
+            .await
+            .map_err(|e| format!("Failed to restore from trash: {}", e))??;
+
+            index::mark_present_under(pool, &record.source_path).await?;
+        }
+        OperationKind::Move | OperationKind::Rename => {
+            let target = record
+                .target_path
+                .as_deref()
+                .ok_or("Operation has no target path")?;
+            let target = policy.check(Path::new(target), Operation::Delete)?;
+            policy.check(&source, Operation::Write)?;
+
+            if fs::symlink_metadata(&target).is_err() {
+                return Err(UndoError::Lost(format!(
+                    "{} no longer exists",
+                    target.display()
+                )));
+            }
+            if fs::symlink_metadata(&source).is_ok() {
+                return Err(format!("{} already exists", record.source_path).into());
+            }
+
+            relocate(handle, target, source).await?;
+        }
+        OperationKind::Copy => {
+            let target = record
+                .target_path
+                .as_deref()
+                .ok_or("Operation has no target path")?;
+            let target = policy.check(Path::new(target), Operation::Delete)?;
+
+            // A copy that was removed since has nothing left to undo.
+            if fs::symlink_metadata(&target).is_err() {
+                return Ok(());
+            }
+
+            let trashed = target.clone();
+            tauri::async_runtime::spawn_blocking(move || trash::delete(&trashed))
+                .await
+                .map_err(|e| format!("Failed to trash copy: {}", e))?
+                .map_err(|e| format!("Failed to trash copy: {}", e))?;
+
+            index::mark_missing_under(pool, &target.to_string_lossy()).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Restores the trashed item that came from `original`, picking the one deleted
+/// closest to `deleted_at` when the same path was trashed more than once.
+#[cfg(not(target_os = "macos"))]
+fn restore_from_trash(original: &Path, deleted_at: i64) -> Result<(), UndoError> {
+    let item = trash::os_limited::list()
+        .map_err(|e| format!("Failed to read the trash: {}", e))?
+        .into_iter()
+        .filter(|item| item.original_path() == original)
+        .min_by_key(|item| (item.time_deleted - deleted_at).abs())
+        .ok_or_else(|| UndoError::Lost("The item is no longer in the trash".into()))?;
+
+    trash::os_limited::restore_all([item])
+        .map_err(|e| format!("Failed to restore from trash: {}", e).into())
+}
+
+#[cfg(target_os = "macos")]
+fn restore_from_trash(_original: &Path, _deleted_at: i64) -> Result<(), UndoError> {
+    Err(UndoError::Lost(
+        "Restoring from the trash is not supported on macOS".into(),
+    ))
+}
@@ -4,16 +4,92 @@ use tauri::Manager;
 use tauri::path::BaseDirectory;
 
 mod datasets;
+mod documents;
+mod downloads;
+mod encryption;
 mod files;
+mod jobs;
+mod journal;
+mod library;
+mod media;
 mod migrations;
+mod security;
+mod thumbnails;
+mod upscale;
 mod utils;
+mod vaults;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let commands: fn(tauri::ipc::Invoke) -> bool = tauri::generate_handler![
+        documents::extract_document::extract_document,
+        downloads::add_download::add_download,
+        downloads::cancel_download::cancel_download,
+        downloads::list_downloads::list_downloads,
+        downloads::pause_download::pause_download,
+        downloads::remove_download::remove_download,
+        downloads::resume_download::resume_download,
+        encryption::encrypt_database::encrypt_database,
+        encryption::get_database_status::get_database_status,
+        encryption::rekey_database::rekey_database,
+        encryption::unlock_database::unlock_database,
+        files::highlight_path::highlight_path,
+        files::get_available_disks::get_available_disks,
+        files::get_downloads_dir::get_downloads_dir,
+        files::get_folders::get_folders,
+        files::get_parent_path::get_parent_path,
+        files::get_path_segments::get_path_segments,
+        files::cancel_fetch_files::cancel_fetch_files,
+        files::cancel_search_files::cancel_search_files,
+        files::copy_file::copy_file,
+        files::fetch_files::fetch_files,
+        files::file_details::file_details,
+        files::move_path::move_path,
+        files::open_external_url::open_external_url,
+        files::rename_path::rename_path,
+        files::search_files::search_files,
+        files::trash::trash,
+        jobs::cancel_job::cancel_job,
+        jobs::get_job_report::get_job_report,
+        jobs::list_jobs::list_jobs,
+        jobs::pause_job::pause_job,
+        jobs::resume_job::resume_job,
+        jobs::start_job::start_job,
+        journal::list_operations::list_operations,
+        journal::undo_last_operation::undo_last_operation,
+        library::find_duplicates::find_duplicates,
+        library::remove_library_root::remove_library_root,
+        library::scan_library::scan_library,
+        library::search_library::search_library,
+        media::find_similar_images::find_similar_images,
+        media::probe_image::probe_image,
+        media::probe_image::probe_images,
+        media::probe_video::probe_video,
+        media::probe_video::probe_videos,
+        security::get_lock_status::get_lock_status,
+        security::lock_app::lock_app,
+        security::record_activity::record_activity,
+        security::set_auto_lock::set_auto_lock,
+        security::set_lock_password::set_lock_password,
+        security::verify_lock_password::verify_lock_password,
+        thumbnails::clear_thumbnail_cache::clear_thumbnail_cache,
+        thumbnails::get_thumbnail::get_thumbnail,
+        upscale::cancel_upscale::cancel_upscale,
+        upscale::list_upscales::list_upscales,
+        upscale::start_upscale::start_upscale,
+        vaults::add_vault::add_vault,
+        vaults::dismount_vault::dismount_vault,
+        vaults::list_vaults::list_vaults,
+        vaults::mount_vault::mount_vault,
+        vaults::remove_vault::remove_vault,
+        datasets::download_dataset_image::download_dataset_image,
+        datasets::fetch_datasets::fetch_datasets,
+    ];
+
     tauri::Builder::default()
         .plugin(
             tauri_plugin_sql::Builder::new()
-                .add_migrations("sqlite:archv3.db", migrations::get_migrations())
+                .add_migrations(utils::db::DB_URL, migrations::get_migrations())
                 .build(),
         )
         .plugin(tauri_plugin_fs::init())
@@ -22,6 +98,8 @@ pub fn run() {
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .setup(|app| {
+            encryption::register_key_hook();
+
             if cfg!(debug_assertions) {
                 app.handle().plugin(
                     tauri_plugin_log::Builder::default()
@@ -41,21 +119,20 @@ pub fn run() {
                     .map_err(|e| format!("Failed to create cache directory: {}", e))?;
             }
 
+            app.manage(thumbnails::ThumbnailCache::new(&app_cache_dir)?);
+            app.manage(utils::cancellation::CancelRegistry::default());
+
+            let library_watcher = library::watcher::LibraryWatcher::start(app.handle())?;
+            app.manage(library_watcher);
+
+            app.manage(jobs::JobManager::start(app.handle()));
+            app.manage(security::LockManager::start(app.handle()));
+            app.manage(upscale::UpscaleManager::start(app.handle()));
+            app.manage(downloads::DownloadManager::start(app.handle()));
+
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![
-            files::highlight_path::highlight_path,
-            files::get_available_disks::get_available_disks,
-            files::get_folders::get_folders,
-            files::get_parent_path::get_parent_path,
-            files::get_path_segments::get_path_segments,
-            files::copy_file::copy_file,
-            files::fetch_files::fetch_files,
-            files::file_details::file_details,
-            files::trash::trash,
-            datasets::download_dataset_image::download_dataset_image,
-            datasets::fetch_datasets::fetch_datasets,
-        ])
+        .invoke_handler(move |invoke| security::guard(invoke, commands))
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -0,0 +1,34 @@
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Size of the blocks read from each end of a file for the partial hash.
+pub const PARTIAL_BLOCK: u64 = 64 * 1024;
+
+/// Hashes the first and last block of a file. Files that fit in two blocks are hashed
+/// whole, so their partial hash doubles as the full hash.
+pub fn partial_hash(path: &Path, size: u64) -> io::Result<String> {
+    if size <= PARTIAL_BLOCK * 2 {
+        return full_hash(path);
+    }
+
+    let mut file = File::open(path)?;
+    let mut block = vec![0; PARTIAL_BLOCK as usize];
+    let mut hasher = blake3::Hasher::new();
+
+    file.read_exact(&mut block)?;
+    hasher.update(&block);
+    file.seek(SeekFrom::End(-(PARTIAL_BLOCK as i64)))?;
+    file.read_exact(&mut block)?;
+    hasher.update(&block);
+    hasher.update(&size.to_le_bytes());
+
+    Ok(hasher.finalize().to_hex().to_string())
+}
+
+/// BLAKE3 hash of the whole file, as a hex string.
+pub fn full_hash(path: &Path) -> io::Result<String> {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update_reader(File::open(path)?)?;
+    Ok(hasher.finalize().to_hex().to_string())
+}
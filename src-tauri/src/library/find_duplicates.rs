@@ -0,0 +1,428 @@
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+use tauri::Emitter;
+use walkdir::WalkDir;
+
+use crate::library::content_hash::{self, PARTIAL_BLOCK};
+use crate::library::index::{self, UNDER_ROOT_CLAUSE};
+use crate::utils::db;
+use crate::utils::path_policy::{Operation, PathPolicy};
+
+const PROGRESS_EVENT: &str = "library-duplicates-progress";
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+const BATCH_SIZE: usize = 250;
+
+#[derive(Serialize, Clone, Default)]
+pub struct DuplicateProgress {
+    /// `scanning`, `partial_hash`, `full_hash` or `finished`.
+    pub stage: &'static str,
+    pub processed: u64,
+    pub total: u64,
+    pub bytes_hashed: u64,
+    pub skipped: u64,
+    pub current_path: Option<String>,
+    pub groups: u64,
+    pub finished: bool,
+}
+
+#[derive(Serialize)]
+pub struct DuplicateFile {
+    pub path: String,
+    pub mtime: u64,
+    /// Whether a `contents` row points at this file.
+    pub in_content: bool,
+}
+
+#[derive(Serialize)]
+pub struct DuplicateGroup {
+    pub hash: String,
+    pub size: u64,
+    pub files: Vec<DuplicateFile>,
+    /// The copy we suggest keeping when the others are removed.
+    pub keeper: String,
+    pub reclaimable_bytes: u64,
+}
+
+struct Candidate {
+    path: String,
+    size: u64,
+    mtime: u64,
+}
+
+struct CachedHash {
+    hash: String,
+    size: u64,
+    mtime: u64,
+}
+
+/// Finds files with identical contents under `roots`. Candidates are narrowed down by
+/// size, then by a hash of their first and last blocks, and only then hashed in full
+/// with BLAKE3. Full hashes are cached on indexed `files` rows, keyed by modification
+/// time, so later runs skip unchanged files. Progress is streamed through
+/// `library-duplicates-progress` events.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn find_duplicates(
+    handle: tauri::AppHandle,
+    roots: Vec<String>,
+) -> Result<Vec<DuplicateGroup>, String> {
+    if roots.is_empty() {
+        return Err("No folders to search".into());
+    }
+
+    let policy = PathPolicy::load(&handle).await;
+    for root in &roots {
+        let root_path = Path::new(root);
+        if !root_path.is_absolute() || !root_path.is_dir() {
+            return Err(format!("Invalid folder: {}", root));
+        }
+        policy.check(root_path, Operation::Read)?;
+    }
+
+    // Without a loaded database there is nothing to reuse or update, but the search
+    // itself still works.
+    let pool = db::pool(&handle).await.ok();
+    let (cache, in_content) = match &pool {
+        Some(pool) => load_index(pool, &roots).await?,
+        None => (HashMap::new(), HashSet::new()),
+    };
+
+    let emitter = handle.clone();
+    let (groups, hashed, mut reporter) = tauri::async_runtime::spawn_blocking(move || {
+        let mut reporter = Reporter::new(emitter);
+        let (groups, hashed) = search(&roots, &policy, &cache, &mut reporter);
+        (groups, hashed, reporter)
+    })
+    .await
+    .map_err(|e| format!("Duplicate search failed: {}", e))?;
+
+    if let Some(pool) = &pool
+        && let Err(e) = store_hashes(pool, &hashed).await
+    {
+        log::warn!("{}", e);
+    }
+
+    let mut groups: Vec<DuplicateGroup> = groups
+        .into_iter()
+        .map(|(hash, files)| build_group(hash, files, &in_content))
+        .collect();
+    groups.sort_by(|a, b| {
+        b.reclaimable_bytes
+            .cmp(&a.reclaimable_bytes)
+            .then_with(|| a.keeper.cmp(&b.keeper))
+    });
+
+    reporter.progress.groups = groups.len() as u64;
+    reporter.finish();
+
+    Ok(groups)
+}
+
+type HashedGroups = Vec<(String, Vec<Candidate>)>;
+
+/// Returns the duplicate groups keyed by full hash, and the freshly computed hashes
+/// that should be written back to the index.
+fn search(
+    roots: &[String],
+    policy: &PathPolicy,
+    cache: &HashMap<String, CachedHash>,
+    reporter: &mut Reporter,
+) -> (HashedGroups, Vec<(String, String, u64)>) {
+    let by_size = collect_candidates(roots, policy, reporter);
+
+    let candidates: Vec<Candidate> = by_size
+        .into_values()
+        .filter(|group| group.len() > 1)
+        .flatten()
+        .collect();
+
+    reporter.stage("partial_hash", candidates.len());
+    let mut by_partial: HashMap<(u64, String), Vec<Candidate>> = HashMap::new();
+    for candidate in candidates {
+        reporter.advance(&candidate.path);
+        let hash = match cache.get(&candidate.path) {
+            // A cached full hash of a small file is also its partial hash.
+            Some(cached) if is_fresh(cached, &candidate) && candidate.size <= PARTIAL_BLOCK * 2 => {
+                cached.hash.clone()
+            }
+            _ => match content_hash::partial_hash(Path::new(&candidate.path), candidate.size) {
+                Ok(hash) => {
+                    reporter.progress.bytes_hashed += candidate.size.min(PARTIAL_BLOCK * 2);
+                    hash
+                }
+                Err(_) => {
+                    reporter.progress.skipped += 1;
+                    continue;
+                }
+            },
+        };
+        by_partial
+            .entry((candidate.size, hash))
+            .or_default()
+            .push(candidate);
+    }
+
+    let candidates: Vec<((u64, String), Candidate)> = by_partial
+        .into_iter()
+        .filter(|(_, group)| group.len() > 1)
+        .flat_map(|(key, group)| group.into_iter().map(move |c| (key.clone(), c)))
+        .collect();
+
+    reporter.stage("full_hash", candidates.len());
+    let mut hashed = Vec::new();
+    let mut by_full: HashMap<String, Vec<Candidate>> = HashMap::new();
+    for ((size, partial), candidate) in candidates {
+        reporter.advance(&candidate.path);
+        let hash = match cache.get(&candidate.path) {
+            Some(cached) if is_fresh(cached, &candidate) => cached.hash.clone(),
+            _ if size <= PARTIAL_BLOCK * 2 => partial,
+            _ => match content_hash::full_hash(Path::new(&candidate.path)) {
+                Ok(hash) => {
+                    reporter.progress.bytes_hashed += size;
+                    hash
+                }
+                Err(_) => {
+                    reporter.progress.skipped += 1;
+                    continue;
+                }
+            },
+        };
+
+        if !cache
+            .get(&candidate.path)
+            .is_some_and(|cached| is_fresh(cached, &candidate))
+        {
+            hashed.push((candidate.path.clone(), hash.clone(), candidate.mtime));
+        }
+        by_full.entry(hash).or_default().push(candidate);
+    }
+
+    let groups: HashedGroups = by_full
+        .into_iter()
+        .filter(|(_, group)| group.len() > 1)
+        .collect();
+
+    (groups, hashed)
+}
+
+/// Walks every root and buckets regular, non-empty files by size. Hidden entries and
+/// paths the policy does not allow are skipped, and a file reachable twice (overlapping
+/// roots or hard links) only counts once, since deleting one of those frees nothing.
+fn collect_candidates(
+    roots: &[String],
+    policy: &PathPolicy,
+    reporter: &mut Reporter,
+) -> HashMap<u64, Vec<Candidate>> {
+    reporter.stage("scanning", 0);
+
+    let mut seen_paths: HashSet<PathBuf> = HashSet::new();
+    #[cfg(unix)]
+    let mut seen_inodes: HashSet<(u64, u64)> = HashSet::new();
+    let mut by_size: HashMap<u64, Vec<Candidate>> = HashMap::new();
+
+    for root in roots {
+        let entries = WalkDir::new(root).into_iter().filter_entry(|entry| {
+            entry.depth() == 0
+                || (!entry.file_name().to_string_lossy().starts_with('.')
+                    && policy.check(entry.path(), Operation::Read).is_ok())
+        });
+
+        for entry in entries {
+            let Ok(entry) = entry else {
+                reporter.progress.skipped += 1;
+                continue;
+            };
+            if !entry.file_type().is_file() || !seen_paths.insert(entry.path().to_path_buf()) {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                reporter.progress.skipped += 1;
+                continue;
+            };
+
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::MetadataExt;
+                if !seen_inodes.insert((metadata.dev(), metadata.ino())) {
+                    continue;
+                }
+            }
+
+            if metadata.len() == 0 {
+                continue;
+            }
+
+            let path = entry.path().to_string_lossy().to_string();
+            reporter.advance(&path);
+            by_size.entry(metadata.len()).or_default().push(Candidate {
+                path,
+                size: metadata.len(),
+                mtime: metadata
+                    .modified()
+                    .map(|time| {
+                        time.duration_since(UNIX_EPOCH)
+                            .unwrap_or_default()
+                            .as_secs()
+                    })
+                    .unwrap_or(0),
+            });
+        }
+    }
+
+    by_size
+}
+
+fn is_fresh(cached: &CachedHash, candidate: &Candidate) -> bool {
+    cached.mtime == candidate.mtime && cached.size == candidate.size
+}
+
+/// Picks the keeper: a copy that library content points at, then the oldest copy, then
+/// the shortest path.
+fn build_group(
+    hash: String,
+    mut files: Vec<Candidate>,
+    in_content: &HashSet<String>,
+) -> DuplicateGroup {
+    files.sort_by(|a, b| {
+        in_content
+            .contains(&b.path)
+            .cmp(&in_content.contains(&a.path))
+            .then(a.mtime.cmp(&b.mtime))
+            .then(a.path.len().cmp(&b.path.len()))
+            .then_with(|| a.path.cmp(&b.path))
+    });
+
+    let size = files[0].size;
+    DuplicateGroup {
+        hash,
+        size,
+        keeper: files[0].path.clone(),
+        reclaimable_bytes: size * (files.len() as u64 - 1),
+        files: files
+            .into_iter()
+            .map(|file| DuplicateFile {
+                in_content: in_content.contains(&file.path),
+                path: file.path,
+                mtime: file.mtime,
+            })
+            .collect(),
+    }
+}
+
+async fn load_index(
+    pool: &Pool<Sqlite>,
+    roots: &[String],
+) -> Result<(HashMap<String, CachedHash>, HashSet<String>), String> {
+    let mut cache = HashMap::new();
+    let mut in_content = HashSet::new();
+
+    for root in roots {
+        let rows: Vec<(String, i64, String, i64)> = sqlx::query_as(&format!(
+            "SELECT path, size, content_hash, content_hash_mtime FROM files
+             WHERE content_hash IS NOT NULL AND content_hash_mtime IS NOT NULL AND {}",
+            UNDER_ROOT_CLAUSE
+        ))
+        .bind(root)
+        .bind(index::root_prefix(root))
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to read cached hashes: {}", e))?;
+
+        cache.extend(rows.into_iter().map(|(path, size, hash, mtime)| {
+            (
+                path,
+                CachedHash {
+                    hash,
+                    size: size as u64,
+                    mtime: mtime as u64,
+                },
+            )
+        }));
+
+        let paths: Vec<String> = sqlx::query_scalar(&format!(
+            "SELECT f.path FROM files f JOIN contents c ON c.file_id = f.id WHERE {}",
+            UNDER_ROOT_CLAUSE.replace("path", "f.path")
+        ))
+        .bind(root)
+        .bind(index::root_prefix(root))
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to read library contents: {}", e))?;
+
+        in_content.extend(paths);
+    }
+
+    Ok((cache, in_content))
+}
+
+/// Caches full hashes on the matching `files` rows. Files outside the index are left
+/// alone.
+async fn store_hashes(pool: &Pool<Sqlite>, hashed: &[(String, String, u64)]) -> Result<(), String> {
+    for chunk in hashed.chunks(BATCH_SIZE) {
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        for (path, hash, mtime) in chunk {
+            sqlx::query("UPDATE files SET content_hash = ?, content_hash_mtime = ? WHERE path = ?")
+                .bind(hash)
+                .bind(*mtime as i64)
+                .bind(path)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| format!("Failed to cache content hash: {}", e))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to cache content hashes: {}", e))?;
+    }
+
+    Ok(())
+}
+
+struct Reporter {
+    handle: tauri::AppHandle,
+    progress: DuplicateProgress,
+    last_emit: Instant,
+}
+
+impl Reporter {
+    fn new(handle: tauri::AppHandle) -> Reporter {
+        Reporter {
+            handle,
+            progress: DuplicateProgress::default(),
+            last_emit: Instant::now(),
+        }
+    }
+
+    fn stage(&mut self, stage: &'static str, total: usize) {
+        self.progress.stage = stage;
+        self.progress.processed = 0;
+        self.progress.total = total as u64;
+        self.emit();
+    }
+
+    fn advance(&mut self, path: &str) {
+        self.progress.processed += 1;
+        if self.last_emit.elapsed() >= PROGRESS_INTERVAL {
+            self.progress.current_path = Some(path.to_string());
+            self.emit();
+        }
+    }
+
+    fn finish(&mut self) {
+        self.progress.stage = "finished";
+        self.progress.current_path = None;
+        self.progress.finished = true;
+        self.emit();
+    }
+
+    fn emit(&mut self) {
+        let _ = self.handle.emit(PROGRESS_EVENT, &self.progress);
+        self.last_emit = Instant::now();
+    }
+}
@@ -0,0 +1,327 @@
+use sqlx::query::QueryScalar;
+use sqlx::sqlite::SqliteArguments;
+use sqlx::{Executor, Pool, Sqlite, SqliteConnection};
+
+use crate::files::IFile;
+
+/// `files.file_type` only accepts these values, anything else is stored as `other`.
+const STORABLE_FILE_TYPES: &[&str] = &["video", "image", "binary", "document", "other"];
+
+/// `files.mime` is limited to 50 characters, which some Office MIME types exceed. Those
+/// are stored as a generic type; the precise one can be derived from the extension.
+const MAX_MIME_LENGTH: usize = 50;
+
+/// `files.extension` is limited to 50 characters. Longer ones come from names with a
+/// dot but no real extension, such as `Mr. Smith Goes to Washington`, and are dropped.
+const MAX_EXTENSION_LENGTH: usize = 50;
+
+pub struct IndexedFile {
+    pub id: i64,
+    pub size: i64,
+    pub mtime: i64,
+    pub missing: bool,
+}
+
+pub fn storable_file_type(file_type: &str) -> &str {
+    if STORABLE_FILE_TYPES.contains(&file_type) {
+        file_type
+    } else {
+        "other"
+    }
+}
+
+pub fn storable_mime(mime: &str) -> &str {
+    if mime.len() <= MAX_MIME_LENGTH {
+        mime
+    } else {
+        "application/octet-stream"
+    }
+}
+
+pub fn storable_extension(extension: &str) -> &str {
+    if extension.chars().count() <= MAX_EXTENSION_LENGTH {
+        extension
+    } else {
+        ""
+    }
+}
+
+/// SQL fragment matching `path` against a directory and everything below it, bound to
+/// the directory itself and the directory followed by a separator.
+pub const UNDER_ROOT_CLAUSE: &str = "(path = ?1 OR substr(path, 1, length(?2)) = ?2)";
+
+pub fn root_prefix(root: &str) -> String {
+    let separator = std::path::MAIN_SEPARATOR;
+    if root.ends_with(separator) {
+        root.to_string()
+    } else {
+        format!("{}{}", root, separator)
+    }
+}
+
+/// Inserts `file` or refreshes the row with the same path, clearing its missing flag.
+/// Returns the row id.
+pub async fn upsert_file<'e, E>(executor: E, file: &IFile) -> Result<i64, String>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    sqlx::query_scalar(
+        r#"
+        INSERT INTO files (path, name, size, mime, extension, ctime, mtime, file_type, missing)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, 0)
+        ON CONFLICT(path) DO UPDATE SET
+            name = excluded.name,
+            size = excluded.size,
+            mime = excluded.mime,
+            extension = excluded.extension,
+            ctime = excluded.ctime,
+            mtime = excluded.mtime,
+            file_type = excluded.file_type,
+            missing = 0,
+            updated_at = CURRENT_TIMESTAMP
+        RETURNING id
+        "#,
+    )
+    .bind(&file.path)
+    .bind(&file.name)
+    .bind(file.size as i64)
+    .bind(storable_mime(&file.mime))
+    .bind(storable_extension(&file.extension))
+    .bind(file.ctime as i64)
+    .bind(file.mtime as i64)
+    .bind(storable_file_type(&file.file_type))
+    .fetch_one(executor)
+    .await
+    .map_err(|e| format!("Failed to index {}: {}", file.path, e))
+}
+
+/// Flags `path` and, for directories, every row below it as missing.
+pub async fn mark_missing_under<'e, E>(executor: E, path: &str) -> Result<u64, String>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    sqlx::query(&format!(
+        "UPDATE files SET missing = 1, updated_at = CURRENT_TIMESTAMP WHERE missing = 0 AND {}",
+        UNDER_ROOT_CLAUSE
+    ))
+    .bind(path)
+    .bind(root_prefix(path))
+    .execute(executor)
+    .await
+    .map(|result| result.rows_affected())
+    .map_err(|e| format!("Failed to flag missing files: {}", e))
+}
+
+/// Clears the missing flag of `path` and everything below it, for items that came back.
+pub async fn mark_present_under<'e, E>(executor: E, path: &str) -> Result<u64, String>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    sqlx::query(&format!(
+        "UPDATE files SET missing = 0, updated_at = CURRENT_TIMESTAMP WHERE missing = 1 AND {}",
+        UNDER_ROOT_CLAUSE
+    ))
+    .bind(path)
+    .bind(root_prefix(path))
+    .execute(executor)
+    .await
+    .map(|result| result.rows_affected())
+    .map_err(|e| format!("Failed to flag restored files: {}", e))
+}
+
+/// Rewrites the rows for `from` (and everything below it) to live under `to`, keeping
+/// their ids so `contents.file_id` stays valid. Rows already recorded under `to`
+/// describe files the move replaced; their contents are unlinked before they are
+/// dropped so no content entry is cascaded away.
+pub async fn move_records(conn: &mut SqliteConnection, from: &str, to: &str) -> Result<(), String> {
+    let replaced = format!("SELECT id FROM files WHERE {}", UNDER_ROOT_CLAUSE);
+
+    sqlx::query(&format!(
+        "UPDATE contents SET file_id = NULL WHERE file_id IN ({})",
+        replaced
+    ))
+    .bind(to)
+    .bind(root_prefix(to))
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to unlink replaced files: {}", e))?;
+
+    sqlx::query(&format!("DELETE FROM files WHERE {}", UNDER_ROOT_CLAUSE))
+        .bind(to)
+        .bind(root_prefix(to))
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to drop replaced files: {}", e))?;
+
+    sqlx::query(&format!(
+        "UPDATE files SET path = ?3 || substr(path, length(?1) + 1), updated_at = CURRENT_TIMESTAMP WHERE {}",
+        UNDER_ROOT_CLAUSE
+    ))
+    .bind(from)
+    .bind(root_prefix(from))
+    .bind(to)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to move file records: {}", e))?;
+
+    let name = std::path::Path::new(to)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    sqlx::query("UPDATE files SET name = ? WHERE path = ?")
+        .bind(name)
+        .bind(to)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to rename file record: {}", e))?;
+
+    Ok(())
+}
+
+/// Runs [`move_records`] in a transaction of its own.
+pub async fn record_move(pool: &Pool<Sqlite>, from: &str, to: &str) -> Result<(), String> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+    move_records(&mut tx, from, to).await?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit move: {}", e))
+}
+
+/// Adds `root`, or updates the scan options of a root that is already registered.
+/// `scan_options` is the JSON of the `ScanOptions` the watcher applies to its changes.
+pub async fn register_root<'e, E>(executor: E, root: &str, scan_options: &str) -> Result<(), String>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    sqlx::query(
+        r#"
+        INSERT INTO library_roots (path, scan_options) VALUES (?, ?)
+        ON CONFLICT(path) DO UPDATE SET
+            scan_options = excluded.scan_options,
+            updated_at = CURRENT_TIMESTAMP
+        "#,
+    )
+    .bind(root)
+    .bind(scan_options)
+    .execute(executor)
+    .await
+    .map(|_| ())
+    .map_err(|e| format!("Failed to register library root: {}", e))
+}
+
+/// Every registered root with the JSON of its scan options, `None` for roots
+/// registered before the options were stored.
+pub async fn load_roots<'e, E>(executor: E) -> Result<Vec<(String, Option<String>)>, String>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    sqlx::query_as("SELECT path, scan_options FROM library_roots ORDER BY path")
+        .fetch_all(executor)
+        .await
+        .map_err(|e| format!("Failed to read library roots: {}", e))
+}
+
+pub async fn load_root_options<'e, E>(executor: E, root: &str) -> Result<Option<String>, String>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    sqlx::query_scalar("SELECT scan_options FROM library_roots WHERE path = ?")
+        .bind(root)
+        .fetch_optional(executor)
+        .await
+        .map(Option::flatten)
+        .map_err(|e| format!("Failed to read library root: {}", e))
+}
+
+/// Looks up the indexed row for `path` together with the metadata row it links to.
+/// `file_metadata_id` points into `images`, `videos` or `documents` depending on the
+/// row's `file_type`, so the link is only returned when that matches `file_type`.
+pub async fn metadata_link<'e, E>(
+    executor: E,
+    path: &str,
+    file_type: &str,
+) -> Result<Option<(i64, Option<i64>)>, String>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    sqlx::query_as(
+        "SELECT id, CASE WHEN file_type = ? THEN file_metadata_id END FROM files WHERE path = ?",
+    )
+    .bind(file_type)
+    .bind(path)
+    .fetch_optional(executor)
+    .await
+    .map_err(|e| format!("Failed to read file record: {}", e))
+}
+
+pub async fn link_metadata<'e, E>(executor: E, file_id: i64, metadata_id: i64) -> Result<(), String>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    sqlx::query(
+        "UPDATE files SET file_metadata_id = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+    )
+    .bind(metadata_id)
+    .bind(file_id)
+    .execute(executor)
+    .await
+    .map(|_| ())
+    .map_err(|e| format!("Failed to link file metadata: {}", e))
+}
+
+/// A statement against `images`, `videos` or `documents` that returns the row id.
+pub type MetadataQuery<'q> = QueryScalar<'q, Sqlite, i64, SqliteArguments<'q>>;
+
+/// Writes the metadata of the indexed file at `path` in one transaction. `update` runs
+/// against the row the file already links to, with that row's id bound after the
+/// values from `bind`; `insert` creates and links a new row when there is none or it
+/// is gone. Files that are not indexed are left alone.
+pub async fn store_metadata<'q>(
+    pool: &Pool<Sqlite>,
+    path: &str,
+    file_type: &str,
+    update: &'q str,
+    insert: &'q str,
+    bind: impl Fn(MetadataQuery<'q>) -> MetadataQuery<'q>,
+) -> Result<Option<i64>, String> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+    let Some((file_id, metadata_id)) = metadata_link(&mut *tx, path, file_type).await? else {
+        return Ok(None);
+    };
+
+    let updated = match metadata_id {
+        Some(metadata_id) => bind(sqlx::query_scalar(update))
+            .bind(metadata_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to update {} record: {}", file_type, e))?,
+        None => None,
+    };
+
+    let id = match updated {
+        Some(id) => id,
+        None => {
+            let id = bind(sqlx::query_scalar(insert))
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| format!("Failed to insert {} record: {}", file_type, e))?;
+
+            link_metadata(&mut *tx, file_id, id).await?;
+            id
+        }
+    };
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit {} record: {}", file_type, e))?;
+
+    Ok(Some(id))
+}
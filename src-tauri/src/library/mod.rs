@@ -0,0 +1,7 @@
+pub mod content_hash;
+pub mod find_duplicates;
+pub mod index;
+pub mod remove_library_root;
+pub mod scan_library;
+pub mod search_library;
+pub mod watcher;
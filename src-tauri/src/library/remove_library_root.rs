@@ -0,0 +1,24 @@
+use std::path::Path;
+use tauri::Manager;
+
+use crate::library::watcher::LibraryWatcher;
+use crate::utils::db;
+
+/// Stops watching `root` and forgets it. Indexed files are kept so contents linked to
+/// them stay intact.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn remove_library_root(handle: tauri::AppHandle, root: String) -> Result<(), String> {
+    let pool = db::pool(&handle).await?;
+
+    sqlx::query("DELETE FROM library_roots WHERE path = ?")
+        .bind(&root)
+        .execute(&pool)
+        .await
+        .map_err(|e| format!("Failed to remove library root: {}", e))?;
+
+    if let Err(e) = handle.state::<LibraryWatcher>().unwatch(Path::new(&root)) {
+        log::warn!("{}", e);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,339 @@
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Sqlite};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+use crate::files::IFile;
+use crate::files::file_details::get_file_details;
+use crate::library::index::{self, UNDER_ROOT_CLAUSE};
+use crate::library::watcher::LibraryWatcher;
+use crate::utils::db;
+use crate::utils::path_policy::{Operation, PathPolicy};
+
+const PROGRESS_EVENT: &str = "library-scan-progress";
+const BATCH_SIZE: usize = 250;
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Stored with the root, so the watcher indexes later changes the same way.
+#[derive(Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+pub struct ScanOptions {
+    pub ignore_patterns: Vec<String>,
+    pub follow_symlinks: bool,
+    pub max_depth: Option<usize>,
+}
+
+impl ScanOptions {
+    /// Reads options stored by `scan_library`, falling back to the defaults.
+    pub fn from_stored(stored: Option<&str>) -> ScanOptions {
+        stored
+            .and_then(|json| serde_json::from_str(json).ok())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Serialize, Clone, Default)]
+pub struct ScanProgress {
+    pub scan_id: String,
+    pub root: String,
+    pub scanned: u64,
+    pub added: u64,
+    pub updated: u64,
+    pub unchanged: u64,
+    pub missing: u64,
+    pub skipped: u64,
+    pub current_path: Option<String>,
+    pub finished: bool,
+    pub error: Option<String>,
+}
+
+/// Registers `root` as a watched library, starts indexing it into the `files` table and
+/// returns the scan id used in the `library-scan-progress` events. Without `options`,
+/// a root scanned before keeps the options it was registered with.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn scan_library(
+    handle: tauri::AppHandle,
+    root: String,
+    options: Option<ScanOptions>,
+) -> Result<String, String> {
+    let root_path = Path::new(&root);
+    if !root_path.is_absolute() || !root_path.is_dir() {
+        return Err("Invalid library root".into());
+    }
+
+    let policy = PathPolicy::load(&handle).await;
+    policy.check(root_path, Operation::Read)?;
+
+    let pool = db::pool(&handle).await?;
+    let options = match options {
+        Some(options) => options,
+        None => ScanOptions::from_stored(index::load_root_options(&pool, &root).await?.as_deref()),
+    };
+    let ignore_set = build_ignore_set(&options.ignore_patterns)?;
+
+    let stored = serde_json::to_string(&options)
+        .map_err(|e| format!("Failed to store scan options: {}", e))?;
+    index::register_root(&pool, &root, &stored).await?;
+    if let Err(e) =
+        handle
+            .state::<LibraryWatcher>()
+            .watch(root_path, options.clone(), ignore_set.clone())
+    {
+        log::warn!("{}", e);
+    }
+
+    let scan_id = Uuid::new_v4().to_string();
+    let mut progress = ScanProgress {
+        scan_id: scan_id.clone(),
+        root: root.clone(),
+        ..Default::default()
+    };
+
+    tauri::async_runtime::spawn(async move {
+        let result = run_scan(
+            &handle,
+            &pool,
+            PathBuf::from(&root),
+            options,
+            ignore_set,
+            policy,
+            &mut progress,
+        )
+        .await;
+
+        progress.finished = true;
+        progress.current_path = None;
+        progress.error = result.err();
+        let _ = handle.emit(PROGRESS_EVENT, &progress);
+    });
+
+    Ok(scan_id)
+}
+
+pub fn build_ignore_set(patterns: &[String]) -> Result<GlobSet, String> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob =
+            Glob::new(pattern).map_err(|e| format!("Invalid ignore pattern {}: {}", pattern, e))?;
+        builder.add(glob);
+    }
+    builder
+        .build()
+        .map_err(|e| format!("Invalid ignore patterns: {}", e))
+}
+
+/// Ignore patterns match either the path relative to the root or the bare entry name,
+/// so both `*.tmp` and `node_modules` behave as expected at any depth.
+pub fn is_ignored(ignore_set: &GlobSet, root: &Path, path: &Path) -> bool {
+    if ignore_set.is_empty() {
+        return false;
+    }
+
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    ignore_set.is_match(relative)
+        || path
+            .file_name()
+            .is_some_and(|name| ignore_set.is_match(name))
+}
+
+/// Whether a walk of `root` descends into or indexes the entry at `path`. Hidden and
+/// ignored entries are left out, as are those the path policy does not allow reading.
+pub fn is_admitted(root: &Path, ignore_set: &GlobSet, policy: &PathPolicy, path: &Path) -> bool {
+    !path
+        .file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+        && !is_ignored(ignore_set, root, path)
+        && policy.check(path, Operation::Read).is_ok()
+}
+
+async fn run_scan(
+    handle: &tauri::AppHandle,
+    pool: &Pool<Sqlite>,
+    root: PathBuf,
+    options: ScanOptions,
+    ignore_set: GlobSet,
+    policy: PathPolicy,
+    progress: &mut ScanProgress,
+) -> Result<(), String> {
+    let root_str = root.to_string_lossy().to_string();
+    let mut indexed = load_indexed_files(pool, &root_str).await?;
+
+    let (sender, mut receiver) = mpsc::channel::<Option<IFile>>(BATCH_SIZE * 4);
+    let walker = tauri::async_runtime::spawn_blocking(move || {
+        walk(&root, &options, &ignore_set, &policy, &sender);
+    });
+
+    let mut batch = Vec::with_capacity(BATCH_SIZE);
+    let mut last_emit = Instant::now();
+
+    while let Some(item) = receiver.recv().await {
+        match item {
+            Some(file) => batch.push(file),
+            None => progress.skipped += 1,
+        }
+
+        if batch.len() >= BATCH_SIZE {
+            write_batch(pool, &mut batch, &mut indexed, progress).await?;
+        }
+
+        if last_emit.elapsed() >= PROGRESS_INTERVAL {
+            let _ = handle.emit(PROGRESS_EVENT, &*progress);
+            last_emit = Instant::now();
+        }
+    }
+    write_batch(pool, &mut batch, &mut indexed, progress).await?;
+
+    walker
+        .await
+        .map_err(|e| format!("Library walk failed: {}", e))?;
+
+    // Entries the walk could not reach (unreadable, ignored or beyond max depth) stay
+    // unflagged unless they are really gone.
+    let vanished: Vec<i64> = indexed
+        .into_iter()
+        .filter(|(path, record)| !record.missing && !Path::new(path).exists())
+        .map(|(_, record)| record.id)
+        .collect();
+    mark_missing(pool, &vanished).await?;
+    progress.missing = vanished.len() as u64;
+
+    Ok(())
+}
+
+fn walk(
+    root: &Path,
+    options: &ScanOptions,
+    ignore_set: &GlobSet,
+    policy: &PathPolicy,
+    sender: &mpsc::Sender<Option<IFile>>,
+) {
+    let mut walker = WalkDir::new(root).follow_links(options.follow_symlinks);
+    if let Some(max_depth) = options.max_depth {
+        walker = walker.max_depth(max_depth);
+    }
+
+    let entries = walker.into_iter().filter_entry(|entry| {
+        entry.depth() == 0 || is_admitted(root, ignore_set, policy, entry.path())
+    });
+
+    // `None` reports an entry that could not be read.
+    for entry in entries {
+        let item = match entry {
+            Ok(entry) if entry.file_type().is_dir() => continue,
+            Ok(entry) => match get_file_details(entry.path().to_string_lossy().to_string()) {
+                Ok(file) if file.is_directory == 0 => Some(file),
+                _ => None,
+            },
+            Err(_) => None,
+        };
+
+        if sender.blocking_send(item).is_err() {
+            return;
+        }
+    }
+}
+
+async fn load_indexed_files(
+    pool: &Pool<Sqlite>,
+    root: &str,
+) -> Result<HashMap<String, index::IndexedFile>, String> {
+    let rows: Vec<(i64, String, i64, i64, i64)> = sqlx::query_as(&format!(
+        "SELECT id, path, size, mtime, missing FROM files WHERE {}",
+        UNDER_ROOT_CLAUSE
+    ))
+    .bind(root)
+    .bind(index::root_prefix(root))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to read indexed files: {}", e))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(id, path, size, mtime, missing)| {
+            (
+                path,
+                index::IndexedFile {
+                    id,
+                    size,
+                    mtime,
+                    missing: missing != 0,
+                },
+            )
+        })
+        .collect())
+}
+
+async fn write_batch(
+    pool: &Pool<Sqlite>,
+    batch: &mut Vec<IFile>,
+    indexed: &mut HashMap<String, index::IndexedFile>,
+    progress: &mut ScanProgress,
+) -> Result<(), String> {
+    if batch.is_empty() {
+        return Ok(());
+    }
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+    for file in batch.drain(..) {
+        progress.scanned += 1;
+        progress.current_path = Some(file.path.clone());
+
+        let existing = match indexed.remove(&file.path) {
+            Some(record)
+                if !record.missing
+                    && record.size == file.size as i64
+                    && record.mtime == file.mtime as i64 =>
+            {
+                progress.unchanged += 1;
+                continue;
+            }
+            existing => existing.is_some(),
+        };
+
+        // A failed statement leaves the transaction usable, so one bad entry only
+        // skips itself.
+        match index::upsert_file(&mut *tx, &file).await {
+            Ok(_) if existing => progress.updated += 1,
+            Ok(_) => progress.added += 1,
+            Err(e) => {
+                log::warn!("{}", e);
+                progress.skipped += 1;
+            }
+        }
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit scan batch: {}", e))
+}
+
+pub async fn mark_missing(pool: &Pool<Sqlite>, ids: &[i64]) -> Result<(), String> {
+    for chunk in ids.chunks(BATCH_SIZE) {
+        let placeholders = vec!["?"; chunk.len()].join(", ");
+        let sql = format!(
+            "UPDATE files SET missing = 1, updated_at = CURRENT_TIMESTAMP WHERE id IN ({})",
+            placeholders
+        );
+
+        let mut query = sqlx::query(&sql);
+        for id in chunk {
+            query = query.bind(id);
+        }
+        query
+            .execute(pool)
+            .await
+            .map_err(|e| format!("Failed to flag missing files: {}", e))?;
+    }
+
+    Ok(())
+}
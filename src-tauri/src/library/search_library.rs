@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+
+use crate::utils::db;
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 500;
+
+/// Tokens of context kept around the matched terms in `snippet`.
+const SNIPPET_TOKENS: i64 = 12;
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct SearchFilters {
+    pub content_type_id: Option<i64>,
+    pub category_id: Option<i64>,
+    pub favorite: Option<bool>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Serialize)]
+pub struct LibraryHit {
+    pub content_id: i64,
+    pub content_type_id: i64,
+    pub category_id: i64,
+    pub name: String,
+    pub cover: Option<String>,
+    /// Name with the matched terms wrapped in `<mark>`.
+    pub highlighted_name: String,
+    /// Best matching excerpt across description, category, tags and metadata.
+    pub snippet: String,
+    /// bm25 score, lower is more relevant.
+    pub rank: f64,
+}
+
+type HitRow = (i64, i64, i64, String, Option<String>, String, String, f64);
+
+/// Full-text search over content names, descriptions, categories, tags and metadata
+/// values. Every word of `query` must match, as a prefix, somewhere in the content.
+/// Results are ranked by bm25 with the name weighted highest.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn search_library(
+    handle: tauri::AppHandle,
+    query: String,
+    filters: Option<SearchFilters>,
+) -> Result<Vec<LibraryHit>, String> {
+    let Some(expression) = match_expression(&query) else {
+        return Ok(Vec::new());
+    };
+    let filters = filters.unwrap_or_default();
+
+    let pool = db::pool(&handle).await?;
+    let rows: Vec<HitRow> = sqlx::query_as(
+        r#"
+        SELECT
+            c.id,
+            c.content_type_id,
+            c.category_id,
+            c.name,
+            c.cover,
+            highlight(contents_fts, 0, '<mark>', '</mark>'),
+            snippet(contents_fts, -1, '<mark>', '</mark>', '…', ?),
+            bm25(contents_fts, 10.0, 3.0, 2.0, 5.0, 1.0) AS score
+        FROM contents_fts
+        JOIN contents c ON c.id = contents_fts.rowid
+        WHERE contents_fts MATCH ?
+            AND (? IS NULL OR c.content_type_id = ?)
+            AND (? IS NULL OR c.category_id = ?)
+            AND (? IS NULL OR c.favorite = ?)
+        ORDER BY score
+        LIMIT ? OFFSET ?
+        "#,
+    )
+    .bind(SNIPPET_TOKENS)
+    .bind(&expression)
+    .bind(filters.content_type_id)
+    .bind(filters.content_type_id)
+    .bind(filters.category_id)
+    .bind(filters.category_id)
+    .bind(filters.favorite)
+    .bind(filters.favorite)
+    .bind(filters.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT))
+    .bind(filters.offset.unwrap_or(0).max(0))
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| format!("Failed to search library: {}", e))?;
+
+    Ok(rows.into_iter().map(from_row).collect())
+}
+
+/// Turns free text into an FTS5 expression of quoted prefix terms, so user input can
+/// never be read as FTS5 syntax. Returns `None` when there is nothing to search for.
+fn match_expression(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()))
+        .filter(|word| !word.is_empty())
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect();
+
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+fn from_row(row: HitRow) -> LibraryHit {
+    let (content_id, content_type_id, category_id, name, cover, highlighted_name, snippet, rank) =
+        row;
+    LibraryHit {
+        content_id,
+        content_type_id,
+        category_id,
+        name,
+        cover,
+        highlighted_name,
+        snippet,
+        rank,
+    }
+}
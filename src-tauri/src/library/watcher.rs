@@ -0,0 +1,359 @@
+use globset::GlobSet;
+use notify_debouncer_full::notify::event::{ModifyKind, RenameMode};
+use notify_debouncer_full::notify::{EventKind, RecommendedWatcher, RecursiveMode};
+use notify_debouncer_full::{
+    DebounceEventResult, DebouncedEvent, Debouncer, RecommendedCache, new_debouncer,
+};
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::mpsc;
+use walkdir::WalkDir;
+
+use crate::files::file_details::get_file_details;
+use crate::library::index;
+use crate::library::scan_library::{self, ScanOptions};
+use crate::utils::db;
+use crate::utils::path_policy::PathPolicy;
+
+const CHANGE_EVENT: &str = "library-file-changed";
+const DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(750);
+const DATABASE_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Indexed,
+    Renamed,
+    Removed,
+}
+
+#[derive(Serialize, Clone)]
+pub struct FileChange {
+    pub kind: ChangeKind,
+    pub path: String,
+    pub old_path: Option<String>,
+}
+
+/// Keeps the `files` table in sync with changes made outside the app for every
+/// registered library root.
+pub struct LibraryWatcher {
+    debouncer: Mutex<Debouncer<RecommendedWatcher, RecommendedCache>>,
+    roots: Mutex<Vec<Arc<WatchedRoot>>>,
+}
+
+/// A library root with the options it was scanned with, which changes below it are
+/// indexed with as well.
+struct WatchedRoot {
+    path: PathBuf,
+    options: ScanOptions,
+    ignore_set: GlobSet,
+}
+
+impl WatchedRoot {
+    /// Whether the scan of this root would have reached `path`.
+    fn admits(&self, policy: &PathPolicy, path: &Path) -> bool {
+        let Ok(relative) = path.strip_prefix(&self.path) else {
+            return false;
+        };
+        if let Some(max_depth) = self.options.max_depth
+            && relative.components().count() > max_depth
+        {
+            return false;
+        }
+
+        // The walk skips ignored directories together with everything below them.
+        path.ancestors()
+            .take_while(|ancestor| *ancestor != self.path)
+            .all(|ancestor| {
+                scan_library::is_admitted(&self.path, &self.ignore_set, policy, ancestor)
+            })
+    }
+}
+
+impl LibraryWatcher {
+    pub fn start(handle: &AppHandle) -> Result<LibraryWatcher, String> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        let debouncer = new_debouncer(
+            DEBOUNCE_TIMEOUT,
+            None,
+            move |result: DebounceEventResult| match result {
+                Ok(events) => {
+                    let _ = sender.send(events);
+                }
+                Err(errors) => {
+                    for error in errors {
+                        log::warn!("Library watcher error: {}", error);
+                    }
+                }
+            },
+        )
+        .map_err(|e| format!("Failed to start library watcher: {}", e))?;
+
+        tauri::async_runtime::spawn(process_events(handle.clone(), receiver));
+        tauri::async_runtime::spawn(watch_registered_roots(handle.clone()));
+
+        Ok(LibraryWatcher {
+            debouncer: Mutex::new(debouncer),
+            roots: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// Watches `root`, or only updates its options when it is watched already.
+    pub fn watch(
+        &self,
+        root: &Path,
+        options: ScanOptions,
+        ignore_set: GlobSet,
+    ) -> Result<(), String> {
+        let watched = Arc::new(WatchedRoot {
+            path: root.to_path_buf(),
+            options,
+            ignore_set,
+        });
+
+        {
+            let mut roots = self
+                .roots
+                .lock()
+                .map_err(|_| "Library watcher is unavailable")?;
+            if let Some(existing) = roots.iter_mut().find(|existing| existing.path == root) {
+                *existing = watched;
+                return Ok(());
+            }
+            roots.push(watched);
+        }
+
+        self.debouncer
+            .lock()
+            .map_err(|_| "Library watcher is unavailable")?
+            .watch(root, RecursiveMode::Recursive)
+            .map_err(|e| format!("Failed to watch {}: {}", root.display(), e))
+    }
+
+    pub fn unwatch(&self, root: &Path) -> Result<(), String> {
+        if let Ok(mut roots) = self.roots.lock() {
+            roots.retain(|watched| watched.path != root);
+        }
+
+        self.debouncer
+            .lock()
+            .map_err(|_| "Library watcher is unavailable")?
+            .unwatch(root)
+            .map_err(|e| format!("Failed to unwatch {}: {}", root.display(), e))
+    }
+
+    /// The innermost watched root containing `path`.
+    fn root_of(&self, path: &Path) -> Option<Arc<WatchedRoot>> {
+        self.roots
+            .lock()
+            .ok()?
+            .iter()
+            .filter(|watched| path.starts_with(&watched.path))
+            .max_by_key(|watched| watched.path.components().count())
+            .cloned()
+    }
+}
+
+/// The database is opened by the frontend after startup, so the registered roots can
+/// only be read once its pool shows up.
+async fn watch_registered_roots(handle: AppHandle) {
+    let pool = loop {
+        if let Ok(pool) = db::pool(&handle).await {
+            break pool;
+        }
+        tokio::time::sleep(DATABASE_POLL_INTERVAL).await;
+    };
+
+    let roots = match index::load_roots(&pool).await {
+        Ok(roots) => roots,
+        Err(e) => {
+            log::warn!("{}", e);
+            return;
+        }
+    };
+
+    let watcher = handle.state::<LibraryWatcher>();
+    for (root, stored) in roots {
+        let root = Path::new(&root);
+        if !root.is_dir() {
+            continue;
+        }
+
+        let options = ScanOptions::from_stored(stored.as_deref());
+        let watched = scan_library::build_ignore_set(&options.ignore_patterns)
+            .and_then(|ignore_set| watcher.watch(root, options, ignore_set));
+        if let Err(e) = watched {
+            log::warn!("{}", e);
+        }
+    }
+}
+
+async fn process_events(
+    handle: AppHandle,
+    mut receiver: mpsc::UnboundedReceiver<Vec<DebouncedEvent>>,
+) {
+    while let Some(events) = receiver.recv().await {
+        let Ok(pool) = db::pool(&handle).await else {
+            continue;
+        };
+        let watcher = handle.state::<LibraryWatcher>();
+        let policy = PathPolicy::load(&handle).await;
+
+        for event in events {
+            match apply_event(&pool, &watcher, &policy, &event).await {
+                Ok(changes) => {
+                    for change in changes {
+                        let _ = handle.emit(CHANGE_EVENT, &change);
+                    }
+                }
+                Err(e) => log::warn!("Failed to apply library change: {}", e),
+            }
+        }
+    }
+}
+
+async fn apply_event(
+    pool: &Pool<Sqlite>,
+    watcher: &LibraryWatcher,
+    policy: &PathPolicy,
+    event: &DebouncedEvent,
+) -> Result<Vec<FileChange>, String> {
+    let paths = &event.paths;
+
+    match event.kind {
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if paths.len() == 2 => {
+            let from = paths[0].to_string_lossy().to_string();
+            match admitting_root(watcher, policy, &paths[1]) {
+                Some(root) => rename(pool, &root, policy, &from, &paths[1]).await,
+                // Moved somewhere the scan would not have indexed it.
+                None => remove(pool, &from).await,
+            }
+        }
+        EventKind::Modify(ModifyKind::Name(RenameMode::From)) | EventKind::Remove(_) => {
+            let mut changes = Vec::new();
+            for path in paths {
+                changes.extend(remove(pool, &path.to_string_lossy()).await?);
+            }
+            Ok(changes)
+        }
+        EventKind::Modify(ModifyKind::Metadata(_)) => Ok(Vec::new()),
+        EventKind::Create(_) | EventKind::Modify(_) => {
+            let mut changes = Vec::new();
+            for path in paths {
+                // Directories report content changes too; their files send their own events.
+                if matches!(event.kind, EventKind::Modify(_)) && path.is_dir() {
+                    continue;
+                }
+
+                if !path.exists() {
+                    changes.extend(remove(pool, &path.to_string_lossy()).await?);
+                } else if let Some(root) = admitting_root(watcher, policy, path) {
+                    changes.extend(index_tree(pool, &root, policy, path).await?);
+                }
+            }
+            Ok(changes)
+        }
+        _ => Ok(Vec::new()),
+    }
+}
+
+async fn rename(
+    pool: &Pool<Sqlite>,
+    root: &Arc<WatchedRoot>,
+    policy: &PathPolicy,
+    from: &str,
+    to: &Path,
+) -> Result<Vec<FileChange>, String> {
+    let to_str = to.to_string_lossy().to_string();
+    index::record_move(pool, from, &to_str).await?;
+
+    // Refresh size and mtime, and pick up files that were not indexed before the move.
+    index_tree(pool, root, policy, to).await?;
+
+    Ok(vec![FileChange {
+        kind: ChangeKind::Renamed,
+        path: to_str,
+        old_path: Some(from.to_string()),
+    }])
+}
+
+/// The root `path` belongs to, if the scan of that root would have indexed it.
+fn admitting_root(
+    watcher: &LibraryWatcher,
+    policy: &PathPolicy,
+    path: &Path,
+) -> Option<Arc<WatchedRoot>> {
+    watcher
+        .root_of(path)
+        .filter(|root| root.admits(policy, path))
+}
+
+async fn remove(pool: &Pool<Sqlite>, path: &str) -> Result<Vec<FileChange>, String> {
+    if index::mark_missing_under(pool, path).await? == 0 {
+        return Ok(Vec::new());
+    }
+
+    Ok(vec![FileChange {
+        kind: ChangeKind::Removed,
+        path: path.to_string(),
+        old_path: None,
+    }])
+}
+
+/// Indexes a file, or every file below a directory that was created or moved in, with
+/// the scan options of its root.
+async fn index_tree(
+    pool: &Pool<Sqlite>,
+    root: &Arc<WatchedRoot>,
+    policy: &PathPolicy,
+    path: &Path,
+) -> Result<Vec<FileChange>, String> {
+    let root = Arc::clone(root);
+    let policy = policy.clone();
+    let path = path.to_path_buf();
+    let files = tauri::async_runtime::spawn_blocking(move || {
+        let mut walker = WalkDir::new(&path).follow_links(root.options.follow_symlinks);
+        if let Some(max_depth) = root.options.max_depth {
+            let depth = path
+                .strip_prefix(&root.path)
+                .map(|relative| relative.components().count())
+                .unwrap_or(0);
+            walker = walker.max_depth(max_depth.saturating_sub(depth));
+        }
+
+        walker
+            .into_iter()
+            .filter_entry(|entry| {
+                entry.depth() == 0
+                    || scan_library::is_admitted(
+                        &root.path,
+                        &root.ignore_set,
+                        &policy,
+                        entry.path(),
+                    )
+            })
+            .flatten()
+            .filter(|entry| !entry.file_type().is_dir())
+            .filter_map(|entry| get_file_details(entry.path().to_string_lossy().to_string()).ok())
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|e| format!("Failed to read changed files: {}", e))?;
+
+    let mut changes = Vec::with_capacity(files.len());
+    for file in files {
+        index::upsert_file(pool, &file).await?;
+        changes.push(FileChange {
+            kind: ChangeKind::Indexed,
+            path: file.path,
+            old_path: None,
+        });
+    }
+
+    Ok(changes)
+}
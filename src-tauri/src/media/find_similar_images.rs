@@ -0,0 +1,284 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::media::perceptual_hash::{self, distance};
+use crate::utils::db;
+use crate::utils::path_policy::{Operation, PathPolicy};
+
+/// Hashes more than this many bits apart are unrelated pictures.
+const MAX_THRESHOLD: u32 = 32;
+
+#[derive(Serialize)]
+pub struct SimilarImage {
+    pub file_id: i64,
+    pub path: String,
+    pub width: u32,
+    pub height: u32,
+    pub size: u64,
+    /// Bits between this image's hash and the first image of the group.
+    pub distance: u32,
+}
+
+#[derive(Serialize)]
+pub struct SimilarGroup {
+    /// Largest image first, which is the copy we suggest keeping.
+    pub images: Vec<SimilarImage>,
+}
+
+struct Candidate {
+    file_id: i64,
+    path: String,
+    width: u32,
+    height: u32,
+    size: u64,
+    hash: u64,
+}
+
+/// Groups indexed images whose perceptual hashes are at most `threshold` bits apart.
+/// Images without a hash are decoded and hashed first; the hash is kept until the
+/// image is probed again. Similarity chains, so A and C share a group when both are
+/// close to B.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn find_similar_images(
+    handle: tauri::AppHandle,
+    threshold: u32,
+) -> Result<Vec<SimilarGroup>, String> {
+    if threshold > MAX_THRESHOLD {
+        return Err(format!("Threshold must be at most {}", MAX_THRESHOLD));
+    }
+
+    let pool = db::pool(&handle).await?;
+    let rows: Vec<ImageRow> = sqlx::query_as(
+        r#"
+        SELECT f.id, f.path, i.id, i.width, i.height, f.size, i.perceptual_hash
+        FROM files f
+        JOIN images i ON i.id = f.file_metadata_id
+        WHERE f.file_type = 'image' AND f.missing = 0
+        "#,
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| format!("Failed to read image hashes: {}", e))?;
+
+    let policy = PathPolicy::load(&handle).await;
+    let rows: Vec<ImageRow> = rows
+        .into_iter()
+        .filter(|(_, path, ..)| policy.check(Path::new(path), Operation::Read).is_ok())
+        .collect();
+
+    let (candidates, hashed) = tauri::async_runtime::spawn_blocking(move || hash_all(rows))
+        .await
+        .map_err(|e| format!("Failed to hash images: {}", e))?;
+
+    for (image_id, hash) in hashed {
+        let stored = sqlx::query("UPDATE images SET perceptual_hash = ? WHERE id = ?")
+            .bind(perceptual_hash::to_hex(hash))
+            .bind(image_id)
+            .execute(&pool)
+            .await;
+        if let Err(e) = stored {
+            log::warn!("Failed to store perceptual hash: {}", e);
+        }
+    }
+
+    tauri::async_runtime::spawn_blocking(move || group(candidates, threshold))
+        .await
+        .map_err(|e| format!("Failed to compare images: {}", e))
+}
+
+/// File id, path, image id, width, height, size and stored hash of an indexed image.
+type ImageRow = (i64, String, i64, i64, i64, i64, Option<String>);
+
+/// Turns the rows into candidates, hashing the images that have no hash yet. Also
+/// returns the new hashes by image id. Images that cannot be decoded are left out.
+fn hash_all(rows: Vec<ImageRow>) -> (Vec<Candidate>, Vec<(i64, u64)>) {
+    let mut hashed = Vec::new();
+    let candidates = rows
+        .into_iter()
+        .filter_map(|(file_id, path, image_id, width, height, size, hash)| {
+            let hash = match hash.as_deref().and_then(perceptual_hash::from_hex) {
+                Some(hash) => hash,
+                None => {
+                    let hash = perceptual_hash::dhash(Path::new(&path)).ok()?;
+                    hashed.push((image_id, hash));
+                    hash
+                }
+            };
+            Some(Candidate {
+                file_id,
+                path,
+                width: width as u32,
+                height: height as u32,
+                size: size as u64,
+                hash,
+            })
+        })
+        .collect();
+    (candidates, hashed)
+}
+
+fn group(candidates: Vec<Candidate>, threshold: u32) -> Vec<SimilarGroup> {
+    let mut tree = BkTree::default();
+    for (index, candidate) in candidates.iter().enumerate() {
+        tree.insert(candidate.hash, index);
+    }
+
+    let mut sets = DisjointSet::new(candidates.len());
+    for (index, candidate) in candidates.iter().enumerate() {
+        for other in tree.find(candidate.hash, threshold) {
+            sets.union(index, other);
+        }
+    }
+
+    let mut members: HashMap<usize, Vec<usize>> = HashMap::new();
+    for index in 0..candidates.len() {
+        members.entry(sets.find(index)).or_default().push(index);
+    }
+
+    let mut candidates: Vec<Option<Candidate>> = candidates.into_iter().map(Some).collect();
+    let mut groups: Vec<SimilarGroup> = members
+        .into_values()
+        .filter(|indices| indices.len() > 1)
+        .map(|indices| {
+            let mut images: Vec<Candidate> = indices
+                .into_iter()
+                .filter_map(|index| candidates[index].take())
+                .collect();
+            images.sort_by(|a, b| {
+                (b.width as u64 * b.height as u64)
+                    .cmp(&(a.width as u64 * a.height as u64))
+                    .then(b.size.cmp(&a.size))
+                    .then_with(|| a.path.cmp(&b.path))
+            });
+
+            let reference = images[0].hash;
+            SimilarGroup {
+                images: images
+                    .into_iter()
+                    .map(|image| SimilarImage {
+                        distance: distance(reference, image.hash),
+                        file_id: image.file_id,
+                        path: image.path,
+                        width: image.width,
+                        height: image.height,
+                        size: image.size,
+                    })
+                    .collect(),
+            }
+        })
+        .collect();
+
+    groups.sort_by(|a, b| {
+        b.images
+            .len()
+            .cmp(&a.images.len())
+            .then_with(|| a.images[0].path.cmp(&b.images[0].path))
+    });
+    groups
+}
+
+/// Burkhard-Keller tree over Hamming distance, so a lookup only visits the subtrees
+/// whose distance to the query can be within the threshold.
+#[derive(Default)]
+struct BkTree {
+    nodes: Vec<BkNode>,
+}
+
+struct BkNode {
+    hash: u64,
+    /// Indices of every candidate with exactly this hash.
+    items: Vec<usize>,
+    children: HashMap<u32, usize>,
+}
+
+impl BkTree {
+    fn insert(&mut self, hash: u64, item: usize) {
+        if self.nodes.is_empty() {
+            self.nodes.push(BkNode::new(hash, item));
+            return;
+        }
+
+        let mut current = 0;
+        loop {
+            let bits = distance(self.nodes[current].hash, hash);
+            if bits == 0 {
+                self.nodes[current].items.push(item);
+                return;
+            }
+
+            match self.nodes[current].children.get(&bits) {
+                Some(&child) => current = child,
+                None => {
+                    let child = self.nodes.len();
+                    self.nodes.push(BkNode::new(hash, item));
+                    self.nodes[current].children.insert(bits, child);
+                    return;
+                }
+            }
+        }
+    }
+
+    fn find(&self, hash: u64, threshold: u32) -> Vec<usize> {
+        let mut found = Vec::new();
+        let mut pending = if self.nodes.is_empty() {
+            vec![]
+        } else {
+            vec![0]
+        };
+
+        while let Some(current) = pending.pop() {
+            let node = &self.nodes[current];
+            let bits = distance(node.hash, hash);
+            if bits <= threshold {
+                found.extend(&node.items);
+            }
+
+            pending.extend(
+                node.children
+                    .iter()
+                    .filter(|(edge, _)| edge.abs_diff(bits) <= threshold)
+                    .map(|(_, &child)| child),
+            );
+        }
+
+        found
+    }
+}
+
+impl BkNode {
+    fn new(hash: u64, item: usize) -> BkNode {
+        BkNode {
+            hash,
+            items: vec![item],
+            children: HashMap::new(),
+        }
+    }
+}
+
+struct DisjointSet {
+    parents: Vec<usize>,
+}
+
+impl DisjointSet {
+    fn new(size: usize) -> DisjointSet {
+        DisjointSet {
+            parents: (0..size).collect(),
+        }
+    }
+
+    fn find(&mut self, mut index: usize) -> usize {
+        while self.parents[index] != index {
+            self.parents[index] = self.parents[self.parents[index]];
+            index = self.parents[index];
+        }
+        index
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a != b {
+            self.parents[b] = a;
+        }
+    }
+}
@@ -0,0 +1,465 @@
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use crate::media::isobmff::{self, Cursor};
+
+/// Everything the `images` table needs that can be read without decoding pixels.
+pub struct ImageHeader {
+    pub format: &'static str,
+    pub width: u32,
+    pub height: u32,
+    pub color_space: Option<&'static str>,
+    pub has_exif: bool,
+}
+
+type Reader = BufReader<File>;
+
+pub fn read_header(path: &Path) -> Result<ImageHeader, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open image: {}", e))?;
+    let file_len = file
+        .metadata()
+        .map_err(|e| format!("Failed to read metadata: {}", e))?
+        .len();
+    let mut reader = BufReader::new(file);
+
+    let mut magic = [0u8; 12];
+    let read = read_up_to(&mut reader, &mut magic).map_err(invalid)?;
+    reader.rewind().map_err(invalid)?;
+
+    let header = match &magic[..read] {
+        [0xFF, 0xD8, 0xFF, ..] => jpeg(&mut reader),
+        [0x89, b'P', b'N', b'G', ..] => png(&mut reader),
+        [b'G', b'I', b'F', b'8', ..] => gif(&mut reader),
+        [b'B', b'M', ..] => bmp(&mut reader),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P'] => webp(&mut reader),
+        [b'I', b'I', 42, 0, ..] => tiff(&mut reader, false),
+        [b'M', b'M', 0, 42, ..] => tiff(&mut reader, true),
+        [_, _, _, _, b'f', b't', b'y', b'p', ..] => heif(&mut reader, file_len),
+        _ => return Err("Unsupported image format".to_string()),
+    };
+
+    match header {
+        Ok(header) if header.width > 0 && header.height > 0 => Ok(header),
+        Ok(_) => Err("Image has no dimensions".to_string()),
+        Err(e) => Err(invalid(e)),
+    }
+}
+
+fn invalid(e: io::Error) -> String {
+    format!("Failed to read image header: {}", e)
+}
+
+fn malformed(what: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("Malformed {}", what))
+}
+
+fn read_up_to(reader: &mut Reader, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..])? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
+
+fn read_array<const N: usize>(reader: &mut Reader) -> io::Result<[u8; N]> {
+    let mut buf = [0u8; N];
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+fn jpeg(reader: &mut Reader) -> io::Result<ImageHeader> {
+    reader.seek(SeekFrom::Start(2))?;
+    let mut has_exif = false;
+
+    loop {
+        let [marker_start] = read_array::<1>(reader)?;
+        if marker_start != 0xFF {
+            return Err(malformed("JPEG marker"));
+        }
+
+        let mut marker = 0xFF;
+        while marker == 0xFF {
+            [marker] = read_array::<1>(reader)?;
+        }
+
+        match marker {
+            // Standalone markers carry no length.
+            0x01 | 0xD0..=0xD7 => continue,
+            // Start of scan or end of image before any frame header.
+            0xD9 | 0xDA => return Err(malformed("JPEG without frame header")),
+            _ => {}
+        }
+
+        let length = u16::from_be_bytes(read_array::<2>(reader)?) as i64;
+        if length < 2 {
+            return Err(malformed("JPEG segment"));
+        }
+        let segment_end = reader.stream_position()? + length as u64 - 2;
+
+        match marker {
+            0xE1 if length >= 8 => {
+                has_exif |= &read_array::<6>(reader)? == b"Exif\0\0";
+            }
+            // SOF0..SOF15, except DHT (C4), JPG (C8) and DAC (CC).
+            0xC0..=0xCF if !matches!(marker, 0xC4 | 0xC8 | 0xCC) => {
+                let frame = read_array::<6>(reader)?;
+                let height = u16::from_be_bytes([frame[1], frame[2]]) as u32;
+                let width = u16::from_be_bytes([frame[3], frame[4]]) as u32;
+                let color_space = match frame[5] {
+                    1 => Some("Gray"),
+                    3 => Some("YCbCr"),
+                    4 => Some("CMYK"),
+                    _ => None,
+                };
+
+                return Ok(ImageHeader {
+                    format: "jpeg",
+                    width,
+                    height,
+                    color_space,
+                    has_exif,
+                });
+            }
+            _ => {}
+        }
+
+        reader.seek(SeekFrom::Start(segment_end))?;
+    }
+}
+
+fn png(reader: &mut Reader) -> io::Result<ImageHeader> {
+    reader.seek(SeekFrom::Start(8))?;
+    let mut header: Option<ImageHeader> = None;
+
+    loop {
+        let length = u32::from_be_bytes(read_array::<4>(reader)?) as u64;
+        let kind = read_array::<4>(reader)?;
+        let chunk_end = reader.stream_position()? + length + 4;
+
+        match &kind {
+            b"IHDR" => {
+                let ihdr = read_array::<13>(reader)?;
+                header = Some(ImageHeader {
+                    format: "png",
+                    width: u32::from_be_bytes([ihdr[0], ihdr[1], ihdr[2], ihdr[3]]),
+                    height: u32::from_be_bytes([ihdr[4], ihdr[5], ihdr[6], ihdr[7]]),
+                    color_space: match ihdr[9] {
+                        0 => Some("Gray"),
+                        2 => Some("RGB"),
+                        3 => Some("Indexed"),
+                        4 => Some("GrayAlpha"),
+                        6 => Some("RGBA"),
+                        _ => None,
+                    },
+                    has_exif: false,
+                });
+            }
+            b"eXIf" => {
+                if let Some(header) = header.as_mut() {
+                    header.has_exif = true;
+                }
+            }
+            // Metadata chunks that matter here precede the image data.
+            b"IDAT" | b"IEND" => break,
+            _ => {}
+        }
+
+        reader.seek(SeekFrom::Start(chunk_end))?;
+    }
+
+    header.ok_or_else(|| malformed("PNG without IHDR"))
+}
+
+fn gif(reader: &mut Reader) -> io::Result<ImageHeader> {
+    let header = read_array::<10>(reader)?;
+
+    Ok(ImageHeader {
+        format: "gif",
+        width: u16::from_le_bytes([header[6], header[7]]) as u32,
+        height: u16::from_le_bytes([header[8], header[9]]) as u32,
+        color_space: Some("Indexed"),
+        has_exif: false,
+    })
+}
+
+fn bmp(reader: &mut Reader) -> io::Result<ImageHeader> {
+    let header = read_array::<30>(reader)?;
+    let dib_size = u32::from_le_bytes([header[14], header[15], header[16], header[17]]);
+
+    let (width, height, bits_per_pixel) = if dib_size == 12 {
+        (
+            u16::from_le_bytes([header[18], header[19]]) as u32,
+            u16::from_le_bytes([header[20], header[21]]) as u32,
+            u16::from_le_bytes([header[24], header[25]]),
+        )
+    } else {
+        (
+            i32::from_le_bytes([header[18], header[19], header[20], header[21]]).unsigned_abs(),
+            // Negative heights mark top-down bitmaps.
+            i32::from_le_bytes([header[22], header[23], header[24], header[25]]).unsigned_abs(),
+            u16::from_le_bytes([header[28], header[29]]),
+        )
+    };
+
+    Ok(ImageHeader {
+        format: "bmp",
+        width,
+        height,
+        color_space: Some(match bits_per_pixel {
+            0..=8 => "Indexed",
+            32 => "RGBA",
+            _ => "RGB",
+        }),
+        has_exif: false,
+    })
+}
+
+fn webp(reader: &mut Reader) -> io::Result<ImageHeader> {
+    let riff = read_array::<12>(reader)?;
+    let riff_end = 8 + u32::from_le_bytes([riff[4], riff[5], riff[6], riff[7]]) as u64;
+
+    let mut header: Option<ImageHeader> = None;
+    let mut has_exif = false;
+    let mut offset = 12;
+
+    while offset + 8 <= riff_end {
+        reader.seek(SeekFrom::Start(offset))?;
+        let chunk = read_array::<8>(reader)?;
+        let size = u32::from_le_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]) as u64;
+
+        match &chunk[..4] {
+            b"VP8X" => {
+                let vp8x = read_array::<10>(reader)?;
+                has_exif |= vp8x[0] & 0x08 != 0;
+                header = Some(ImageHeader {
+                    format: "webp",
+                    width: u32::from_le_bytes([vp8x[4], vp8x[5], vp8x[6], 0]) + 1,
+                    height: u32::from_le_bytes([vp8x[7], vp8x[8], vp8x[9], 0]) + 1,
+                    color_space: Some(if vp8x[0] & 0x10 != 0 { "RGBA" } else { "RGB" }),
+                    has_exif: false,
+                });
+            }
+            b"VP8 " if header.is_none() => {
+                let frame = read_array::<10>(reader)?;
+                if frame[3..6] != [0x9D, 0x01, 0x2A] {
+                    return Err(malformed("WebP lossy frame"));
+                }
+                header = Some(ImageHeader {
+                    format: "webp",
+                    width: (u16::from_le_bytes([frame[6], frame[7]]) & 0x3FFF) as u32,
+                    height: (u16::from_le_bytes([frame[8], frame[9]]) & 0x3FFF) as u32,
+                    color_space: Some("YCbCr"),
+                    has_exif: false,
+                });
+            }
+            b"VP8L" if header.is_none() => {
+                let frame = read_array::<5>(reader)?;
+                if frame[0] != 0x2F {
+                    return Err(malformed("WebP lossless frame"));
+                }
+                let bits = u32::from_le_bytes([frame[1], frame[2], frame[3], frame[4]]);
+                header = Some(ImageHeader {
+                    format: "webp",
+                    width: (bits & 0x3FFF) + 1,
+                    height: ((bits >> 14) & 0x3FFF) + 1,
+                    color_space: Some(if bits & (1 << 28) != 0 { "RGBA" } else { "RGB" }),
+                    has_exif: false,
+                });
+            }
+            b"EXIF" => has_exif = true,
+            _ => {}
+        }
+
+        // Chunks are padded to an even size.
+        offset += 8 + size + (size & 1);
+    }
+
+    let mut header = header.ok_or_else(|| malformed("WebP without image data"))?;
+    header.has_exif = has_exif;
+    Ok(header)
+}
+
+fn tiff(reader: &mut Reader, big_endian: bool) -> io::Result<ImageHeader> {
+    let u16_at = |bytes: &[u8]| {
+        let pair = [bytes[0], bytes[1]];
+        if big_endian {
+            u16::from_be_bytes(pair)
+        } else {
+            u16::from_le_bytes(pair)
+        }
+    };
+    let u32_at = |bytes: &[u8]| {
+        let quad = [bytes[0], bytes[1], bytes[2], bytes[3]];
+        if big_endian {
+            u32::from_be_bytes(quad)
+        } else {
+            u32::from_le_bytes(quad)
+        }
+    };
+
+    let header = read_array::<8>(reader)?;
+    reader.seek(SeekFrom::Start(u32_at(&header[4..]) as u64))?;
+    let entry_count = u16_at(&read_array::<2>(reader)?);
+
+    let mut width = 0;
+    let mut height = 0;
+    let mut photometric = None;
+    let mut samples_per_pixel = 1;
+    let mut has_exif = false;
+
+    for _ in 0..entry_count {
+        let entry = read_array::<12>(reader)?;
+        let tag = u16_at(&entry[0..]);
+        let field_type = u16_at(&entry[2..]);
+        // SHORT values are left-aligned in the value field.
+        let value = if field_type == 3 {
+            u16_at(&entry[8..]) as u32
+        } else {
+            u32_at(&entry[8..])
+        };
+
+        match tag {
+            256 => width = value,
+            257 => height = value,
+            262 => photometric = Some(value),
+            277 => samples_per_pixel = value,
+            34665 => has_exif = true,
+            _ => {}
+        }
+    }
+
+    let color_space = match (photometric, samples_per_pixel) {
+        (Some(0 | 1), 1) => Some("Gray"),
+        (Some(0 | 1), _) => Some("GrayAlpha"),
+        (Some(2), 4..) => Some("RGBA"),
+        (Some(2), _) => Some("RGB"),
+        (Some(3), _) => Some("Indexed"),
+        (Some(5), _) => Some("CMYK"),
+        (Some(6), _) => Some("YCbCr"),
+        _ => None,
+    };
+
+    Ok(ImageHeader {
+        format: "tiff",
+        width,
+        height,
+        color_space,
+        has_exif,
+    })
+}
+
+/// AVIF and HEIC store image properties in `meta/iprp/ipco`, and EXIF as an item
+/// declared in `meta/iinf`.
+fn heif(reader: &mut Reader, file_len: u64) -> io::Result<ImageHeader> {
+    let top = isobmff::children(reader, 0, file_len)?;
+
+    let ftyp = isobmff::find(&top, b"ftyp").ok_or_else(|| malformed("HEIF without ftyp"))?;
+    let brands = isobmff::read_payload(reader, ftyp)?;
+    let has_brand = |brand: &[u8]| {
+        brands
+            .chunks(4)
+            .enumerate()
+            // Skip the minor version that follows the major brand.
+            .any(|(index, chunk)| index != 1 && chunk == brand)
+    };
+    let format = if has_brand(b"avif") || has_brand(b"avis") {
+        "avif"
+    } else if has_brand(b"heic") || has_brand(b"heix") || has_brand(b"mif1") {
+        "heif"
+    } else {
+        return Err(malformed("ISO media file that is not an image"));
+    };
+
+    let meta = isobmff::find(&top, b"meta").ok_or_else(|| malformed("HEIF without meta"))?;
+    let meta_children = isobmff::full_box_children(reader, meta, 0)?;
+
+    let mut width = 0;
+    let mut height = 0;
+    let mut channels = None;
+    let mut matrix_coefficients = None;
+
+    if let Some(iprp) = isobmff::find(&meta_children, b"iprp") {
+        let iprp_children = isobmff::children(reader, iprp.payload_start, iprp.end)?;
+        if let Some(ipco) = isobmff::find(&iprp_children, b"ipco") {
+            for property in isobmff::children(reader, ipco.payload_start, ipco.end)? {
+                let payload = isobmff::read_payload(reader, &property)?;
+                let mut cursor = Cursor::new(&payload);
+
+                if property.is(b"ispe") {
+                    cursor.skip(4);
+                    let (Some(w), Some(h)) = (cursor.u32(), cursor.u32()) else {
+                        continue;
+                    };
+                    // Grids and thumbnails carry their own extents; keep the largest.
+                    if w as u64 * h as u64 > width as u64 * height as u64 {
+                        width = w;
+                        height = h;
+                    }
+                } else if property.is(b"pixi") {
+                    cursor.skip(4);
+                    channels = channels.or(cursor.u8());
+                } else if property.is(b"colr") && cursor.fourcc() == Some(*b"nclx") {
+                    cursor.skip(4);
+                    matrix_coefficients = matrix_coefficients.or(cursor.u16());
+                }
+            }
+        }
+    }
+
+    let has_exif = match isobmff::find(&meta_children, b"iinf") {
+        Some(iinf) => has_exif_item(reader, iinf)?,
+        None => false,
+    };
+
+    let color_space = match (channels, matrix_coefficients) {
+        (Some(1), _) => Some("Gray"),
+        (_, Some(0)) => Some("RGB"),
+        (_, Some(_)) => Some("YCbCr"),
+        _ => None,
+    };
+
+    Ok(ImageHeader {
+        format,
+        width,
+        height,
+        color_space,
+        has_exif,
+    })
+}
+
+fn has_exif_item(reader: &mut Reader, iinf: &isobmff::BoxHeader) -> io::Result<bool> {
+    reader.seek(SeekFrom::Start(iinf.payload_start))?;
+    let [version] = read_array::<1>(reader)?;
+    let count_size = if version == 0 { 2 } else { 4 };
+
+    for infe in isobmff::full_box_children(reader, iinf, count_size)? {
+        if !infe.is(b"infe") {
+            continue;
+        }
+
+        let payload = isobmff::read_payload(reader, &infe)?;
+        let mut cursor = Cursor::new(&payload);
+        let Some(version) = cursor.u8() else {
+            continue;
+        };
+        cursor.skip(3);
+
+        // Item types only exist from version 2 on.
+        let id_size = match version {
+            2 => 2,
+            3 => 4,
+            _ => continue,
+        };
+        cursor.skip(id_size + 2);
+
+        if cursor.fourcc() == Some(*b"Exif") {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
@@ -0,0 +1,158 @@
+use std::io::{self, Read, Seek, SeekFrom};
+
+/// Payloads larger than this are never read into memory; they are skipped by offset.
+const MAX_PAYLOAD_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Header of an ISO base media file format box (MP4, MOV, HEIF/AVIF).
+#[derive(Debug, Clone, Copy)]
+pub struct BoxHeader {
+    pub kind: [u8; 4],
+    /// Offset of the first payload byte.
+    pub payload_start: u64,
+    /// Offset one past the last payload byte.
+    pub end: u64,
+}
+
+impl BoxHeader {
+    pub fn is(&self, kind: &[u8; 4]) -> bool {
+        &self.kind == kind
+    }
+
+    pub fn payload_len(&self) -> u64 {
+        self.end - self.payload_start
+    }
+}
+
+/// Lists the boxes between `start` and `end`, which is either the whole file or the
+/// payload of a container box.
+pub fn children<R: Read + Seek>(
+    reader: &mut R,
+    start: u64,
+    end: u64,
+) -> io::Result<Vec<BoxHeader>> {
+    let mut boxes = Vec::new();
+    let mut offset = start;
+
+    while offset
+        .checked_add(8)
+        .is_some_and(|header_end| header_end <= end)
+    {
+        reader.seek(SeekFrom::Start(offset))?;
+        let mut header = [0u8; 8];
+        reader.read_exact(&mut header)?;
+
+        let size32 = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as u64;
+        let kind = [header[4], header[5], header[6], header[7]];
+
+        let (size, header_len) = match size32 {
+            // A 64-bit size follows the type.
+            1 => {
+                let mut large = [0u8; 8];
+                reader.read_exact(&mut large)?;
+                (u64::from_be_bytes(large), 16)
+            }
+            // The box extends to the end of its parent.
+            0 => (end - offset, 8),
+            size => (size, 8),
+        };
+
+        // Sizes come from the file, so a crafted one must not wrap around.
+        let Some(box_end) = offset
+            .checked_add(size)
+            .filter(|box_end| size >= header_len && *box_end <= end)
+        else {
+            break;
+        };
+
+        boxes.push(BoxHeader {
+            kind,
+            payload_start: offset + header_len,
+            end: box_end,
+        });
+        offset = box_end;
+    }
+
+    Ok(boxes)
+}
+
+/// Lists the children of a full box (`meta`, `iinf`, ...), whose payload starts with
+/// a version and flags word.
+pub fn full_box_children<R: Read + Seek>(
+    reader: &mut R,
+    parent: &BoxHeader,
+    skip: u64,
+) -> io::Result<Vec<BoxHeader>> {
+    children(
+        reader,
+        parent.payload_start.saturating_add(4 + skip),
+        parent.end,
+    )
+}
+
+pub fn find<'a>(boxes: &'a [BoxHeader], kind: &[u8; 4]) -> Option<&'a BoxHeader> {
+    boxes.iter().find(|b| b.is(kind))
+}
+
+pub fn read_payload<R: Read + Seek>(reader: &mut R, header: &BoxHeader) -> io::Result<Vec<u8>> {
+    if header.payload_len() > MAX_PAYLOAD_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Box payload is too large",
+        ));
+    }
+
+    reader.seek(SeekFrom::Start(header.payload_start))?;
+    let mut payload = vec![0u8; header.payload_len() as usize];
+    reader.read_exact(&mut payload)?;
+    Ok(payload)
+}
+
+/// Big-endian cursor over a box payload. Reads past the end yield `None`.
+pub struct Cursor<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(data: &'a [u8]) -> Cursor<'a> {
+        Cursor { data, position: 0 }
+    }
+
+    pub fn skip(&mut self, count: usize) -> Option<()> {
+        self.take(count).map(|_| ())
+    }
+
+    pub fn take(&mut self, count: usize) -> Option<&'a [u8]> {
+        let end = self.position.checked_add(count)?;
+        let bytes = self.data.get(self.position..end)?;
+        self.position = end;
+        Some(bytes)
+    }
+
+    pub fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|bytes| bytes[0])
+    }
+
+    pub fn u16(&mut self) -> Option<u16> {
+        self.take(2)
+            .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    pub fn u32(&mut self) -> Option<u32> {
+        self.take(4)
+            .map(|bytes| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    pub fn u64(&mut self) -> Option<u64> {
+        self.take(8).map(|bytes| {
+            let mut buf = [0u8; 8];
+            buf.copy_from_slice(bytes);
+            u64::from_be_bytes(buf)
+        })
+    }
+
+    pub fn fourcc(&mut self) -> Option<[u8; 4]> {
+        self.take(4)
+            .map(|bytes| [bytes[0], bytes[1], bytes[2], bytes[3]])
+    }
+}
@@ -0,0 +1,563 @@
+use std::io::{self, Read, Seek, SeekFrom};
+
+use crate::media::video_container::{
+    AudioStream, Chapter, SubtitleTrack, VideoContainer, language, malformed,
+};
+
+const EBML: u32 = 0x1A45DFA3;
+const DOC_TYPE: u32 = 0x4282;
+const SEGMENT: u32 = 0x18538067;
+const SEEK_HEAD: u32 = 0x114D9B74;
+const SEEK: u32 = 0x4DBB;
+const SEEK_ID: u32 = 0x53AB;
+const SEEK_POSITION: u32 = 0x53AC;
+const CLUSTER: u32 = 0x1F43B675;
+
+const INFO: u32 = 0x1549A966;
+const TIMESTAMP_SCALE: u32 = 0x2AD7B1;
+const DURATION: u32 = 0x4489;
+
+const TRACKS: u32 = 0x1654AE6B;
+const TRACK_ENTRY: u32 = 0xAE;
+const TRACK_TYPE: u32 = 0x83;
+const FLAG_ENABLED: u32 = 0xB9;
+const FLAG_DEFAULT: u32 = 0x88;
+const FLAG_FORCED: u32 = 0x55AA;
+const DEFAULT_DURATION: u32 = 0x23E383;
+const NAME: u32 = 0x536E;
+const LANGUAGE: u32 = 0x22B59C;
+const LANGUAGE_BCP47: u32 = 0x22B59D;
+const CODEC_ID: u32 = 0x86;
+const VIDEO: u32 = 0xE0;
+const PIXEL_WIDTH: u32 = 0xB0;
+const PIXEL_HEIGHT: u32 = 0xBA;
+const AUDIO: u32 = 0xE1;
+const SAMPLING_FREQUENCY: u32 = 0xB5;
+const CHANNELS: u32 = 0x9F;
+
+const CHAPTERS: u32 = 0x1043A770;
+const EDITION_ENTRY: u32 = 0x45B9;
+const EDITION_FLAG_DEFAULT: u32 = 0x45DB;
+const CHAPTER_ATOM: u32 = 0xB6;
+const CHAPTER_TIME_START: u32 = 0x91;
+const CHAPTER_TIME_END: u32 = 0x92;
+const CHAPTER_FLAG_HIDDEN: u32 = 0x98;
+const CHAPTER_DISPLAY: u32 = 0x80;
+const CHAP_STRING: u32 = 0x85;
+
+const TRACK_TYPE_VIDEO: u64 = 1;
+const TRACK_TYPE_AUDIO: u64 = 2;
+const TRACK_TYPE_SUBTITLE: u64 = 17;
+
+/// Strings and binary values longer than this are never read into memory.
+const MAX_VALUE_SIZE: u64 = 64 * 1024;
+
+#[derive(Debug, Clone, Copy)]
+struct Element {
+    id: u32,
+    data_start: u64,
+    /// `None` for elements of unknown size, which run until their parent ends.
+    end: Option<u64>,
+}
+
+#[derive(Default)]
+struct TrackEntry {
+    track_type: u64,
+    codec_id: String,
+    name: Option<String>,
+    language: Option<String>,
+    enabled: bool,
+    default: bool,
+    forced: bool,
+    default_duration: u64,
+    width: u32,
+    height: u32,
+    channels: Option<u32>,
+    sample_rate: Option<u32>,
+}
+
+pub fn read<R: Read + Seek>(reader: &mut R, file_len: u64) -> io::Result<VideoContainer> {
+    let header = read_element(reader)?;
+    let header_end = header.end.ok_or_else(|| malformed("EBML header"))?;
+    if header.id != EBML {
+        return Err(malformed("EBML header"));
+    }
+
+    let mut format = "mkv";
+    for child in children(reader, header.data_start, header_end)? {
+        if child.id == DOC_TYPE && read_string(reader, &child)? == "webm" {
+            format = "webm";
+        }
+    }
+
+    reader.seek(SeekFrom::Start(header_end))?;
+    let segment = read_element(reader)?;
+    if segment.id != SEGMENT {
+        return Err(malformed("Matroska segment"));
+    }
+    let segment_end = segment.end.unwrap_or(file_len).min(file_len);
+
+    let mut info = None;
+    let mut tracks = None;
+    let mut chapters = None;
+    let mut seek_positions = Vec::new();
+
+    // Metadata normally precedes the clusters; elements written after them are found
+    // through the seek head instead of walking every cluster.
+    let mut offset = segment.data_start;
+    while offset < segment_end {
+        reader.seek(SeekFrom::Start(offset))?;
+        let element = read_element(reader)?;
+
+        match element.id {
+            INFO => info = Some(element),
+            TRACKS => tracks = Some(element),
+            CHAPTERS => chapters = Some(element),
+            SEEK_HEAD => {
+                seek_positions.extend(read_seek_head(reader, &element, segment.data_start)?)
+            }
+            CLUSTER => break,
+            _ => {}
+        }
+
+        match element.end {
+            Some(end) => offset = end,
+            None => break,
+        }
+    }
+
+    for (id, position) in seek_positions {
+        let slot = match id {
+            INFO => &mut info,
+            TRACKS => &mut tracks,
+            CHAPTERS => &mut chapters,
+            _ => continue,
+        };
+        if slot.is_some() || position >= segment_end {
+            continue;
+        }
+
+        reader.seek(SeekFrom::Start(position))?;
+        let element = read_element(reader)?;
+        if element.id == id {
+            *slot = Some(element);
+        }
+    }
+
+    let (timestamp_scale, duration) = match info {
+        Some(info) => read_info(reader, &info, segment_end)?,
+        None => (1_000_000, 0.0),
+    };
+    let entries = match tracks {
+        Some(tracks) => read_tracks(reader, &tracks, segment_end)?,
+        None => Vec::new(),
+    };
+    let chapters = match chapters {
+        Some(chapters) => read_chapters(reader, &chapters, segment_end)?,
+        None => Vec::new(),
+    };
+
+    let mut container = VideoContainer {
+        format,
+        duration: duration * timestamp_scale as f64 / 1_000_000_000.0,
+        fps: 0.0,
+        width: 0,
+        height: 0,
+        codec: String::new(),
+        audio_streams: Vec::new(),
+        subtitles: Vec::new(),
+        chapters,
+    };
+
+    if let Some(video) = entries.iter().find(|t| t.track_type == TRACK_TYPE_VIDEO) {
+        container.width = video.width;
+        container.height = video.height;
+        container.codec = codec_name(&video.codec_id);
+        if video.default_duration > 0 {
+            container.fps = 1_000_000_000.0 / video.default_duration as f64;
+        }
+    }
+
+    for track in entries.iter().filter(|t| t.enabled) {
+        match track.track_type {
+            TRACK_TYPE_AUDIO => container.audio_streams.push(AudioStream {
+                index: container.audio_streams.len(),
+                codec: codec_name(&track.codec_id),
+                language: track.language.clone(),
+                title: track.name.clone(),
+                channels: track.channels,
+                sample_rate: track.sample_rate,
+                default: track.default,
+            }),
+            TRACK_TYPE_SUBTITLE => container.subtitles.push(SubtitleTrack {
+                index: container.subtitles.len(),
+                codec: codec_name(&track.codec_id),
+                language: track.language.clone(),
+                title: track.name.clone(),
+                default: track.default,
+                forced: track.forced,
+            }),
+            _ => {}
+        }
+    }
+
+    Ok(container)
+}
+
+/// Returns the scale of segment timestamps in nanoseconds and the duration in ticks.
+fn read_info<R: Read + Seek>(
+    reader: &mut R,
+    info: &Element,
+    parent_end: u64,
+) -> io::Result<(u64, f64)> {
+    let mut timestamp_scale = 1_000_000;
+    let mut duration = 0.0;
+
+    for child in children(reader, info.data_start, info.end.unwrap_or(parent_end))? {
+        match child.id {
+            TIMESTAMP_SCALE => timestamp_scale = read_uint(reader, &child)?.max(1),
+            DURATION => duration = read_float(reader, &child)?,
+            _ => {}
+        }
+    }
+
+    Ok((timestamp_scale, duration))
+}
+
+fn read_seek_head<R: Read + Seek>(
+    reader: &mut R,
+    seek_head: &Element,
+    segment_start: u64,
+) -> io::Result<Vec<(u32, u64)>> {
+    let Some(end) = seek_head.end else {
+        return Ok(Vec::new());
+    };
+
+    let mut positions = Vec::new();
+    for seek in children(reader, seek_head.data_start, end)? {
+        let Some(seek_end) = seek.end.filter(|_| seek.id == SEEK) else {
+            continue;
+        };
+
+        let mut id = None;
+        let mut position = None;
+        for child in children(reader, seek.data_start, seek_end)? {
+            match child.id {
+                // The id is stored as its raw bytes, which read back as an unsigned int.
+                SEEK_ID => id = Some(read_uint(reader, &child)? as u32),
+                SEEK_POSITION => position = Some(read_uint(reader, &child)?),
+                _ => {}
+            }
+        }
+
+        // Positions come from the file; one past the address space points nowhere.
+        if let (Some(id), Some(position)) = (id, position)
+            && let Some(position) = segment_start.checked_add(position)
+        {
+            positions.push((id, position));
+        }
+    }
+
+    Ok(positions)
+}
+
+fn read_tracks<R: Read + Seek>(
+    reader: &mut R,
+    tracks: &Element,
+    parent_end: u64,
+) -> io::Result<Vec<TrackEntry>> {
+    let mut entries = Vec::new();
+
+    for entry in children(reader, tracks.data_start, tracks.end.unwrap_or(parent_end))? {
+        let Some(entry_end) = entry.end.filter(|_| entry.id == TRACK_ENTRY) else {
+            continue;
+        };
+
+        // Spec defaults for elements a muxer may leave out.
+        let mut track = TrackEntry {
+            enabled: true,
+            default: true,
+            language: Some("eng".to_string()),
+            ..TrackEntry::default()
+        };
+        let mut bcp47 = None;
+
+        for child in children(reader, entry.data_start, entry_end)? {
+            match child.id {
+                TRACK_TYPE => track.track_type = read_uint(reader, &child)?,
+                FLAG_ENABLED => track.enabled = read_uint(reader, &child)? != 0,
+                FLAG_DEFAULT => track.default = read_uint(reader, &child)? != 0,
+                FLAG_FORCED => track.forced = read_uint(reader, &child)? != 0,
+                DEFAULT_DURATION => track.default_duration = read_uint(reader, &child)?,
+                NAME => track.name = Some(read_string(reader, &child)?).filter(|n| !n.is_empty()),
+                LANGUAGE => track.language = language(&read_string(reader, &child)?),
+                LANGUAGE_BCP47 => bcp47 = language(&read_string(reader, &child)?),
+                CODEC_ID => track.codec_id = read_string(reader, &child)?,
+                VIDEO => {
+                    for setting in
+                        children(reader, child.data_start, child.end.unwrap_or(entry_end))?
+                    {
+                        match setting.id {
+                            PIXEL_WIDTH => track.width = read_uint(reader, &setting)? as u32,
+                            PIXEL_HEIGHT => track.height = read_uint(reader, &setting)? as u32,
+                            _ => {}
+                        }
+                    }
+                }
+                AUDIO => {
+                    // Sampling frequency defaults to 8kHz and channels to mono.
+                    track.sample_rate = Some(8000);
+                    track.channels = Some(1);
+                    for setting in
+                        children(reader, child.data_start, child.end.unwrap_or(entry_end))?
+                    {
+                        match setting.id {
+                            SAMPLING_FREQUENCY => {
+                                track.sample_rate =
+                                    Some(read_float(reader, &setting)?.round() as u32)
+                            }
+                            CHANNELS => track.channels = Some(read_uint(reader, &setting)? as u32),
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // The BCP 47 tag overrides the legacy ISO 639-2 one when both are present.
+        if bcp47.is_some() {
+            track.language = bcp47;
+        }
+        entries.push(track);
+    }
+
+    Ok(entries)
+}
+
+/// Reads the default edition, or the first one when none is flagged. Hidden chapters
+/// and nested sub-chapters are left out.
+fn read_chapters<R: Read + Seek>(
+    reader: &mut R,
+    chapters: &Element,
+    parent_end: u64,
+) -> io::Result<Vec<Chapter>> {
+    let mut editions = Vec::new();
+    for edition in children(
+        reader,
+        chapters.data_start,
+        chapters.end.unwrap_or(parent_end),
+    )? {
+        let Some(edition_end) = edition.end.filter(|_| edition.id == EDITION_ENTRY) else {
+            continue;
+        };
+
+        let mut is_default = false;
+        let mut atoms = Vec::new();
+        for child in children(reader, edition.data_start, edition_end)? {
+            match child.id {
+                EDITION_FLAG_DEFAULT => is_default = read_uint(reader, &child)? != 0,
+                CHAPTER_ATOM => atoms.extend(child.end.map(|end| (child.data_start, end))),
+                _ => {}
+            }
+        }
+        editions.push((is_default, atoms));
+    }
+
+    let Some(atoms) = editions
+        .iter()
+        .find(|(is_default, _)| *is_default)
+        .or(editions.first())
+        .map(|(_, atoms)| atoms)
+    else {
+        return Ok(Vec::new());
+    };
+
+    let mut chapters = Vec::new();
+    for &(start, end) in atoms {
+        let mut chapter = Chapter {
+            title: None,
+            start: 0.0,
+            end: None,
+        };
+        let mut hidden = false;
+
+        for child in children(reader, start, end)? {
+            match child.id {
+                // Chapter times are in nanoseconds, independent of the timestamp scale.
+                CHAPTER_TIME_START => chapter.start = read_uint(reader, &child)? as f64 / 1e9,
+                CHAPTER_TIME_END => chapter.end = Some(read_uint(reader, &child)? as f64 / 1e9),
+                CHAPTER_FLAG_HIDDEN => hidden = read_uint(reader, &child)? != 0,
+                CHAPTER_DISPLAY if chapter.title.is_none() => {
+                    let display_end = child.end.unwrap_or(end);
+                    for display in children(reader, child.data_start, display_end)? {
+                        if display.id == CHAP_STRING {
+                            chapter.title =
+                                Some(read_string(reader, &display)?).filter(|t| !t.is_empty());
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if !hidden {
+            chapters.push(chapter);
+        }
+    }
+
+    Ok(chapters)
+}
+
+/// Lists the elements between `start` and `end`. Stops at an element of unknown size,
+/// since its extent can only be found by parsing its contents.
+fn children<R: Read + Seek>(reader: &mut R, start: u64, end: u64) -> io::Result<Vec<Element>> {
+    let mut elements = Vec::new();
+    let mut offset = start;
+
+    while offset < end {
+        reader.seek(SeekFrom::Start(offset))?;
+        let element = read_element(reader)?;
+
+        match element.end {
+            Some(element_end) if element_end <= end => {
+                elements.push(element);
+                offset = element_end;
+            }
+            _ => break,
+        }
+    }
+
+    Ok(elements)
+}
+
+fn read_element<R: Read + Seek>(reader: &mut R) -> io::Result<Element> {
+    let (id, _) = read_vint(reader, 4, false)?;
+    let (size, length) = read_vint(reader, 8, true)?;
+    let data_start = reader.stream_position()?;
+
+    // A size with every value bit set means unknown.
+    let unknown = size == (1u64 << (7 * length)) - 1;
+
+    Ok(Element {
+        id: id as u32,
+        data_start,
+        end: (!unknown).then(|| data_start.saturating_add(size)),
+    })
+}
+
+/// Reads an EBML variable-length integer of at most `max_length` bytes. Ids keep their
+/// length marker bit, sizes drop it.
+fn read_vint<R: Read>(
+    reader: &mut R,
+    max_length: u32,
+    strip_marker: bool,
+) -> io::Result<(u64, u32)> {
+    let mut first = [0u8; 1];
+    reader.read_exact(&mut first)?;
+
+    let length = first[0].leading_zeros() + 1;
+    if length > max_length {
+        return Err(malformed("EBML variable-length integer"));
+    }
+
+    let mut value = if strip_marker {
+        (first[0] & (0xFF >> length)) as u64
+    } else {
+        first[0] as u64
+    };
+
+    for _ in 1..length {
+        let mut byte = [0u8; 1];
+        reader.read_exact(&mut byte)?;
+        value = (value << 8) | byte[0] as u64;
+    }
+
+    Ok((value, length))
+}
+
+fn read_data<R: Read + Seek>(reader: &mut R, element: &Element) -> io::Result<Vec<u8>> {
+    let size = element.end.unwrap_or(element.data_start) - element.data_start;
+    if size > MAX_VALUE_SIZE {
+        return Err(malformed("Matroska value"));
+    }
+
+    reader.seek(SeekFrom::Start(element.data_start))?;
+    let mut data = vec![0u8; size as usize];
+    reader.read_exact(&mut data)?;
+    Ok(data)
+}
+
+fn read_uint<R: Read + Seek>(reader: &mut R, element: &Element) -> io::Result<u64> {
+    let data = read_data(reader, element)?;
+    if data.len() > 8 {
+        return Err(malformed("Matroska unsigned integer"));
+    }
+
+    Ok(data
+        .iter()
+        .fold(0, |value, &byte| (value << 8) | byte as u64))
+}
+
+fn read_float<R: Read + Seek>(reader: &mut R, element: &Element) -> io::Result<f64> {
+    let data = read_data(reader, element)?;
+
+    match data.len() {
+        0 => Ok(0.0),
+        4 => Ok(f32::from_be_bytes([data[0], data[1], data[2], data[3]]) as f64),
+        8 => {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&data);
+            Ok(f64::from_be_bytes(bytes))
+        }
+        _ => Err(malformed("Matroska float")),
+    }
+}
+
+fn read_string<R: Read + Seek>(reader: &mut R, element: &Element) -> io::Result<String> {
+    let data = read_data(reader, element)?;
+    Ok(String::from_utf8_lossy(&data)
+        .trim_end_matches(char::from(0))
+        .to_string())
+}
+
+fn codec_name(codec_id: &str) -> String {
+    let name = match codec_id {
+        "V_MPEG4/ISO/AVC" => "h264",
+        "V_MPEGH/ISO/HEVC" => "hevc",
+        "V_AV1" => "av1",
+        "V_VP8" => "vp8",
+        "V_VP9" => "vp9",
+        "V_MPEG1" => "mpeg1video",
+        "V_MPEG2" => "mpeg2video",
+        "V_MJPEG" => "mjpeg",
+        "V_THEORA" => "theora",
+        "V_PRORES" => "prores",
+        "A_AAC" => "aac",
+        "A_AC3" => "ac3",
+        "A_EAC3" => "eac3",
+        "A_DTS" => "dts",
+        "A_TRUEHD" => "truehd",
+        "A_OPUS" => "opus",
+        "A_VORBIS" => "vorbis",
+        "A_FLAC" => "flac",
+        "A_ALAC" => "alac",
+        "A_MPEG/L3" => "mp3",
+        "A_MPEG/L2" => "mp2",
+        "S_TEXT/UTF8" => "subrip",
+        "S_TEXT/ASS" | "S_ASS" => "ass",
+        "S_TEXT/SSA" | "S_SSA" => "ssa",
+        "S_TEXT/WEBVTT" => "webvtt",
+        "S_HDMV/PGS" => "pgs",
+        "S_HDMV/TEXTST" => "hdmv_text",
+        "S_VOBSUB" => "dvd_subtitle",
+        "S_DVBSUB" => "dvb_subtitle",
+        id if id.starts_with("V_MPEG4/ISO/") || id.starts_with("V_MPEG4/MS/") => "mpeg4",
+        id if id.starts_with("A_AAC/") => "aac",
+        id if id.starts_with("A_PCM/") => "pcm",
+        id if id.starts_with("A_DTS/") => "dts",
+        id => return id.to_lowercase(),
+    };
+
+    name.to_string()
+}
@@ -0,0 +1,25 @@
+pub mod find_similar_images;
+pub mod image_headers;
+pub mod isobmff;
+pub mod matroska;
+pub mod mp4;
+pub mod perceptual_hash;
+pub mod probe_image;
+pub mod probe_video;
+pub mod video_container;
+
+/// Quality buckets accepted by the `quality` CHECK constraint of `images` and `videos`.
+const QUALITY_BUCKETS: [u32; 6] = [320, 480, 720, 1080, 2160, 4320];
+
+/// Buckets media by its shorter side, so portrait and landscape media of the same size
+/// land in the same bucket. Anything below the smallest bucket is stored as 320.
+pub fn quality_bucket(width: u32, height: u32) -> u32 {
+    let short_side = width.min(height);
+
+    QUALITY_BUCKETS
+        .iter()
+        .rev()
+        .copied()
+        .find(|&bucket| bucket <= short_side)
+        .unwrap_or(QUALITY_BUCKETS[0])
+}
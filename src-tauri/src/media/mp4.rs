@@ -0,0 +1,605 @@
+use std::io::{self, Read, Seek, SeekFrom};
+
+use crate::media::isobmff::{self, BoxHeader, Cursor};
+use crate::media::video_container::{
+    AudioStream, Chapter, SubtitleTrack, VideoContainer, language, malformed,
+};
+
+/// Chapter tracks are tiny; anything beyond this is not a chapter list.
+const MAX_CHAPTERS: usize = 1000;
+
+#[derive(Default)]
+struct Track {
+    id: u32,
+    enabled: bool,
+    handler: [u8; 4],
+    timescale: u32,
+    duration: u64,
+    language: Option<String>,
+    title: Option<String>,
+    display_width: u32,
+    display_height: u32,
+    /// Type and payload of the first sample description.
+    sample_entry: Option<([u8; 4], Vec<u8>)>,
+    sample_count: u64,
+    sample_duration: u64,
+    chapter_track_ids: Vec<u32>,
+    stbl: Option<BoxHeader>,
+}
+
+impl Track {
+    fn is_subtitle(&self) -> bool {
+        matches!(&self.handler, b"sbtl" | b"subt" | b"text" | b"clcp")
+    }
+}
+
+pub fn read<R: Read + Seek>(reader: &mut R, file_len: u64) -> io::Result<VideoContainer> {
+    let top = isobmff::children(reader, 0, file_len)?;
+
+    let format = match isobmff::find(&top, b"ftyp") {
+        Some(ftyp) if !isobmff::read_payload(reader, ftyp)?.starts_with(b"qt  ") => "mp4",
+        _ => "mov",
+    };
+
+    let moov = isobmff::find(&top, b"moov").ok_or_else(|| malformed("MP4 without moov"))?;
+    let moov_children = isobmff::children(reader, moov.payload_start, moov.end)?;
+
+    let mut duration = match isobmff::find(&moov_children, b"mvhd") {
+        Some(mvhd) => movie_duration(&isobmff::read_payload(reader, mvhd)?),
+        None => 0.0,
+    };
+    if duration == 0.0 {
+        duration = fragment_duration(reader, &moov_children)?;
+    }
+
+    let mut tracks = Vec::new();
+    for trak in moov_children.iter().filter(|b| b.is(b"trak")) {
+        tracks.push(read_track(reader, trak)?);
+    }
+
+    if duration == 0.0 {
+        duration = tracks
+            .iter()
+            .filter(|t| t.timescale > 0)
+            .map(|t| t.duration as f64 / t.timescale as f64)
+            .fold(0.0, f64::max);
+    }
+
+    let chapter_track_ids: Vec<u32> = tracks
+        .iter()
+        .flat_map(|t| t.chapter_track_ids.iter().copied())
+        .collect();
+
+    let mut chapters = nero_chapters(reader, &moov_children)?.unwrap_or_default();
+    if chapters.is_empty()
+        && let Some(track) = tracks.iter().find(|t| chapter_track_ids.contains(&t.id))
+    {
+        chapters = quicktime_chapters(reader, track)?;
+    }
+
+    let mut container = VideoContainer {
+        format,
+        duration,
+        fps: 0.0,
+        width: 0,
+        height: 0,
+        codec: String::new(),
+        audio_streams: Vec::new(),
+        subtitles: Vec::new(),
+        chapters,
+    };
+
+    if let Some(video) = tracks.iter().find(|t| &t.handler == b"vide") {
+        let (width, height) = video
+            .sample_entry
+            .as_ref()
+            .and_then(|(_, payload)| visual_size(payload))
+            .unwrap_or((video.display_width, video.display_height));
+
+        container.width = width;
+        container.height = height;
+        container.codec = video
+            .sample_entry
+            .as_ref()
+            .map(|(kind, _)| codec_name(kind))
+            .unwrap_or_default();
+        if video.sample_duration > 0 {
+            container.fps =
+                video.sample_count as f64 * video.timescale as f64 / video.sample_duration as f64;
+        }
+    }
+
+    for track in tracks.iter().filter(|t| &t.handler == b"soun") {
+        let (channels, sample_rate) = track
+            .sample_entry
+            .as_ref()
+            .and_then(|(_, payload)| audio_format(payload))
+            .unzip();
+
+        container.audio_streams.push(AudioStream {
+            index: container.audio_streams.len(),
+            codec: track
+                .sample_entry
+                .as_ref()
+                .map(|(kind, _)| codec_name(kind))
+                .unwrap_or_default(),
+            language: track.language.clone(),
+            title: track.title.clone(),
+            channels,
+            sample_rate,
+            default: track.enabled,
+        });
+    }
+
+    for track in tracks
+        .iter()
+        .filter(|t| t.is_subtitle() && !chapter_track_ids.contains(&t.id))
+    {
+        container.subtitles.push(SubtitleTrack {
+            index: container.subtitles.len(),
+            codec: track
+                .sample_entry
+                .as_ref()
+                .map(|(kind, _)| codec_name(kind))
+                .unwrap_or_default(),
+            language: track.language.clone(),
+            title: track.title.clone(),
+            default: track.enabled,
+            forced: false,
+        });
+    }
+
+    Ok(container)
+}
+
+fn movie_duration(mvhd: &[u8]) -> f64 {
+    let mut cursor = Cursor::new(mvhd);
+    let Some(version) = cursor.u8() else {
+        return 0.0;
+    };
+    cursor.skip(3);
+
+    let (timescale, duration) = if version == 1 {
+        cursor.skip(16);
+        (cursor.u32(), cursor.u64())
+    } else {
+        cursor.skip(8);
+        (cursor.u32(), cursor.u32().map(u64::from))
+    };
+
+    match (timescale, duration) {
+        // An all-ones duration means unknown.
+        (Some(timescale), Some(duration))
+            if timescale > 0 && duration != u64::MAX && duration != u32::MAX as u64 =>
+        {
+            duration as f64 / timescale as f64
+        }
+        _ => 0.0,
+    }
+}
+
+/// Fragmented files leave `mvhd` empty and put the total in `mvex/mehd`, in movie
+/// timescale units.
+fn fragment_duration<R: Read + Seek>(reader: &mut R, moov: &[BoxHeader]) -> io::Result<f64> {
+    let (Some(mvhd), Some(mvex)) = (isobmff::find(moov, b"mvhd"), isobmff::find(moov, b"mvex"))
+    else {
+        return Ok(0.0);
+    };
+
+    let mvhd = isobmff::read_payload(reader, mvhd)?;
+    let mut cursor = Cursor::new(&mvhd);
+    let version = cursor.u8();
+    cursor.skip(if version == Some(1) { 19 } else { 11 });
+    let Some(timescale) = cursor.u32().filter(|&t| t > 0) else {
+        return Ok(0.0);
+    };
+
+    let mvex_children = isobmff::children(reader, mvex.payload_start, mvex.end)?;
+    let Some(mehd) = isobmff::find(&mvex_children, b"mehd") else {
+        return Ok(0.0);
+    };
+
+    let mehd = isobmff::read_payload(reader, mehd)?;
+    let mut cursor = Cursor::new(&mehd);
+    let duration = match cursor.u8() {
+        Some(1) => cursor.skip(3).and_then(|_| cursor.u64()),
+        Some(_) => cursor.skip(3).and_then(|_| cursor.u32()).map(u64::from),
+        None => None,
+    };
+
+    Ok(duration.map_or(0.0, |d| d as f64 / timescale as f64))
+}
+
+fn read_track<R: Read + Seek>(reader: &mut R, trak: &BoxHeader) -> io::Result<Track> {
+    let mut track = Track::default();
+
+    for child in isobmff::children(reader, trak.payload_start, trak.end)? {
+        match &child.kind {
+            b"tkhd" => read_tkhd(&isobmff::read_payload(reader, &child)?, &mut track),
+            b"tref" => {
+                for reference in isobmff::children(reader, child.payload_start, child.end)? {
+                    if reference.is(b"chap") {
+                        let ids = isobmff::read_payload(reader, &reference)?;
+                        let mut cursor = Cursor::new(&ids);
+                        while let Some(id) = cursor.u32() {
+                            track.chapter_track_ids.push(id);
+                        }
+                    }
+                }
+            }
+            b"udta" => {
+                let udta = isobmff::children(reader, child.payload_start, child.end)?;
+                if let Some(name) = isobmff::find(&udta, b"name") {
+                    let name = isobmff::read_payload(reader, name)?;
+                    track.title = text(&name);
+                }
+            }
+            b"mdia" => read_mdia(reader, &child, &mut track)?,
+            _ => {}
+        }
+    }
+
+    Ok(track)
+}
+
+fn read_tkhd(tkhd: &[u8], track: &mut Track) {
+    let mut cursor = Cursor::new(tkhd);
+    let (Some(version), Some(flags)) = (cursor.u8(), cursor.take(3)) else {
+        return;
+    };
+    track.enabled = flags[2] & 0x01 != 0;
+
+    cursor.skip(if version == 1 { 16 } else { 8 });
+    track.id = cursor.u32().unwrap_or_default();
+    cursor.skip(if version == 1 { 12 } else { 8 });
+    // Reserved, layer, alternate group, volume, reserved and the matrix.
+    cursor.skip(52);
+
+    // 16.16 fixed point.
+    track.display_width = cursor.u32().unwrap_or_default() >> 16;
+    track.display_height = cursor.u32().unwrap_or_default() >> 16;
+}
+
+fn read_mdia<R: Read + Seek>(
+    reader: &mut R,
+    mdia: &BoxHeader,
+    track: &mut Track,
+) -> io::Result<()> {
+    for child in isobmff::children(reader, mdia.payload_start, mdia.end)? {
+        match &child.kind {
+            b"mdhd" => {
+                let mdhd = isobmff::read_payload(reader, &child)?;
+                let mut cursor = Cursor::new(&mdhd);
+                let version = cursor.u8();
+                cursor.skip(3);
+                if version == Some(1) {
+                    cursor.skip(16);
+                    track.timescale = cursor.u32().unwrap_or_default();
+                    track.duration = cursor.u64().unwrap_or_default();
+                } else {
+                    cursor.skip(8);
+                    track.timescale = cursor.u32().unwrap_or_default();
+                    track.duration = cursor.u32().unwrap_or_default() as u64;
+                }
+                track.language = cursor.u16().and_then(packed_language);
+            }
+            b"hdlr" => {
+                let hdlr = isobmff::read_payload(reader, &child)?;
+                let mut cursor = Cursor::new(&hdlr);
+                cursor.skip(8);
+                track.handler = cursor.fourcc().unwrap_or_default();
+            }
+            b"minf" => {
+                let minf = isobmff::children(reader, child.payload_start, child.end)?;
+                if let Some(stbl) = isobmff::find(&minf, b"stbl") {
+                    read_stbl(reader, stbl, track)?;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn read_stbl<R: Read + Seek>(
+    reader: &mut R,
+    stbl: &BoxHeader,
+    track: &mut Track,
+) -> io::Result<()> {
+    track.stbl = Some(*stbl);
+    let tables = isobmff::children(reader, stbl.payload_start, stbl.end)?;
+
+    if let Some(stsd) = isobmff::find(&tables, b"stsd") {
+        // Skip the entry count; only the first description matters.
+        let entries = isobmff::full_box_children(reader, stsd, 4)?;
+        if let Some(entry) = entries.first() {
+            track.sample_entry = Some((entry.kind, isobmff::read_payload(reader, entry)?));
+        }
+    }
+
+    if let Some(stts) = isobmff::find(&tables, b"stts") {
+        for (count, delta) in time_to_sample(&isobmff::read_payload(reader, stts)?) {
+            track.sample_count = track.sample_count.saturating_add(count as u64);
+            track.sample_duration = track
+                .sample_duration
+                .saturating_add(count as u64 * delta as u64);
+        }
+    }
+
+    Ok(())
+}
+
+/// `stts` entries as (sample count, sample duration) pairs.
+fn time_to_sample(stts: &[u8]) -> Vec<(u32, u32)> {
+    let mut cursor = Cursor::new(stts);
+    cursor.skip(4);
+    let count = cursor.u32().unwrap_or_default();
+
+    (0..count)
+        .map_while(|_| Some((cursor.u32()?, cursor.u32()?)))
+        .collect()
+}
+
+/// Width and height from a visual sample entry.
+fn visual_size(entry: &[u8]) -> Option<(u32, u32)> {
+    let mut cursor = Cursor::new(entry);
+    cursor.skip(24)?;
+    let width = cursor.u16()? as u32;
+    let height = cursor.u16()? as u32;
+    (width > 0 && height > 0).then_some((width, height))
+}
+
+/// Channel count and sample rate from an audio sample entry.
+fn audio_format(entry: &[u8]) -> Option<(u32, u32)> {
+    let mut cursor = Cursor::new(entry);
+    cursor.skip(8)?;
+    let version = cursor.u16()?;
+    cursor.skip(6)?;
+    let channels = cursor.u16()? as u32;
+    cursor.skip(6)?;
+    let sample_rate = cursor.u32()? >> 16;
+
+    if version == 2 {
+        // QuickTime sound description v2 moves both fields behind the legacy ones.
+        cursor.skip(4)?;
+        let sample_rate = f64::from_bits(cursor.u64()?);
+        let channels = cursor.u32()?;
+        return Some((channels, sample_rate.round() as u32));
+    }
+
+    Some((channels, sample_rate))
+}
+
+/// Three 5-bit letters offset by 0x60. Smaller values are legacy Macintosh codes.
+fn packed_language(packed: u16) -> Option<String> {
+    if packed < 0x400 {
+        return None;
+    }
+
+    let letters: String = [10, 5, 0]
+        .iter()
+        .map(|shift| char::from((((packed >> shift) & 0x1F) as u8) + 0x60))
+        .collect();
+    language(&letters)
+}
+
+/// Nero chapters (`moov/udta/chpl`), written by ffmpeg and most muxers, with start
+/// times in 100ns units.
+fn nero_chapters<R: Read + Seek>(
+    reader: &mut R,
+    moov: &[BoxHeader],
+) -> io::Result<Option<Vec<Chapter>>> {
+    let Some(udta) = isobmff::find(moov, b"udta") else {
+        return Ok(None);
+    };
+    let udta = isobmff::children(reader, udta.payload_start, udta.end)?;
+    let Some(chpl) = isobmff::find(&udta, b"chpl") else {
+        return Ok(None);
+    };
+
+    let chpl = isobmff::read_payload(reader, chpl)?;
+    let mut cursor = Cursor::new(&chpl);
+    let version = cursor.u8().unwrap_or_default();
+    cursor.skip(3);
+    if version == 1 {
+        cursor.skip(4);
+    }
+
+    let count = cursor.u8().unwrap_or_default();
+    let mut chapters = Vec::new();
+    for _ in 0..count {
+        let (Some(start), Some(length)) = (cursor.u64(), cursor.u8()) else {
+            break;
+        };
+        let Some(title) = cursor.take(length as usize) else {
+            break;
+        };
+
+        chapters.push(Chapter {
+            title: text(title),
+            start: start as f64 / 10_000_000.0,
+            end: None,
+        });
+    }
+
+    Ok(Some(chapters))
+}
+
+/// QuickTime chapters: a text track referenced through `tref/chap`, with one sample per
+/// chapter holding a length-prefixed title.
+fn quicktime_chapters<R: Read + Seek>(reader: &mut R, track: &Track) -> io::Result<Vec<Chapter>> {
+    let Some(stbl) = track.stbl.as_ref().filter(|_| track.timescale > 0) else {
+        return Ok(Vec::new());
+    };
+    let tables = isobmff::children(reader, stbl.payload_start, stbl.end)?;
+
+    let payload = |reader: &mut R, kind: &[u8; 4]| match isobmff::find(&tables, kind) {
+        Some(table) => isobmff::read_payload(reader, table).map(Some),
+        None => Ok(None),
+    };
+
+    let (Some(stts), Some(stsz), Some(stsc)) = (
+        payload(reader, b"stts")?,
+        payload(reader, b"stsz")?,
+        payload(reader, b"stsc")?,
+    ) else {
+        return Ok(Vec::new());
+    };
+
+    let chunk_offsets = match (payload(reader, b"stco")?, payload(reader, b"co64")?) {
+        (Some(stco), _) => table_entries(&stco, |cursor| cursor.u32().map(u64::from)),
+        (None, Some(co64)) => table_entries(&co64, |cursor| cursor.u64()),
+        (None, None) => return Ok(Vec::new()),
+    };
+
+    let sizes = sample_sizes(&stsz);
+    let offsets = sample_offsets(&stsc, &chunk_offsets, &sizes);
+
+    let mut starts = Vec::new();
+    let mut time = 0u64;
+    for (count, delta) in time_to_sample(&stts) {
+        for _ in 0..count {
+            if starts.len() >= MAX_CHAPTERS {
+                break;
+            }
+            starts.push(time);
+            time += delta as u64;
+        }
+    }
+
+    let mut chapters = Vec::new();
+    for ((start, offset), size) in starts.into_iter().zip(offsets).zip(sizes) {
+        let mut sample = vec![0u8; size.min(1024) as usize];
+        reader.seek(SeekFrom::Start(offset))?;
+        reader.read_exact(&mut sample)?;
+
+        let mut cursor = Cursor::new(&sample);
+        let length = cursor.u16().unwrap_or_default() as usize;
+        let title = cursor.take(length).and_then(text);
+
+        chapters.push(Chapter {
+            title,
+            start: start as f64 / track.timescale as f64,
+            end: None,
+        });
+    }
+
+    Ok(chapters)
+}
+
+/// Entries of a full box table that starts with an entry count.
+fn table_entries<T>(table: &[u8], read: impl Fn(&mut Cursor) -> Option<T>) -> Vec<T> {
+    let mut cursor = Cursor::new(table);
+    cursor.skip(4);
+    let count = cursor.u32().unwrap_or_default() as usize;
+
+    (0..count.min(MAX_CHAPTERS))
+        .map_while(|_| read(&mut cursor))
+        .collect()
+}
+
+fn sample_sizes(stsz: &[u8]) -> Vec<u32> {
+    let mut cursor = Cursor::new(stsz);
+    cursor.skip(4);
+    let (Some(uniform), Some(count)) = (cursor.u32(), cursor.u32()) else {
+        return Vec::new();
+    };
+    let count = (count as usize).min(MAX_CHAPTERS);
+
+    if uniform != 0 {
+        vec![uniform; count]
+    } else {
+        (0..count).map_while(|_| cursor.u32()).collect()
+    }
+}
+
+/// File offset of each sample, walking the chunks described by `stsc`.
+fn sample_offsets(stsc: &[u8], chunk_offsets: &[u64], sizes: &[u32]) -> Vec<u64> {
+    let runs = table_entries(stsc, |cursor| {
+        let first_chunk = cursor.u32()?;
+        let samples_per_chunk = cursor.u32()?;
+        cursor.skip(4)?;
+        Some((first_chunk as usize, samples_per_chunk as usize))
+    });
+
+    let mut offsets = Vec::with_capacity(sizes.len());
+    let mut sample = 0;
+
+    for (chunk_index, &chunk_offset) in chunk_offsets.iter().enumerate() {
+        // Chunks are numbered from 1.
+        let samples_per_chunk = runs
+            .iter()
+            .rev()
+            .find(|(first_chunk, _)| *first_chunk <= chunk_index + 1)
+            .map_or(0, |(_, samples)| *samples);
+
+        let mut offset = chunk_offset;
+        for _ in 0..samples_per_chunk {
+            let Some(&size) = sizes.get(sample) else {
+                return offsets;
+            };
+            offsets.push(offset);
+            // Offsets and sizes come from the file. Past an overflow no later sample can
+            // be located, and skipping one would shift the rest onto the wrong samples.
+            let Some(next) = offset.checked_add(size as u64) else {
+                return offsets;
+            };
+            offset = next;
+            sample += 1;
+        }
+    }
+
+    offsets
+}
+
+/// Decodes a title stored as UTF-8, or UTF-16 when it starts with a byte order mark.
+fn text(bytes: &[u8]) -> Option<String> {
+    let decoded = match bytes {
+        [0xFE, 0xFF, rest @ ..] => String::from_utf16_lossy(
+            &rest
+                .chunks_exact(2)
+                .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                .collect::<Vec<_>>(),
+        ),
+        [0xFF, 0xFE, rest @ ..] => String::from_utf16_lossy(
+            &rest
+                .chunks_exact(2)
+                .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+                .collect::<Vec<_>>(),
+        ),
+        _ => String::from_utf8_lossy(bytes).into_owned(),
+    };
+
+    let trimmed = decoded.trim_matches(char::from(0)).trim();
+    (!trimmed.is_empty()).then(|| trimmed.to_string())
+}
+
+fn codec_name(kind: &[u8; 4]) -> String {
+    match kind {
+        b"avc1" | b"avc3" => "h264",
+        b"hvc1" | b"hev1" => "hevc",
+        b"av01" => "av1",
+        b"vp08" => "vp8",
+        b"vp09" => "vp9",
+        b"mp4v" => "mpeg4",
+        b"jpeg" | b"mjpa" | b"mjpb" => "mjpeg",
+        b"apch" | b"apcn" | b"apcs" | b"apco" | b"ap4h" | b"ap4x" => "prores",
+        b"mp4a" => "aac",
+        b"ac-3" => "ac3",
+        b"ec-3" => "eac3",
+        b"Opus" => "opus",
+        b"fLaC" => "flac",
+        b"alac" => "alac",
+        b".mp3" => "mp3",
+        b"lpcm" | b"sowt" | b"twos" | b"in24" | b"in32" | b"fl32" | b"fl64" => "pcm",
+        b"tx3g" | b"text" => "mov_text",
+        b"wvtt" => "webvtt",
+        b"stpp" => "ttml",
+        b"c608" => "eia_608",
+        other => {
+            return String::from_utf8_lossy(other).trim().to_lowercase();
+        }
+    }
+    .to_string()
+}
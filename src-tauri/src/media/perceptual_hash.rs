@@ -0,0 +1,35 @@
+use std::path::Path;
+
+use crate::thumbnails::generate::decode_image;
+
+/// Difference hash of an image: the picture is shrunk to 9x8 grayscale pixels and
+/// every bit records whether a pixel is brighter than its right neighbour. Resized or
+/// recompressed copies end up a few bits apart, unrelated pictures around 32.
+pub fn dhash(path: &Path) -> Result<u64, String> {
+    let pixels = decode_image(path)?.thumbnail_exact(9, 8).to_luma8();
+
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash <<= 1;
+            if pixels.get_pixel(x, y)[0] > pixels.get_pixel(x + 1, y)[0] {
+                hash |= 1;
+            }
+        }
+    }
+
+    Ok(hash)
+}
+
+pub fn to_hex(hash: u64) -> String {
+    format!("{:016x}", hash)
+}
+
+pub fn from_hex(hex: &str) -> Option<u64> {
+    u64::from_str_radix(hex, 16).ok()
+}
+
+/// Number of differing bits between two hashes.
+pub fn distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
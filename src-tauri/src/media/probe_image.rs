@@ -0,0 +1,154 @@
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+use std::path::Path;
+
+use crate::library::index;
+use crate::media::image_headers::read_header;
+use crate::media::quality_bucket;
+use crate::utils::db;
+use crate::utils::path_policy::{Operation, PathPolicy};
+
+/// A row of the `images` table. `id` is set once the probe has been stored and linked
+/// to the file's `files.file_metadata_id`, which requires the file to be indexed.
+#[derive(Debug, Serialize)]
+pub struct ImageProbe {
+    pub id: Option<i64>,
+    pub width: u32,
+    pub height: u32,
+    pub resolution: String,
+    pub quality: u32,
+    pub format: String,
+    pub color_space: Option<String>,
+    pub has_exif: u8,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImageProbeResult {
+    pub path: String,
+    pub image: Option<ImageProbe>,
+    pub error: Option<String>,
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub async fn probe_image(handle: tauri::AppHandle, path: String) -> Result<ImageProbe, String> {
+    let resolved = PathPolicy::load(&handle)
+        .await
+        .check(Path::new(&path), Operation::Read)?;
+
+    let mut image = tauri::async_runtime::spawn_blocking(move || probe(&resolved))
+        .await
+        .map_err(|e| format!("Failed to probe image: {}", e))??;
+
+    if let Ok(pool) = db::pool(&handle).await {
+        image.id = store(&pool, &path, &image).await?;
+    }
+
+    Ok(image)
+}
+
+/// Probes every path, reporting failures per entry instead of failing the batch.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn probe_images(
+    handle: tauri::AppHandle,
+    paths: Vec<String>,
+) -> Result<Vec<ImageProbeResult>, String> {
+    let policy = PathPolicy::load(&handle).await;
+
+    let mut results = tauri::async_runtime::spawn_blocking(move || {
+        paths
+            .into_iter()
+            .map(|path| {
+                let probed = policy
+                    .check(Path::new(&path), Operation::Read)
+                    .and_then(|resolved| probe(&resolved));
+
+                match probed {
+                    Ok(image) => ImageProbeResult {
+                        path,
+                        image: Some(image),
+                        error: None,
+                    },
+                    Err(e) => ImageProbeResult {
+                        path,
+                        image: None,
+                        error: Some(e),
+                    },
+                }
+            })
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|e| format!("Failed to probe images: {}", e))?;
+
+    if let Ok(pool) = db::pool(&handle).await {
+        for result in &mut results {
+            let Some(image) = result.image.as_mut() else {
+                continue;
+            };
+
+            match store(&pool, &result.path, image).await {
+                Ok(id) => image.id = id,
+                Err(e) => result.error = Some(e),
+            }
+        }
+    }
+
+    Ok(results)
+}
+
+pub fn probe(path: &Path) -> Result<ImageProbe, String> {
+    if !path.is_file() {
+        return Err("Path is not a file".to_string());
+    }
+
+    let header = read_header(path)?;
+
+    Ok(ImageProbe {
+        id: None,
+        width: header.width,
+        height: header.height,
+        resolution: format!("{}x{}", header.width, header.height),
+        quality: quality_bucket(header.width, header.height),
+        format: header.format.to_string(),
+        color_space: header.color_space.map(str::to_string),
+        has_exif: header.has_exif as u8,
+    })
+}
+
+/// Stores `image` through `index::store_metadata`. The perceptual hash is cleared, since
+/// the file may have changed, and computed again by `find_similar_images`.
+pub async fn store(
+    pool: &Pool<Sqlite>,
+    path: &str,
+    image: &ImageProbe,
+) -> Result<Option<i64>, String> {
+    index::store_metadata(
+        pool,
+        path,
+        "image",
+        r#"
+        UPDATE images SET
+            width = ?, height = ?, resolution = ?, quality = ?, format = ?,
+            color_space = ?, has_exif = ?, perceptual_hash = NULL,
+            updated_at = CURRENT_TIMESTAMP
+        WHERE id = ?
+        RETURNING id
+        "#,
+        r#"
+        INSERT INTO images (width, height, resolution, quality, format, color_space, has_exif)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        RETURNING id
+        "#,
+        |query| {
+            query
+                .bind(image.width)
+                .bind(image.height)
+                .bind(&image.resolution)
+                .bind(image.quality)
+                .bind(&image.format)
+                .bind(&image.color_space)
+                .bind(image.has_exif)
+        },
+    )
+    .await
+}
@@ -0,0 +1,185 @@
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+use std::path::Path;
+
+use crate::library::index;
+use crate::media::quality_bucket;
+use crate::media::video_container::read_container;
+use crate::utils::db;
+use crate::utils::path_policy::{Operation, PathPolicy};
+
+/// `videos.codec` is limited to 50 characters.
+const MAX_CODEC_LENGTH: usize = 50;
+
+/// A row of the `videos` table. The track lists and chapters are JSON text, as stored.
+/// `id` is set once the probe has been stored and linked to the file's
+/// `files.file_metadata_id`, which requires the file to be indexed.
+#[derive(Debug, Serialize)]
+pub struct VideoProbe {
+    pub id: Option<i64>,
+    pub fps: f64,
+    /// Duration in whole seconds.
+    pub duration: i64,
+    pub duration_display: String,
+    pub resolution: String,
+    pub quality: u32,
+    pub format: String,
+    pub codec: String,
+    pub audio_streams: String,
+    pub subtitles: String,
+    pub chapters: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VideoProbeResult {
+    pub path: String,
+    pub video: Option<VideoProbe>,
+    pub error: Option<String>,
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub async fn probe_video(handle: tauri::AppHandle, path: String) -> Result<VideoProbe, String> {
+    let resolved = PathPolicy::load(&handle)
+        .await
+        .check(Path::new(&path), Operation::Read)?;
+
+    let mut video = tauri::async_runtime::spawn_blocking(move || probe(&resolved))
+        .await
+        .map_err(|e| format!("Failed to probe video: {}", e))??;
+
+    if let Ok(pool) = db::pool(&handle).await {
+        video.id = store(&pool, &path, &video).await?;
+    }
+
+    Ok(video)
+}
+
+/// Probes every path, reporting failures per entry instead of failing the batch.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn probe_videos(
+    handle: tauri::AppHandle,
+    paths: Vec<String>,
+) -> Result<Vec<VideoProbeResult>, String> {
+    let policy = PathPolicy::load(&handle).await;
+
+    let mut results = tauri::async_runtime::spawn_blocking(move || {
+        paths
+            .into_iter()
+            .map(|path| {
+                let probed = policy
+                    .check(Path::new(&path), Operation::Read)
+                    .and_then(|resolved| probe(&resolved));
+
+                match probed {
+                    Ok(video) => VideoProbeResult {
+                        path,
+                        video: Some(video),
+                        error: None,
+                    },
+                    Err(e) => VideoProbeResult {
+                        path,
+                        video: None,
+                        error: Some(e),
+                    },
+                }
+            })
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|e| format!("Failed to probe videos: {}", e))?;
+
+    if let Ok(pool) = db::pool(&handle).await {
+        for result in &mut results {
+            let Some(video) = result.video.as_mut() else {
+                continue;
+            };
+
+            match store(&pool, &result.path, video).await {
+                Ok(id) => video.id = id,
+                Err(e) => result.error = Some(e),
+            }
+        }
+    }
+
+    Ok(results)
+}
+
+pub fn probe(path: &Path) -> Result<VideoProbe, String> {
+    if !path.is_file() {
+        return Err("Path is not a file".to_string());
+    }
+
+    let container = read_container(path)?;
+    let duration = container.duration.round() as i64;
+    let to_json = |value: Result<String, serde_json::Error>| {
+        value.map_err(|e| format!("Failed to serialize video tracks: {}", e))
+    };
+
+    Ok(VideoProbe {
+        id: None,
+        fps: (container.fps * 1000.0).round() / 1000.0,
+        duration,
+        duration_display: format_duration(duration),
+        resolution: format!("{}x{}", container.width, container.height),
+        quality: quality_bucket(container.width, container.height),
+        format: container.format.to_string(),
+        codec: container.codec.chars().take(MAX_CODEC_LENGTH).collect(),
+        audio_streams: to_json(serde_json::to_string(&container.audio_streams))?,
+        subtitles: to_json(serde_json::to_string(&container.subtitles))?,
+        chapters: to_json(serde_json::to_string(&container.chapters))?,
+    })
+}
+
+/// `1:02:03` for videos of an hour or more, `2:03` otherwise.
+fn format_duration(seconds: i64) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+/// Stores `video` through `index::store_metadata`.
+pub async fn store(
+    pool: &Pool<Sqlite>,
+    path: &str,
+    video: &VideoProbe,
+) -> Result<Option<i64>, String> {
+    index::store_metadata(
+        pool,
+        path,
+        "video",
+        r#"
+        UPDATE videos SET
+            fps = ?, duration = ?, duration_display = ?, resolution = ?, quality = ?,
+            format = ?, codec = ?, audio_streams = ?, subtitles = ?, chapters = ?,
+            updated_at = CURRENT_TIMESTAMP
+        WHERE id = ?
+        RETURNING id
+        "#,
+        r#"
+        INSERT INTO videos (
+            fps, duration, duration_display, resolution, quality,
+            format, codec, audio_streams, subtitles, chapters
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        RETURNING id
+        "#,
+        |query| {
+            query
+                .bind(video.fps)
+                .bind(video.duration)
+                .bind(&video.duration_display)
+                .bind(&video.resolution)
+                .bind(video.quality)
+                .bind(&video.format)
+                .bind(&video.codec)
+                .bind(&video.audio_streams)
+                .bind(&video.subtitles)
+                .bind(&video.chapters)
+        },
+    )
+    .await
+}
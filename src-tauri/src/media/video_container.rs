@@ -0,0 +1,118 @@
+use serde::Serialize;
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek};
+use std::path::Path;
+
+use crate::media::{matroska, mp4};
+
+/// Everything the `videos` table needs, read from the container without decoding frames.
+pub struct VideoContainer {
+    pub format: &'static str,
+    /// Duration in seconds.
+    pub duration: f64,
+    /// Frames per second, 0 when the container does not say.
+    pub fps: f64,
+    pub width: u32,
+    pub height: u32,
+    pub codec: String,
+    pub audio_streams: Vec<AudioStream>,
+    pub subtitles: Vec<SubtitleTrack>,
+    pub chapters: Vec<Chapter>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AudioStream {
+    pub index: usize,
+    pub codec: String,
+    pub language: Option<String>,
+    pub title: Option<String>,
+    pub channels: Option<u32>,
+    pub sample_rate: Option<u32>,
+    pub default: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubtitleTrack {
+    pub index: usize,
+    pub codec: String,
+    pub language: Option<String>,
+    pub title: Option<String>,
+    pub default: bool,
+    pub forced: bool,
+}
+
+/// Chapter marker with times in seconds.
+#[derive(Debug, Serialize)]
+pub struct Chapter {
+    pub title: Option<String>,
+    pub start: f64,
+    pub end: Option<f64>,
+}
+
+pub fn read_container(path: &Path) -> Result<VideoContainer, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open video: {}", e))?;
+    let file_len = file
+        .metadata()
+        .map_err(|e| format!("Failed to read metadata: {}", e))?
+        .len();
+    let mut reader = BufReader::new(file);
+
+    let mut magic = [0u8; 8];
+    let read = reader
+        .read(&mut magic)
+        .and_then(|read| reader.rewind().map(|_| read))
+        .map_err(invalid)?;
+
+    let mut container = match &magic[..read] {
+        [0x1A, 0x45, 0xDF, 0xA3, ..] => matroska::read(&mut reader, file_len),
+        [_, _, _, _, b'f', b't', b'y', b'p']
+        | [_, _, _, _, b'm', b'o', b'o', b'v']
+        | [_, _, _, _, b'm', b'd', b'a', b't']
+        | [_, _, _, _, b'w', b'i', b'd', b'e']
+        | [_, _, _, _, b'f', b'r', b'e', b'e']
+        | [_, _, _, _, b's', b'k', b'i', b'p'] => mp4::read(&mut reader, file_len),
+        _ => return Err("Unsupported video container".to_string()),
+    }
+    .map_err(invalid)?;
+
+    if container.width == 0 || container.height == 0 {
+        return Err("Video has no video track".to_string());
+    }
+
+    close_chapters(&mut container.chapters, container.duration);
+    Ok(container)
+}
+
+fn invalid(e: io::Error) -> String {
+    format!("Failed to read video container: {}", e)
+}
+
+pub fn malformed(what: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("Malformed {}", what))
+}
+
+/// Containers usually only store where a chapter starts; it ends where the next one
+/// begins, and the last one at the end of the video.
+fn close_chapters(chapters: &mut [Chapter], duration: f64) {
+    chapters.sort_by(|a, b| a.start.total_cmp(&b.start));
+
+    let starts: Vec<f64> = chapters.iter().skip(1).map(|c| c.start).collect();
+    for (chapter, next_start) in chapters
+        .iter_mut()
+        .zip(starts.into_iter().map(Some).chain([None]))
+    {
+        if chapter.end.is_none() {
+            chapter.end = next_start.or((duration > 0.0).then_some(duration));
+        }
+    }
+}
+
+/// ISO 639-2 codes where `und` and empty values mean no language was set.
+pub fn language(code: &str) -> Option<String> {
+    let code = code.trim_matches(char::from(0)).trim();
+    if code.is_empty() || code.eq_ignore_ascii_case("und") {
+        None
+    } else {
+        Some(code.to_string())
+    }
+}
@@ -253,5 +253,266 @@ pub fn get_migrations() -> Vec<Migration> {
             "#,
             kind: MigrationKind::Up,
         },
+        Migration {
+            version: 13,
+            description: "add_path_policy_to_settings",
+            sql: r#"
+            ALTER TABLE settings ADD COLUMN protected_paths TEXT;
+            ALTER TABLE settings ADD COLUMN allowed_paths TEXT;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 14,
+            description: "add_missing_to_files",
+            sql: r#"
+            ALTER TABLE files ADD COLUMN missing INTEGER DEFAULT 0 CHECK(missing IN (0, 1));
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 15,
+            description: "create_library_roots_table",
+            sql: r#"
+            CREATE TABLE IF NOT EXISTS library_roots (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                path TEXT NOT NULL UNIQUE,
+                created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+                updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+            );
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 16,
+            description: "add_thumbnail_cache_limit_to_settings",
+            sql: r#"
+            ALTER TABLE settings ADD COLUMN thumbnail_cache_limit INTEGER DEFAULT 536870912;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 17,
+            description: "add_content_hash_to_files",
+            sql: r#"
+            ALTER TABLE files ADD COLUMN content_hash TEXT;
+            ALTER TABLE files ADD COLUMN content_hash_mtime INTEGER;
+            CREATE INDEX IF NOT EXISTS idx_files_content_hash ON files(content_hash);
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 18,
+            description: "add_perceptual_hash_to_images",
+            sql: r#"
+            ALTER TABLE images ADD COLUMN perceptual_hash TEXT CHECK(length(perceptual_hash) = 16);
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 19,
+            description: "create_operations_table",
+            sql: r#"
+            CREATE TABLE IF NOT EXISTS operations (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                batch_id TEXT NOT NULL,
+                kind TEXT CHECK(kind IN ('trash', 'move', 'rename', 'copy')) NOT NULL,
+                source_path TEXT NOT NULL,
+                target_path TEXT,
+                undone INTEGER DEFAULT 0 CHECK(undone IN (0, 1)),
+                created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+                updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+            );
+            CREATE INDEX IF NOT EXISTS idx_operations_batch_id ON operations(batch_id);
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 20,
+            description: "create_contents_fts_table",
+            sql: r#"
+            CREATE VIRTUAL TABLE IF NOT EXISTS contents_fts USING fts5(
+                name,
+                description,
+                category,
+                tags,
+                metadata,
+                tokenize = 'unicode61 remove_diacritics 2',
+                prefix = '2 3'
+            );
+
+            CREATE VIEW IF NOT EXISTS contents_fts_source AS
+            SELECT
+                c.id AS content_id,
+                c.name AS name,
+                COALESCE(c.description, '') AS description,
+                COALESCE((SELECT name FROM categories WHERE id = c.category_id), '') AS category,
+                COALESCE((
+                    SELECT group_concat(t.name, ' ')
+                    FROM content_tags ct
+                    JOIN tags t ON t.id = ct.tag_id
+                    WHERE ct.content_id = c.id
+                ), '') AS tags,
+                COALESCE((
+                    SELECT group_concat(v.value, ' ')
+                    FROM metadata_values v
+                    WHERE v.content_id = c.id AND v.value IS NOT NULL
+                ), '') AS metadata
+            FROM contents c;
+
+            INSERT INTO contents_fts (rowid, name, description, category, tags, metadata)
+            SELECT content_id, name, description, category, tags, metadata FROM contents_fts_source;
+
+            CREATE TRIGGER IF NOT EXISTS contents_fts_after_insert AFTER INSERT ON contents BEGIN
+                INSERT INTO contents_fts (rowid, name, description, category, tags, metadata)
+                SELECT content_id, name, description, category, tags, metadata
+                FROM contents_fts_source WHERE content_id = NEW.id;
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS contents_fts_after_update
+            AFTER UPDATE OF name, description, category_id ON contents BEGIN
+                DELETE FROM contents_fts WHERE rowid = OLD.id;
+                INSERT INTO contents_fts (rowid, name, description, category, tags, metadata)
+                SELECT content_id, name, description, category, tags, metadata
+                FROM contents_fts_source WHERE content_id = NEW.id;
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS contents_fts_after_delete AFTER DELETE ON contents BEGIN
+                DELETE FROM contents_fts WHERE rowid = OLD.id;
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS categories_fts_after_update AFTER UPDATE OF name ON categories BEGIN
+                DELETE FROM contents_fts WHERE rowid IN (SELECT id FROM contents WHERE category_id = NEW.id);
+                INSERT INTO contents_fts (rowid, name, description, category, tags, metadata)
+                SELECT content_id, name, description, category, tags, metadata
+                FROM contents_fts_source
+                WHERE content_id IN (SELECT id FROM contents WHERE category_id = NEW.id);
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS tags_fts_after_update AFTER UPDATE OF name ON tags BEGIN
+                DELETE FROM contents_fts WHERE rowid IN (SELECT content_id FROM content_tags WHERE tag_id = NEW.id);
+                INSERT INTO contents_fts (rowid, name, description, category, tags, metadata)
+                SELECT content_id, name, description, category, tags, metadata
+                FROM contents_fts_source
+                WHERE content_id IN (SELECT content_id FROM content_tags WHERE tag_id = NEW.id);
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS content_tags_fts_after_insert AFTER INSERT ON content_tags BEGIN
+                DELETE FROM contents_fts WHERE rowid = NEW.content_id;
+                INSERT INTO contents_fts (rowid, name, description, category, tags, metadata)
+                SELECT content_id, name, description, category, tags, metadata
+                FROM contents_fts_source WHERE content_id = NEW.content_id;
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS content_tags_fts_after_delete AFTER DELETE ON content_tags BEGIN
+                DELETE FROM contents_fts WHERE rowid = OLD.content_id;
+                INSERT INTO contents_fts (rowid, name, description, category, tags, metadata)
+                SELECT content_id, name, description, category, tags, metadata
+                FROM contents_fts_source WHERE content_id = OLD.content_id;
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS metadata_values_fts_after_insert AFTER INSERT ON metadata_values BEGIN
+                DELETE FROM contents_fts WHERE rowid = NEW.content_id;
+                INSERT INTO contents_fts (rowid, name, description, category, tags, metadata)
+                SELECT content_id, name, description, category, tags, metadata
+                FROM contents_fts_source WHERE content_id = NEW.content_id;
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS metadata_values_fts_after_update AFTER UPDATE OF value ON metadata_values BEGIN
+                DELETE FROM contents_fts WHERE rowid = NEW.content_id;
+                INSERT INTO contents_fts (rowid, name, description, category, tags, metadata)
+                SELECT content_id, name, description, category, tags, metadata
+                FROM contents_fts_source WHERE content_id = NEW.content_id;
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS metadata_values_fts_after_delete AFTER DELETE ON metadata_values BEGIN
+                DELETE FROM contents_fts WHERE rowid = OLD.content_id;
+                INSERT INTO contents_fts (rowid, name, description, category, tags, metadata)
+                SELECT content_id, name, description, category, tags, metadata
+                FROM contents_fts_source WHERE content_id = OLD.content_id;
+            END;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 21,
+            description: "add_auto_lock_to_settings",
+            sql: r#"
+            ALTER TABLE settings ADD COLUMN auto_lock_minutes INTEGER DEFAULT 0 CHECK(auto_lock_minutes >= 0);
+            ALTER TABLE settings ADD COLUMN lock_shortcut TEXT CHECK(length(lock_shortcut) <= 100);
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 22,
+            description: "create_vaults_table",
+            sql: r#"
+            CREATE TABLE IF NOT EXISTS vaults (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL CHECK(length(name) <= 255),
+                container_path TEXT NOT NULL UNIQUE,
+                mount_point TEXT,
+                created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+                updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+            );
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 23,
+            description: "add_upscale_concurrency_to_settings",
+            sql: r#"
+            ALTER TABLE settings ADD COLUMN upscale_concurrency INTEGER DEFAULT 1 CHECK(upscale_concurrency > 0);
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 24,
+            description: "create_downloads_table",
+            sql: r#"
+            CREATE TABLE IF NOT EXISTS downloads (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                url TEXT NOT NULL,
+                destination TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'queued' CHECK(status IN ('queued', 'downloading', 'paused', 'completed', 'failed', 'cancelled')),
+                total_bytes INTEGER,
+                downloaded_bytes INTEGER NOT NULL DEFAULT 0,
+                checksum TEXT CHECK(length(checksum) <= 200),
+                etag TEXT,
+                error TEXT,
+                file_id INTEGER,
+                created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+                updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (file_id) REFERENCES files(id) ON DELETE SET NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_downloads_status ON downloads(status);
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 25,
+            description: "add_undo_error_to_operations",
+            sql: r#"
+            ALTER TABLE operations ADD COLUMN undo_error TEXT;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 26,
+            description: "add_scan_options_to_library_roots",
+            sql: r#"
+            ALTER TABLE library_roots ADD COLUMN scan_options TEXT;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 27,
+            description: "add_last_modified_to_downloads",
+            sql: r#"
+            ALTER TABLE downloads ADD COLUMN last_modified TEXT;
+            "#,
+            kind: MigrationKind::Up,
+        },
     ]
 }
@@ -0,0 +1,19 @@
+use tauri::Manager;
+
+use crate::security::{self, LockManager, LockStatus};
+use crate::utils::db;
+
+/// Reports whether the app is locked. Called by the frontend on startup, so a set
+/// password locks the app before anything else is shown.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_lock_status(handle: tauri::AppHandle) -> Result<LockStatus, String> {
+    let pool = db::pool(&handle).await?;
+    let has_password = security::load_settings(&pool)
+        .await?
+        .password_hash
+        .is_some();
+
+    let manager = handle.state::<LockManager>();
+    manager.initialize(has_password);
+    Ok(manager.status(has_password))
+}
@@ -0,0 +1,19 @@
+use crate::security;
+use crate::utils::db;
+
+/// Locks the app right away. Fails when no lock password is set, since the app could
+/// never be unlocked again.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn lock_app(handle: tauri::AppHandle) -> Result<(), String> {
+    let pool = db::pool(&handle).await?;
+    if security::load_settings(&pool)
+        .await?
+        .password_hash
+        .is_none()
+    {
+        return Err("Set a lock password first".into());
+    }
+
+    security::lock(&handle);
+    Ok(())
+}
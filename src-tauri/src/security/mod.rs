@@ -0,0 +1,433 @@
+use argon2::Argon2;
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::ipc::Invoke;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+use crate::utils::db;
+
+pub mod get_lock_status;
+pub mod lock_app;
+pub mod record_activity;
+pub mod set_auto_lock;
+pub mod set_lock_password;
+pub mod verify_lock_password;
+
+const LOCKED_EVENT: &str = "app-locked";
+const UNLOCKED_EVENT: &str = "app-unlocked";
+
+/// How often the idle timer and the lock settings are checked.
+const MONITOR_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Failed attempts allowed before each further one adds a growing wait.
+const FREE_ATTEMPTS: u32 = 5;
+const BASE_LOCKOUT: Duration = Duration::from_secs(30);
+const MAX_LOCKOUT: Duration = Duration::from_secs(15 * 60);
+
+pub const MIN_PASSWORD_LENGTH: usize = 4;
+
+/// Commands the frontend may still call while the app is locked.
+const ALLOWED_WHILE_LOCKED: &[&str] = &[
+    "get_database_status",
+    "get_lock_status",
+    "lock_app",
+    "record_activity",
+    "unlock_database",
+    "verify_lock_password",
+];
+
+#[derive(Serialize)]
+pub struct LockStatus {
+    pub locked: bool,
+    pub has_password: bool,
+    pub failed_attempts: u32,
+    /// Seconds until another password attempt is accepted.
+    pub retry_after: u64,
+}
+
+/// `lock_pwd`, `auto_lock_minutes` and `lock_shortcut` from the settings table.
+pub struct LockSettings {
+    pub password_hash: Option<String>,
+    pub auto_lock_minutes: u64,
+    pub shortcut: Option<String>,
+}
+
+struct Inner {
+    /// Set once the settings have been read, so the app starts locked when a password
+    /// exists.
+    initialized: bool,
+    locked: bool,
+    last_activity: Instant,
+    shortcut: Option<String>,
+}
+
+/// Counts failed password attempts and imposes a growing wait once `FREE_ATTEMPTS`
+/// have been used up.
+#[derive(Default)]
+pub struct Backoff {
+    state: Mutex<BackoffState>,
+    /// Serializes password checks, so parallel guesses cannot slip past the rate limit
+    /// while a hash is being verified.
+    attempt: tokio::sync::Mutex<()>,
+}
+
+#[derive(Default)]
+struct BackoffState {
+    failed_attempts: u32,
+    retry_at: Option<Instant>,
+}
+
+impl Backoff {
+    /// Waits for any check in progress, then refuses while the wait from earlier
+    /// failures has not passed. Hold the guard until the outcome has been recorded.
+    pub async fn begin(&self) -> Result<tokio::sync::MutexGuard<'_, ()>, String> {
+        let guard = self.attempt.lock().await;
+        if let Some(wait) = self.retry_after() {
+            return Err(format!(
+                "Too many failed attempts, try again in {} seconds",
+                wait.as_secs().max(1)
+            ));
+        }
+        Ok(guard)
+    }
+
+    pub fn failed_attempts(&self) -> u32 {
+        self.state
+            .lock()
+            .map(|state| state.failed_attempts)
+            .unwrap_or_default()
+    }
+
+    pub fn retry_after(&self) -> Option<Duration> {
+        let state = self.state.lock().ok()?;
+        state
+            .retry_at
+            .and_then(|at| at.checked_duration_since(Instant::now()))
+    }
+
+    pub fn record_failure(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.failed_attempts += 1;
+            if state.failed_attempts >= FREE_ATTEMPTS {
+                let doublings = (state.failed_attempts - FREE_ATTEMPTS).min(16);
+                let wait = BASE_LOCKOUT.saturating_mul(1 << doublings).min(MAX_LOCKOUT);
+                state.retry_at = Some(Instant::now() + wait);
+            }
+        }
+    }
+
+    pub fn record_success(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.failed_attempts = 0;
+            state.retry_at = None;
+        }
+    }
+}
+
+/// Holds the lock state in Rust, so a locked app cannot be unlocked from the frontend
+/// without the password and the app's own commands are refused until it is. Plugin
+/// commands, including the SQL plugin's queries, do not pass through `guard`.
+pub struct LockManager {
+    inner: Mutex<Inner>,
+    /// Attempts at the lock password.
+    attempts: Backoff,
+    /// Attempts at the database password, which has its own count so failures at one
+    /// prompt do not lock out the other.
+    database_attempts: Backoff,
+}
+
+impl LockManager {
+    pub fn start(handle: &AppHandle) -> LockManager {
+        tauri::async_runtime::spawn(monitor(handle.clone()));
+
+        LockManager {
+            inner: Mutex::new(Inner {
+                initialized: false,
+                locked: false,
+                last_activity: Instant::now(),
+                shortcut: None,
+            }),
+            attempts: Backoff::default(),
+            database_attempts: Backoff::default(),
+        }
+    }
+
+    /// Until the settings have been read a password may exist, so the app counts as
+    /// locked until `initialize` has run.
+    pub fn is_locked(&self) -> bool {
+        self.inner
+            .lock()
+            .map(|inner| !inner.initialized || inner.locked)
+            .unwrap_or(true)
+    }
+
+    pub fn status(&self, has_password: bool) -> LockStatus {
+        let retry_after = self
+            .attempts
+            .retry_after()
+            .map(|wait| wait.as_secs().max(1));
+        let Ok(inner) = self.inner.lock() else {
+            return LockStatus {
+                locked: true,
+                has_password,
+                failed_attempts: 0,
+                retry_after: 0,
+            };
+        };
+
+        LockStatus {
+            locked: inner.locked,
+            has_password,
+            failed_attempts: self.attempts.failed_attempts(),
+            retry_after: retry_after.unwrap_or(0),
+        }
+    }
+
+    /// Locks the app on first use when a password is set.
+    pub fn initialize(&self, has_password: bool) -> bool {
+        let Ok(mut inner) = self.inner.lock() else {
+            return false;
+        };
+        if inner.initialized {
+            return false;
+        }
+        inner.initialized = true;
+        inner.locked = has_password;
+        has_password
+    }
+
+    pub fn touch(&self) {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.last_activity = Instant::now();
+        }
+    }
+
+    fn idle_for(&self) -> Duration {
+        self.inner
+            .lock()
+            .map(|inner| inner.last_activity.elapsed())
+            .unwrap_or_default()
+    }
+
+    /// Returns whether the app was unlocked before.
+    fn set_locked(&self, locked: bool) -> bool {
+        let Ok(mut inner) = self.inner.lock() else {
+            return false;
+        };
+        let changed = inner.locked != locked;
+        inner.locked = locked;
+        inner.initialized = true;
+        if !locked {
+            inner.last_activity = Instant::now();
+        }
+        changed
+    }
+
+    pub fn database_attempts(&self) -> &Backoff {
+        &self.database_attempts
+    }
+}
+
+/// Routes commands to `commands` unless the app is locked, or the lock settings have
+/// not been read yet, in which case only the lock screen's own commands get through. This only covers the app's commands, not
+/// those of plugins such as the SQL plugin.
+pub fn guard(invoke: Invoke, commands: fn(Invoke) -> bool) -> bool {
+    let command = invoke.message.command();
+    if !ALLOWED_WHILE_LOCKED.contains(&command) {
+        let handle = invoke.message.webview_ref().app_handle().clone();
+        if let Some(manager) = handle.try_state::<LockManager>()
+            && manager.is_locked()
+        {
+            invoke.resolver.reject("App is locked");
+            return true;
+        }
+    }
+    commands(invoke)
+}
+
+pub fn lock(handle: &AppHandle) {
+    if handle.state::<LockManager>().set_locked(true) {
+        let _ = handle.emit(LOCKED_EVENT, ());
+    }
+}
+
+pub fn unlock(handle: &AppHandle) {
+    if handle.state::<LockManager>().set_locked(false) {
+        let _ = handle.emit(UNLOCKED_EVENT, ());
+    }
+}
+
+/// Checks `password` against the stored hash, applying the rate limit. Passwords kept
+/// in plain text by older versions are accepted once and replaced with a hash.
+pub async fn check_password(
+    handle: &AppHandle,
+    pool: &Pool<Sqlite>,
+    password: &str,
+) -> Result<bool, String> {
+    let manager = handle.state::<LockManager>();
+    let _attempt = manager.attempts.begin().await?;
+
+    let Some(stored) = load_settings(pool).await?.password_hash else {
+        // The SQL plugin is not guarded, so a locked frontend could clear the stored
+        // hash itself. Without one there is nothing to unlock with.
+        if manager.is_locked() {
+            return Err("No lock password is stored".into());
+        }
+        return Ok(true);
+    };
+
+    let candidate = password.to_string();
+    let legacy = !is_hash(&stored);
+    let matches = tauri::async_runtime::spawn_blocking(move || verify_hash(&candidate, &stored))
+        .await
+        .map_err(|e| format!("Failed to verify password: {}", e))?;
+
+    if !matches {
+        manager.attempts.record_failure();
+        return Ok(false);
+    }
+
+    manager.attempts.record_success();
+    if legacy {
+        let hash = hash_password(password.to_string()).await?;
+        store_password_hash(pool, Some(&hash)).await?;
+    }
+    Ok(true)
+}
+
+pub async fn hash_password(password: String) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let salt = SaltString::generate(&mut OsRng);
+        Argon2::default()
+            .hash_password(password.as_bytes(), &salt)
+            .map(|hash| hash.to_string())
+            .map_err(|e| format!("Failed to hash password: {}", e))
+    })
+    .await
+    .map_err(|e| format!("Failed to hash password: {}", e))?
+}
+
+fn is_hash(stored: &str) -> bool {
+    stored.starts_with("$argon2")
+}
+
+fn verify_hash(password: &str, stored: &str) -> bool {
+    if !is_hash(stored) {
+        return constant_time_eq(password.as_bytes(), stored.as_bytes());
+    }
+
+    match PasswordHash::new(stored) {
+        Ok(hash) => Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .is_ok(),
+        Err(e) => {
+            log::warn!("Stored lock password is not a valid hash: {}", e);
+            false
+        }
+    }
+}
+
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+pub async fn load_settings(pool: &Pool<Sqlite>) -> Result<LockSettings, String> {
+    let row: Option<(Option<String>, Option<i64>, Option<String>)> = sqlx::query_as(
+        "SELECT lock_pwd, auto_lock_minutes, lock_shortcut FROM settings ORDER BY id LIMIT 1",
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("Failed to read lock settings: {}", e))?;
+
+    let (password_hash, minutes, shortcut) = row.unwrap_or_default();
+    Ok(LockSettings {
+        password_hash: password_hash.filter(|hash| !hash.is_empty()),
+        auto_lock_minutes: minutes.unwrap_or(0).max(0) as u64,
+        shortcut: shortcut.filter(|shortcut| !shortcut.trim().is_empty()),
+    })
+}
+
+pub async fn store_password_hash(pool: &Pool<Sqlite>, hash: Option<&str>) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        UPDATE settings SET lock_pwd = ?, updated_at = CURRENT_TIMESTAMP
+        WHERE id = (SELECT id FROM settings ORDER BY id LIMIT 1)
+        "#,
+    )
+    .bind(hash)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to store lock password: {}", e))?;
+    Ok(())
+}
+
+/// Registers `shortcut` as the global lock shortcut, replacing the previous one.
+pub fn apply_shortcut(handle: &AppHandle, shortcut: Option<&str>) -> Result<(), String> {
+    let manager = handle.state::<LockManager>();
+    let mut inner = manager
+        .inner
+        .lock()
+        .map_err(|_| "Lock state is unavailable")?;
+    if inner.shortcut.as_deref() == shortcut {
+        return Ok(());
+    }
+
+    let shortcuts = handle.global_shortcut();
+    if let Some(previous) = inner.shortcut.take()
+        && let Err(e) = shortcuts.unregister(previous.as_str())
+    {
+        log::warn!("Failed to unregister lock shortcut {}: {}", previous, e);
+    }
+
+    if let Some(shortcut) = shortcut {
+        shortcuts
+            .on_shortcut(shortcut, |handle, _, event| {
+                if event.state == ShortcutState::Pressed {
+                    lock(handle);
+                }
+            })
+            .map_err(|e| format!("Failed to register shortcut {}: {}", shortcut, e))?;
+        inner.shortcut = Some(shortcut.to_string());
+    }
+    Ok(())
+}
+
+/// The database is opened by the frontend after startup, so the lock settings are only
+/// picked up once its pool shows up. Afterwards they are re-read on every tick, which
+/// also locks the app once it has been idle for `auto_lock_minutes`.
+async fn monitor(handle: AppHandle) {
+    loop {
+        tokio::time::sleep(MONITOR_INTERVAL).await;
+
+        let Ok(pool) = db::pool(&handle).await else {
+            continue;
+        };
+        let settings = match load_settings(&pool).await {
+            Ok(settings) => settings,
+            Err(e) => {
+                log::warn!("{}", e);
+                continue;
+            }
+        };
+
+        let manager = handle.state::<LockManager>();
+        let has_password = settings.password_hash.is_some();
+        if manager.initialize(has_password) {
+            let _ = handle.emit(LOCKED_EVENT, ());
+        }
+
+        if let Err(e) = apply_shortcut(&handle, settings.shortcut.as_deref()) {
+            log::warn!("{}", e);
+        }
+
+        let idle_limit = Duration::from_secs(settings.auto_lock_minutes * 60);
+        if has_password && !idle_limit.is_zero() && manager.idle_for() >= idle_limit {
+            lock(&handle);
+        }
+    }
+}
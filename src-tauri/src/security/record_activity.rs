@@ -0,0 +1,10 @@
+use tauri::Manager;
+
+use crate::security::LockManager;
+
+/// Resets the idle timer behind `auto_lock_minutes`. The frontend calls this on user
+/// input, throttled on its side.
+#[tauri::command(rename_all = "snake_case")]
+pub fn record_activity(handle: tauri::AppHandle) {
+    handle.state::<LockManager>().touch();
+}
@@ -0,0 +1,37 @@
+use std::str::FromStr;
+use tauri_plugin_global_shortcut::Shortcut;
+
+use crate::security;
+use crate::utils::db;
+
+/// Stores the idle timeout and the global lock shortcut, and registers the shortcut
+/// right away. `idle_minutes` of 0 and an empty `shortcut` turn either off.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn set_auto_lock(
+    handle: tauri::AppHandle,
+    idle_minutes: u32,
+    shortcut: Option<String>,
+) -> Result<(), String> {
+    let shortcut = shortcut
+        .map(|shortcut| shortcut.trim().to_string())
+        .filter(|shortcut| !shortcut.is_empty());
+
+    if let Some(shortcut) = &shortcut {
+        Shortcut::from_str(shortcut).map_err(|e| format!("Invalid shortcut: {}", e))?;
+    }
+
+    let pool = db::pool(&handle).await?;
+    sqlx::query(
+        r#"
+        UPDATE settings SET auto_lock_minutes = ?, lock_shortcut = ?, updated_at = CURRENT_TIMESTAMP
+        WHERE id = (SELECT id FROM settings ORDER BY id LIMIT 1)
+        "#,
+    )
+    .bind(idle_minutes)
+    .bind(&shortcut)
+    .execute(&pool)
+    .await
+    .map_err(|e| format!("Failed to store auto-lock settings: {}", e))?;
+
+    security::apply_shortcut(&handle, shortcut.as_deref())
+}
@@ -0,0 +1,37 @@
+use crate::security::{self, MIN_PASSWORD_LENGTH};
+use crate::utils::db;
+
+/// Sets, changes or removes the lock password. Changing or removing an existing one
+/// requires `current_password`. An empty `new_password` removes the lock.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn set_lock_password(
+    handle: tauri::AppHandle,
+    current_password: Option<String>,
+    new_password: Option<String>,
+) -> Result<(), String> {
+    let pool = db::pool(&handle).await?;
+
+    if security::load_settings(&pool)
+        .await?
+        .password_hash
+        .is_some()
+    {
+        let current = current_password.ok_or("Current password is required")?;
+        if !security::check_password(&handle, &pool, &current).await? {
+            return Err("Current password is incorrect".into());
+        }
+    }
+
+    let hash = match new_password.filter(|password| !password.is_empty()) {
+        Some(password) if password.chars().count() < MIN_PASSWORD_LENGTH => {
+            return Err(format!(
+                "Password must be at least {} characters",
+                MIN_PASSWORD_LENGTH
+            ));
+        }
+        Some(password) => Some(security::hash_password(password).await?),
+        None => None,
+    };
+
+    security::store_password_hash(&pool, hash.as_deref()).await
+}
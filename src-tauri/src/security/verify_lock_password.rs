@@ -0,0 +1,17 @@
+use crate::security;
+use crate::utils::db;
+
+/// Checks the lock password and unlocks the app when it matches. Repeated failures
+/// are refused for a growing amount of time.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn verify_lock_password(
+    handle: tauri::AppHandle,
+    password: String,
+) -> Result<bool, String> {
+    let pool = db::pool(&handle).await?;
+    let matches = security::check_password(&handle, &pool, &password).await?;
+    if matches {
+        security::unlock(&handle);
+    }
+    Ok(matches)
+}
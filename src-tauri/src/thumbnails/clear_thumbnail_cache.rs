@@ -0,0 +1,26 @@
+use tauri::Manager;
+
+use crate::thumbnails::ThumbnailCache;
+use crate::utils::db;
+
+/// Deletes every cached thumbnail and records the time in `settings.last_cache_clear`.
+/// Returns the number of bytes freed.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn clear_thumbnail_cache(handle: tauri::AppHandle) -> Result<u64, String> {
+    let cache = handle.state::<ThumbnailCache>().inner().clone();
+
+    let freed = tauri::async_runtime::spawn_blocking(move || cache.clear())
+        .await
+        .map_err(|e| format!("Failed to clear thumbnail cache: {}", e))??;
+
+    if let Ok(pool) = db::pool(&handle).await {
+        sqlx::query(
+            "UPDATE settings SET last_cache_clear = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP",
+        )
+        .execute(&pool)
+        .await
+        .map_err(|e| format!("Failed to record cache clear: {}", e))?;
+    }
+
+    Ok(freed)
+}
@@ -0,0 +1,94 @@
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader};
+use std::io::Cursor;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::files::sniff::sniff;
+use crate::thumbnails::THUMBNAIL_SIZE;
+use crate::utils::process;
+
+/// Decoding a still from a broken or remote-mounted video should not hang a worker.
+const VIDEO_STILL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Renders a WebP thumbnail for an image, or for a video when ffmpeg is installed.
+pub fn render(source: &Path) -> Result<Vec<u8>, String> {
+    let is_video = sniff(source).is_some_and(|sniffed| sniffed.file_type == "video");
+
+    let image = if is_video {
+        video_still(source)?
+    } else {
+        decode_image(source)?
+    };
+
+    encode(&image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE))
+}
+
+/// Decodes an image and applies its EXIF orientation so thumbnails are upright.
+pub fn decode_image(source: &Path) -> Result<DynamicImage, String> {
+    let mut decoder = ImageReader::open(source)
+        .and_then(|reader| reader.with_guessed_format())
+        .map_err(|e| format!("Failed to open image: {}", e))?
+        .into_decoder()
+        .map_err(|e| format!("Unsupported image: {}", e))?;
+
+    let orientation = decoder
+        .orientation()
+        .map_err(|e| format!("Failed to read image orientation: {}", e))?;
+    let mut image = DynamicImage::from_decoder(decoder)
+        .map_err(|e| format!("Failed to decode image: {}", e))?;
+    image.apply_orientation(orientation);
+
+    Ok(image)
+}
+
+/// Grabs the first keyframe through ffmpeg, which does the decoding no pure-Rust crate
+/// covers for common codecs.
+fn video_still(source: &Path) -> Result<DynamicImage, String> {
+    let ffmpeg =
+        process::find_program("ffmpeg").ok_or("Video thumbnails require ffmpeg to be installed")?;
+
+    let output = process::output_with_timeout(
+        process::command(ffmpeg)
+            .args(["-v", "error", "-skip_frame", "nokey", "-i"])
+            .arg(source)
+            .args([
+                "-an",
+                "-sn",
+                "-frames:v",
+                "1",
+                "-f",
+                "image2pipe",
+                "-c:v",
+                "png",
+                "-",
+            ]),
+        VIDEO_STILL_TIMEOUT,
+    )
+    .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+
+    if !output.status.success() || output.stdout.is_empty() {
+        return Err(format!(
+            "Failed to extract video frame: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    image::load_from_memory_with_format(&output.stdout, ImageFormat::Png)
+        .map_err(|e| format!("Failed to decode video frame: {}", e))
+}
+
+fn encode(image: &DynamicImage) -> Result<Vec<u8>, String> {
+    // The WebP encoder only takes 8-bit RGB and RGBA.
+    let image = if image.color().has_alpha() {
+        DynamicImage::ImageRgba8(image.to_rgba8())
+    } else {
+        DynamicImage::ImageRgb8(image.to_rgb8())
+    };
+
+    let mut bytes = Cursor::new(Vec::new());
+    image
+        .write_to(&mut bytes, ImageFormat::WebP)
+        .map_err(|e| format!("Failed to encode thumbnail: {}", e))?;
+
+    Ok(bytes.into_inner())
+}
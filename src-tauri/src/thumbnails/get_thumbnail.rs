@@ -0,0 +1,43 @@
+use std::path::Path;
+use tauri::Manager;
+
+use crate::thumbnails::{ThumbnailCache, cache_limit, generate};
+use crate::utils::path_policy::{Operation, PathPolicy};
+
+/// Returns the path of a cached thumbnail for `path`, rendering it first when the cache
+/// has none for the file's current version. The frontend loads it through the asset
+/// protocol.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_thumbnail(handle: tauri::AppHandle, path: String) -> Result<String, String> {
+    let source = PathPolicy::load(&handle)
+        .await
+        .check(Path::new(&path), Operation::Read)?;
+
+    let cache = handle.state::<ThumbnailCache>().inner().clone();
+    let limit = cache_limit(&handle).await;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let metadata = source
+            .metadata()
+            .map_err(|e| format!("Failed to read metadata: {}", e))?;
+        if !metadata.is_file() {
+            return Err("Path is not a file".to_string());
+        }
+
+        let modified = metadata
+            .modified()
+            .map_err(|e| format!("Failed to read modification time: {}", e))?;
+        let thumbnail = cache.path_for(&source, modified);
+
+        if thumbnail.is_file() {
+            cache.touch(&thumbnail);
+        } else {
+            let bytes = generate::render(&source)?;
+            cache.insert(&thumbnail, &bytes, limit)?;
+        }
+
+        Ok(thumbnail.to_string_lossy().to_string())
+    })
+    .await
+    .map_err(|e| format!("Failed to create thumbnail: {}", e))?
+}
@@ -0,0 +1,187 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+use crate::utils::db;
+
+pub mod clear_thumbnail_cache;
+pub mod generate;
+pub mod get_thumbnail;
+
+/// Thumbnails fit inside a square of this many pixels.
+pub const THUMBNAIL_SIZE: u32 = 320;
+
+const DEFAULT_CACHE_LIMIT: u64 = 512 * 1024 * 1024;
+
+/// Eviction frees space down to this share of the limit, so it does not run again on
+/// the very next insert.
+const EVICTION_TARGET_PERCENT: u64 = 90;
+
+const THUMBNAIL_EXTENSION: &str = "webp";
+
+/// WebP thumbnails stored under the app cache directory, named after a hash of the
+/// source path and modification time so edited files get a fresh thumbnail. A cache
+/// hit refreshes the file's modification time, which eviction uses as last access.
+#[derive(Clone)]
+pub struct ThumbnailCache {
+    dir: PathBuf,
+    /// Total size of the cache in bytes, measured on first use.
+    size: Arc<Mutex<Option<u64>>>,
+}
+
+impl ThumbnailCache {
+    pub fn new(cache_dir: &Path) -> Result<ThumbnailCache, String> {
+        let dir = cache_dir.join("thumbnails");
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create thumbnail cache directory: {}", e))?;
+
+        Ok(ThumbnailCache {
+            dir,
+            size: Arc::new(Mutex::new(None)),
+        })
+    }
+
+    pub fn path_for(&self, source: &Path, modified: SystemTime) -> PathBuf {
+        let modified = modified
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(source.to_string_lossy().as_bytes());
+        hasher.update(&modified.to_le_bytes());
+        hasher.update(&THUMBNAIL_SIZE.to_le_bytes());
+
+        self.dir.join(format!(
+            "{}.{}",
+            hasher.finalize().to_hex(),
+            THUMBNAIL_EXTENSION
+        ))
+    }
+
+    /// Marks a cached thumbnail as recently used.
+    pub fn touch(&self, thumbnail: &Path) {
+        if let Ok(file) = File::options().write(true).open(thumbnail) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+    }
+
+    /// Writes a thumbnail through a temporary file, so concurrent readers never see a
+    /// partial image, then evicts old entries when the cache outgrew `limit`.
+    pub fn insert(&self, thumbnail: &Path, bytes: &[u8], limit: u64) -> Result<(), String> {
+        let partial = thumbnail.with_extension("partial");
+        File::create(&partial)
+            .and_then(|mut file| file.write_all(bytes))
+            .and_then(|_| fs::rename(&partial, thumbnail))
+            .map_err(|e| {
+                let _ = fs::remove_file(&partial);
+                format!("Failed to write thumbnail: {}", e)
+            })?;
+
+        let mut size = self
+            .size
+            .lock()
+            .map_err(|_| "Thumbnail cache is unavailable")?;
+        let total = match *size {
+            Some(total) => total + bytes.len() as u64,
+            None => self.entries().iter().map(|entry| entry.size).sum(),
+        };
+
+        *size = Some(if total > limit {
+            self.evict(limit * EVICTION_TARGET_PERCENT / 100)
+        } else {
+            total
+        });
+
+        Ok(())
+    }
+
+    /// Deletes every cached thumbnail and returns the number of bytes freed.
+    pub fn clear(&self) -> Result<u64, String> {
+        let mut size = self
+            .size
+            .lock()
+            .map_err(|_| "Thumbnail cache is unavailable")?;
+
+        let mut freed = 0;
+        for entry in self.entries() {
+            if fs::remove_file(&entry.path).is_ok() {
+                freed += entry.size;
+            }
+        }
+
+        *size = None;
+        Ok(freed)
+    }
+
+    /// Removes the least recently used thumbnails until the cache fits in `target`.
+    /// Returns the remaining size.
+    fn evict(&self, target: u64) -> u64 {
+        let mut entries = self.entries();
+        entries.sort_by_key(|entry| entry.last_used);
+
+        let mut total: u64 = entries.iter().map(|entry| entry.size).sum();
+        for entry in entries {
+            if total <= target {
+                break;
+            }
+            if fs::remove_file(&entry.path).is_ok() {
+                total -= entry.size;
+            }
+        }
+
+        total
+    }
+
+    fn entries(&self) -> Vec<CacheEntry> {
+        let Ok(read_dir) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+
+        read_dir
+            .flatten()
+            .filter(|entry| {
+                entry.path().extension().and_then(|ext| ext.to_str()) == Some(THUMBNAIL_EXTENSION)
+            })
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                Some(CacheEntry {
+                    path: entry.path(),
+                    size: metadata.len(),
+                    last_used: metadata.modified().unwrap_or(UNIX_EPOCH),
+                })
+            })
+            .collect()
+    }
+}
+
+struct CacheEntry {
+    path: PathBuf,
+    size: u64,
+    last_used: SystemTime,
+}
+
+/// Reads the cache size limit in bytes from the settings table, falling back to the
+/// default while the database is not loaded yet.
+pub async fn cache_limit(handle: &AppHandle) -> u64 {
+    let Ok(pool) = db::pool(handle).await else {
+        return DEFAULT_CACHE_LIMIT;
+    };
+
+    let limit: Option<Option<i64>> =
+        sqlx::query_scalar("SELECT thumbnail_cache_limit FROM settings ORDER BY id LIMIT 1")
+            .fetch_optional(&pool)
+            .await
+            .unwrap_or_else(|e| {
+                log::warn!("Failed to read thumbnail cache limit from settings: {}", e);
+                None
+            });
+
+    match limit.flatten() {
+        Some(limit) if limit > 0 => limit as u64,
+        _ => DEFAULT_CACHE_LIMIT,
+    }
+}
@@ -0,0 +1,9 @@
+use tauri::Manager;
+
+use crate::upscale::UpscaleManager;
+
+/// Cancels an upscale. A running Video2x process is killed and its partial output removed.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn cancel_upscale(handle: tauri::AppHandle, job_id: String) -> Result<(), String> {
+    handle.state::<UpscaleManager>().cancel(&job_id)
+}
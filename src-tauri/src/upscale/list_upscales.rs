@@ -0,0 +1,8 @@
+use tauri::Manager;
+
+use crate::upscale::{UpscaleManager, UpscaleProgress};
+
+#[tauri::command(rename_all = "snake_case")]
+pub async fn list_upscales(handle: tauri::AppHandle) -> Result<Vec<UpscaleProgress>, String> {
+    Ok(handle.state::<UpscaleManager>().list())
+}
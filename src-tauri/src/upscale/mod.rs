@@ -0,0 +1,321 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tauri::{AppHandle, Manager};
+
+use crate::files::transfer;
+use crate::upscale::video2x::Video2x;
+
+pub mod cancel_upscale;
+pub mod list_upscales;
+pub mod start_upscale;
+mod video2x;
+mod worker;
+
+/// Finished upscales kept around for `list_upscales`, oldest dropped first.
+const FINISHED_JOBS_KEPT: usize = 50;
+
+pub const MIN_SCALE: u32 = 2;
+pub const MAX_SCALE: u32 = 4;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Processor {
+    #[default]
+    Realesrgan,
+    Realcugan,
+    Libplacebo,
+}
+
+impl Processor {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Processor::Realesrgan => "realesrgan",
+            Processor::Realcugan => "realcugan",
+            Processor::Libplacebo => "libplacebo",
+        }
+    }
+}
+
+/// Where the upscaled file is written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputLocation {
+    /// Next to the source file.
+    #[default]
+    Source,
+    /// Into the downloads folder.
+    Downloads,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct UpscaleOptions {
+    #[serde(default)]
+    pub processor: Processor,
+    #[serde(default = "default_scale")]
+    pub scale: u32,
+    /// Model of the Real-ESRGAN and Real-CUGAN processors, or the libplacebo shader.
+    /// Video2x picks its default when empty.
+    pub model: Option<String>,
+    /// GPU index passed to Video2x.
+    pub device: Option<u32>,
+    #[serde(default)]
+    pub output_location: OutputLocation,
+}
+
+fn default_scale() -> u32 {
+    MIN_SCALE
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpscaleState {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl UpscaleState {
+    pub fn is_finished(self) -> bool {
+        matches!(
+            self,
+            UpscaleState::Completed | UpscaleState::Failed | UpscaleState::Cancelled
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UpscaleProgress {
+    pub job_id: String,
+    pub state: UpscaleState,
+    pub source: String,
+    /// Set once the job starts and its output name is chosen.
+    pub output: Option<String>,
+    pub processed_frames: u64,
+    pub total_frames: u64,
+    pub percent: f64,
+    /// Row of the upscaled file in the `files` table, once registered.
+    pub file_id: Option<i64>,
+    pub error: Option<String>,
+}
+
+pub struct UpscaleJob {
+    pub id: String,
+    pub source: PathBuf,
+    pub output_dir: PathBuf,
+    pub options: UpscaleOptions,
+    video2x: Video2x,
+    cancelled: AtomicBool,
+    progress: Mutex<UpscaleProgress>,
+}
+
+impl UpscaleJob {
+    pub fn progress(&self) -> UpscaleProgress {
+        match self.progress.lock() {
+            Ok(progress) => progress.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    fn update(&self, change: impl FnOnce(&mut UpscaleProgress)) {
+        if let Ok(mut progress) = self.progress.lock() {
+            change(&mut progress);
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+struct Queue {
+    pending: VecDeque<Arc<UpscaleJob>>,
+    running: usize,
+    /// `settings.upscale_concurrency`, refreshed on every enqueue.
+    limit: usize,
+    jobs: Vec<Arc<UpscaleJob>>,
+}
+
+/// Runs Video2x for queued upscales, at most `upscale_concurrency` at a time. Each
+/// running job gets its own thread, which waits on the child process.
+pub struct UpscaleManager {
+    handle: AppHandle,
+    queue: Mutex<Queue>,
+}
+
+impl UpscaleManager {
+    pub fn start(handle: &AppHandle) -> UpscaleManager {
+        UpscaleManager {
+            handle: handle.clone(),
+            queue: Mutex::new(Queue {
+                pending: VecDeque::new(),
+                running: 0,
+                limit: 1,
+                jobs: Vec::new(),
+            }),
+        }
+    }
+
+    pub fn enqueue(
+        &self,
+        id: String,
+        source: PathBuf,
+        output_dir: PathBuf,
+        options: UpscaleOptions,
+        video2x: Video2x,
+        limit: usize,
+    ) -> Result<(), String> {
+        let job = Arc::new(UpscaleJob {
+            progress: Mutex::new(UpscaleProgress {
+                job_id: id.clone(),
+                state: UpscaleState::Queued,
+                source: source.to_string_lossy().to_string(),
+                output: None,
+                processed_frames: 0,
+                total_frames: 0,
+                percent: 0.0,
+                file_id: None,
+                error: None,
+            }),
+            id,
+            source,
+            output_dir,
+            options,
+            video2x,
+            cancelled: AtomicBool::new(false),
+        });
+
+        {
+            let mut queue = self
+                .queue
+                .lock()
+                .map_err(|_| "Upscale queue is unavailable")?;
+            queue.limit = limit.max(1);
+            queue.jobs.push(Arc::clone(&job));
+            queue.pending.push_back(job);
+
+            let finished = queue
+                .jobs
+                .iter()
+                .filter(|job| job.progress().state.is_finished())
+                .count();
+            if finished > FINISHED_JOBS_KEPT {
+                let mut excess = finished - FINISHED_JOBS_KEPT;
+                queue.jobs.retain(|job| {
+                    let drop = excess > 0 && job.progress().state.is_finished();
+                    if drop {
+                        excess -= 1;
+                    }
+                    !drop
+                });
+            }
+        }
+
+        self.dispatch();
+        Ok(())
+    }
+
+    /// Starts pending jobs while fewer than the limit are running.
+    fn dispatch(&self) {
+        let Ok(mut queue) = self.queue.lock() else {
+            return;
+        };
+
+        while queue.running < queue.limit {
+            let Some(job) = queue.pending.pop_front() else {
+                break;
+            };
+            if job.is_cancelled() {
+                continue;
+            }
+
+            queue.running += 1;
+            let handle = self.handle.clone();
+            let spawned = thread::Builder::new()
+                .name(format!("upscale-{}", job.id))
+                .spawn(move || {
+                    worker::run(&handle, &job);
+                    handle.state::<UpscaleManager>().finished();
+                });
+
+            if let Err(e) = spawned {
+                log::error!("Failed to start upscale: {}", e);
+                queue.running -= 1;
+            }
+        }
+    }
+
+    fn finished(&self) {
+        if let Ok(mut queue) = self.queue.lock() {
+            queue.running = queue.running.saturating_sub(1);
+        }
+        self.dispatch();
+    }
+
+    /// Picks the output path of `job`, numbering `wanted` when a file or another queued
+    /// or running job already has it, and records it while the queue is locked, so two
+    /// jobs never write to, or clean up, the same file.
+    fn claim_output(&self, job: &UpscaleJob, wanted: &Path) -> PathBuf {
+        let Ok(queue) = self.queue.lock() else {
+            return transfer::available_name(wanted, transfer::is_taken);
+        };
+
+        let claimed: Vec<PathBuf> = queue
+            .jobs
+            .iter()
+            .filter(|other| other.id != job.id)
+            .map(|other| other.progress())
+            .filter(|progress| !progress.state.is_finished())
+            .filter_map(|progress| progress.output.map(PathBuf::from))
+            .collect();
+        let output = transfer::available_name(wanted, |candidate| {
+            transfer::is_taken(candidate) || claimed.iter().any(|claimed| claimed == candidate)
+        });
+
+        job.update(|progress| progress.output = Some(output.to_string_lossy().to_string()));
+        output
+    }
+
+    /// Cancels a job. A queued job is dropped, a running one has its Video2x process
+    /// killed.
+    pub fn cancel(&self, id: &str) -> Result<(), String> {
+        let mut queue = self
+            .queue
+            .lock()
+            .map_err(|_| "Upscale queue is unavailable")?;
+        let job = queue
+            .jobs
+            .iter()
+            .find(|job| job.id == id)
+            .cloned()
+            .ok_or("Upscale not found")?;
+
+        let state = job.progress().state;
+        if state.is_finished() {
+            return Err(format!("Upscale cannot be cancelled while {:?}", state));
+        }
+
+        job.cancelled.store(true, Ordering::Relaxed);
+        // A job already handed to its thread notices the flag there.
+        let waiting = queue.pending.len();
+        queue.pending.retain(|pending| pending.id != id);
+        if queue.pending.len() < waiting {
+            job.update(|progress| progress.state = UpscaleState::Cancelled);
+            drop(queue);
+            worker::emit_finished(&self.handle, &job);
+        }
+        Ok(())
+    }
+
+    pub fn list(&self) -> Vec<UpscaleProgress> {
+        self.queue
+            .lock()
+            .map(|queue| queue.jobs.iter().map(|job| job.progress()).collect())
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,68 @@
+use std::path::Path;
+use tauri::Manager;
+use uuid::Uuid;
+
+use crate::files::file_details::get_file_details;
+use crate::files::get_downloads_dir::resolve_downloads_dir;
+use crate::upscale::video2x::Video2x;
+use crate::upscale::{MAX_SCALE, MIN_SCALE, OutputLocation, UpscaleManager, UpscaleOptions};
+use crate::utils::db;
+use crate::utils::path_policy::{Operation, PathPolicy};
+
+/// Queues an upscale of a video or image and returns its id. Progress arrives through
+/// `upscale-progress` events and the result, with the new file's id, through
+/// `upscale-finished`.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn start_upscale(
+    handle: tauri::AppHandle,
+    source: String,
+    options: UpscaleOptions,
+) -> Result<String, String> {
+    if !(MIN_SCALE..=MAX_SCALE).contains(&options.scale) {
+        return Err(format!(
+            "Scale must be between {} and {}",
+            MIN_SCALE, MAX_SCALE
+        ));
+    }
+
+    let policy = PathPolicy::load(&handle).await;
+    let source = policy.check(Path::new(&source), Operation::Read)?;
+    if !source.is_file() {
+        return Err("File does not exist".into());
+    }
+
+    let details = get_file_details(source.to_string_lossy().to_string())?;
+    if details.file_type != "video" && details.file_type != "image" {
+        return Err("Only videos and images can be upscaled".into());
+    }
+
+    let output_dir = match options.output_location {
+        OutputLocation::Source => {
+            let parent = source.parent().ok_or("Invalid source path")?;
+            policy.check(parent, Operation::Write)?
+        }
+        OutputLocation::Downloads => resolve_downloads_dir(&handle).await?,
+    };
+
+    let video2x = Video2x::load(&handle).await?;
+
+    let pool = db::pool(&handle).await?;
+    let limit: Option<i64> =
+        sqlx::query_scalar("SELECT upscale_concurrency FROM settings ORDER BY id LIMIT 1")
+            .fetch_optional(&pool)
+            .await
+            .map_err(|e| format!("Failed to read upscale concurrency: {}", e))?
+            .flatten();
+
+    let job_id = Uuid::new_v4().to_string();
+    handle.state::<UpscaleManager>().enqueue(
+        job_id.clone(),
+        source,
+        output_dir,
+        options,
+        video2x,
+        limit.unwrap_or(1).max(1) as usize,
+    )?;
+
+    Ok(job_id)
+}
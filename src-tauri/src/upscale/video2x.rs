@@ -0,0 +1,197 @@
+use regex::Regex;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{ChildStderr, ChildStdout, Stdio};
+use std::sync::LazyLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::Duration;
+use tauri::AppHandle;
+
+use crate::upscale::{Processor, UpscaleOptions};
+use crate::utils::{db, process};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Lines of output kept to explain a failed run.
+const ERROR_TAIL_LINES: usize = 5;
+
+/// `Processing frame 120/4500 (2.67%)` style lines from Video2x 6.
+static FRAME_PROGRESS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)frame[^0-9]*(\d+)\s*/\s*(\d+)").expect("valid frame progress pattern")
+});
+
+/// The tqdm bar of earlier Video2x releases: ` 3%|▎         | 120/4500 [00:04<02:30, ...]`.
+static BAR_PROGRESS: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\|\s*(\d+)/(\d+)\s*\[").expect("valid progress bar pattern"));
+
+/// Runs the Video2x command line at `program`.
+pub struct Video2x {
+    program: PathBuf,
+}
+
+impl Video2x {
+    /// Uses `settings.video2x_path`, falling back to `video2x` on `PATH`.
+    pub async fn load(handle: &AppHandle) -> Result<Video2x, String> {
+        let pool = db::pool(handle).await?;
+        let configured: Option<String> =
+            sqlx::query_scalar("SELECT video2x_path FROM settings ORDER BY id LIMIT 1")
+                .fetch_optional(&pool)
+                .await
+                .map_err(|e| format!("Failed to read Video2x path: {}", e))?
+                .flatten()
+                .filter(|path: &String| !path.trim().is_empty());
+
+        let program = match configured {
+            Some(path) => {
+                let path = PathBuf::from(path);
+                if !path.is_file() {
+                    return Err(format!("Video2x not found at {}", path.display()));
+                }
+                path
+            }
+            None => process::find_program("video2x")
+                .ok_or("Set the Video2x path in the settings first")?,
+        };
+
+        Ok(Video2x { program })
+    }
+
+    /// Upscales `source` into `output`, calling `on_progress` with processed and total
+    /// frames. Setting `cancelled` kills the process.
+    pub fn run(
+        &self,
+        source: &Path,
+        output: &Path,
+        options: &UpscaleOptions,
+        cancelled: &AtomicBool,
+        on_progress: &mut dyn FnMut(u64, u64),
+    ) -> Result<(), String> {
+        let mut command = process::command(&self.program);
+        command
+            .arg("-i")
+            .arg(source)
+            .arg("-o")
+            .arg(output)
+            .args(["-p", options.processor.as_str()])
+            .args(["-s", &options.scale.to_string()]);
+        if let Some(model) = &options.model {
+            let flag = match options.processor {
+                Processor::Libplacebo => "--libplacebo-shader",
+                Processor::Realcugan => "--realcugan-model",
+                Processor::Realesrgan => "--realesrgan-model",
+            };
+            command.args([flag, model]);
+        }
+        if let Some(device) = options.device {
+            command.args(["-d", &device.to_string()]);
+        }
+
+        let mut child = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to start Video2x: {}", e))?;
+
+        let (sender, lines) = mpsc::channel();
+        read_lines(child.stdout.take(), sender.clone());
+        read_lines(child.stderr.take(), sender);
+
+        let mut tail: Vec<String> = Vec::new();
+        let mut handle_line = |line: String| {
+            if let Some((processed, total)) = parse_progress(&line) {
+                on_progress(processed, total);
+            } else {
+                tail.push(line);
+                if tail.len() > ERROR_TAIL_LINES {
+                    tail.remove(0);
+                }
+            }
+        };
+
+        let status = loop {
+            match lines.recv_timeout(POLL_INTERVAL) {
+                Ok(line) => handle_line(line),
+                Err(RecvTimeoutError::Timeout) => {}
+                // Both pipes are closed, the process is about to exit.
+                Err(RecvTimeoutError::Disconnected) => {
+                    break child
+                        .wait()
+                        .map_err(|e| format!("Failed to wait for Video2x: {}", e))?;
+                }
+            }
+
+            if cancelled.load(Ordering::Relaxed) {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err("Cancelled".into());
+            }
+            if let Some(status) = child
+                .try_wait()
+                .map_err(|e| format!("Failed to wait for Video2x: {}", e))?
+            {
+                // The readers may still hold the last lines, such as the error message.
+                while let Ok(line) = lines.recv_timeout(POLL_INTERVAL) {
+                    handle_line(line);
+                }
+                break status;
+            }
+        };
+
+        if status.success() {
+            Ok(())
+        } else {
+            Err(format!("Video2x failed: {}", tail.join("\n")))
+        }
+    }
+}
+
+pub fn parse_progress(line: &str) -> Option<(u64, u64)> {
+    let captures = FRAME_PROGRESS
+        .captures(line)
+        .or_else(|| BAR_PROGRESS.captures(line))?;
+    let processed = captures[1].parse().ok()?;
+    let total = captures[2].parse().ok()?;
+    (total > 0).then_some((processed, total))
+}
+
+trait Pipe: Read + Send + 'static {}
+impl Pipe for ChildStdout {}
+impl Pipe for ChildStderr {}
+
+/// Forwards the output of `pipe` line by line. Progress bars redraw with `\r`, so that
+/// ends a line as well.
+fn read_lines(pipe: Option<impl Pipe>, sender: Sender<String>) {
+    let Some(mut pipe) = pipe else {
+        return;
+    };
+
+    thread::spawn(move || {
+        let mut buffer = [0; 4096];
+        let mut line = Vec::new();
+        loop {
+            let read = match pipe.read(&mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(read) => read,
+            };
+            for &byte in &buffer[..read] {
+                if byte == b'\n' || byte == b'\r' {
+                    if !line.is_empty() {
+                        let text = String::from_utf8_lossy(&line).trim().to_string();
+                        line.clear();
+                        if !text.is_empty() && sender.send(text).is_err() {
+                            return;
+                        }
+                    }
+                } else {
+                    line.push(byte);
+                }
+            }
+        }
+        if !line.is_empty() {
+            let _ = sender.send(String::from_utf8_lossy(&line).trim().to_string());
+        }
+    });
+}
@@ -0,0 +1,122 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::files::file_details::get_file_details;
+use crate::library::index;
+use crate::upscale::{UpscaleJob, UpscaleManager, UpscaleState};
+use crate::utils::db;
+
+const PROGRESS_EVENT: &str = "upscale-progress";
+const FINISHED_EVENT: &str = "upscale-finished";
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+pub fn run(handle: &AppHandle, job: &UpscaleJob) {
+    if job.is_cancelled() {
+        job.update(|progress| progress.state = UpscaleState::Cancelled);
+        emit_finished(handle, job);
+        return;
+    }
+
+    let output = handle
+        .state::<UpscaleManager>()
+        .claim_output(job, &output_path(job));
+    job.update(|progress| progress.state = UpscaleState::Running);
+    emit_progress(handle, job);
+
+    let mut last_emit = Instant::now();
+    let mut on_progress = |processed: u64, total: u64| {
+        job.update(|progress| {
+            progress.processed_frames = processed;
+            progress.total_frames = total;
+            progress.percent = (processed as f64 / total as f64 * 100.0).min(100.0);
+        });
+        if last_emit.elapsed() >= PROGRESS_INTERVAL {
+            emit_progress(handle, job);
+            last_emit = Instant::now();
+        }
+    };
+
+    let result = job.video2x.run(
+        &job.source,
+        &output,
+        &job.options,
+        &job.cancelled,
+        &mut on_progress,
+    );
+
+    if let Err(e) = result {
+        // Video2x leaves a truncated file behind when it fails or is killed.
+        let _ = fs::remove_file(&output);
+        let cancelled = job.is_cancelled();
+        job.update(|progress| {
+            if cancelled {
+                progress.state = UpscaleState::Cancelled;
+            } else {
+                progress.state = UpscaleState::Failed;
+                progress.error = Some(e);
+            }
+        });
+        emit_finished(handle, job);
+        return;
+    }
+
+    let registered = if output.is_file() {
+        // The output is complete at this point and kept even when indexing fails.
+        register(handle, &output).map_err(|e| {
+            format!(
+                "Upscaled file saved to {} but not added to the library: {}",
+                output.display(),
+                e
+            )
+        })
+    } else {
+        Err("Video2x finished without writing the output file".into())
+    };
+
+    match registered {
+        Ok(file_id) => job.update(|progress| {
+            progress.state = UpscaleState::Completed;
+            progress.percent = 100.0;
+            progress.file_id = Some(file_id);
+        }),
+        Err(e) => job.update(|progress| {
+            progress.state = UpscaleState::Failed;
+            progress.error = Some(e);
+        }),
+    }
+    emit_finished(handle, job);
+}
+
+/// `clip.mp4` becomes `clip_upscaled_2x.mp4` in the output folder, numbered by
+/// `claim_output` when taken.
+fn output_path(job: &UpscaleJob) -> PathBuf {
+    let stem = job.source.file_stem().unwrap_or_default().to_string_lossy();
+    let mut name = format!("{}_upscaled_{}x", stem, job.options.scale);
+    if let Some(extension) = job.source.extension() {
+        name.push('.');
+        name.push_str(&extension.to_string_lossy());
+    }
+
+    job.output_dir.join(name)
+}
+
+/// Adds the upscaled file to the `files` table and returns its id.
+fn register(handle: &AppHandle, output: &Path) -> Result<i64, String> {
+    let file = get_file_details(output.to_string_lossy().to_string())?;
+    tauri::async_runtime::block_on(async {
+        let pool = db::pool(handle).await?;
+        index::upsert_file(&pool, &file).await
+    })
+}
+
+fn emit_progress(handle: &AppHandle, job: &UpscaleJob) {
+    let _ = handle.emit(PROGRESS_EVENT, &job.progress());
+}
+
+pub fn emit_finished(handle: &AppHandle, job: &UpscaleJob) {
+    let progress = job.progress();
+    let _ = handle.emit(PROGRESS_EVENT, &progress);
+    let _ = handle.emit(FINISHED_EVENT, &progress);
+}
@@ -0,0 +1,36 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Cancellation flags of long-running listings, keyed by an id the caller picks so it
+/// can cancel before the command has returned anything.
+#[derive(Default)]
+pub struct CancelRegistry {
+    flags: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+impl CancelRegistry {
+    pub fn register(&self, id: &str) -> Arc<AtomicBool> {
+        let flag = Arc::new(AtomicBool::new(false));
+        if let Ok(mut flags) = self.flags.lock() {
+            flags.insert(id.to_string(), Arc::clone(&flag));
+        }
+        flag
+    }
+
+    pub fn remove(&self, id: &str) {
+        if let Ok(mut flags) = self.flags.lock() {
+            flags.remove(id);
+        }
+    }
+
+    /// Returns whether something was registered under `id`.
+    pub fn cancel(&self, id: &str) -> bool {
+        self.flags
+            .lock()
+            .ok()
+            .and_then(|flags| flags.get(id).cloned())
+            .map(|flag| flag.store(true, Ordering::Relaxed))
+            .is_some()
+    }
+}
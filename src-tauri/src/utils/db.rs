@@ -0,0 +1,34 @@
+use sqlx::{Pool, Sqlite};
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_sql::{DbInstances, DbPool};
+
+pub const DB_URL: &str = "sqlite:archv3.db";
+
+/// Returns the connection pool opened by the SQL plugin. The pool only exists once the
+/// frontend has loaded the database, so commands running before that get an error.
+pub async fn pool(handle: &AppHandle) -> Result<Pool<Sqlite>, String> {
+    let instances = handle
+        .try_state::<DbInstances>()
+        .ok_or("Database plugin is not initialized")?;
+    let instances = instances.0.read().await;
+
+    match instances.get(DB_URL) {
+        Some(DbPool::Sqlite(pool)) => Ok(pool.clone()),
+        None => Err("Database is not loaded".to_string()),
+    }
+}
+
+/// Location of the database file, resolved the way the SQL plugin resolves `DB_URL`.
+pub fn file_path(handle: &AppHandle) -> Result<PathBuf, String> {
+    let name = DB_URL
+        .split_once(':')
+        .map(|(_, name)| name)
+        .ok_or("Invalid database URL")?;
+
+    handle
+        .path()
+        .app_config_dir()
+        .map(|dir| dir.join(name))
+        .map_err(|e| format!("Failed to resolve App Config directory: {}", e))
+}
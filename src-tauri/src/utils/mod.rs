@@ -1 +1,5 @@
-pub mod protected_paths;
+pub mod cancellation;
+pub mod db;
+pub mod natural_sort;
+pub mod path_policy;
+pub mod process;
@@ -0,0 +1,52 @@
+use std::cmp::Ordering;
+
+/// Compares names the way file managers do: case-insensitively, with runs of digits
+/// compared by value so `file2` sorts before `file10`.
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut left = a.chars().peekable();
+    let mut right = b.chars().peekable();
+
+    loop {
+        match (left.peek().copied(), right.peek().copied()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(l), Some(r)) if l.is_ascii_digit() && r.is_ascii_digit() => {
+                let l_digits = take_digits(&mut left);
+                let r_digits = take_digits(&mut right);
+
+                let l_value = l_digits.trim_start_matches('0');
+                let r_value = r_digits.trim_start_matches('0');
+                let ordering = l_value
+                    .len()
+                    .cmp(&r_value.len())
+                    .then_with(|| l_value.cmp(r_value))
+                    // Equal values with more leading zeros sort last.
+                    .then_with(|| l_digits.len().cmp(&r_digits.len()));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(l), Some(r)) => {
+                let ordering = l.to_lowercase().cmp(r.to_lowercase());
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                left.next();
+                right.next();
+            }
+        }
+    }
+}
+
+fn take_digits(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+    let mut digits = String::new();
+    while let Some(&c) = chars.peek() {
+        if !c.is_ascii_digit() {
+            break;
+        }
+        digits.push(c);
+        chars.next();
+    }
+    digits
+}
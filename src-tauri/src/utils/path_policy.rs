@@ -0,0 +1,314 @@
+use std::path::{Component, Path, PathBuf, Prefix};
+use tauri::{AppHandle, Manager};
+
+use crate::utils::db;
+
+/// What a command is about to do with a path. Reads are only refused for roots holding
+/// secrets, everything else is refused for any protected root.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Read,
+    Copy,
+    Write,
+    Delete,
+}
+
+impl Operation {
+    fn verb(self) -> &'static str {
+        match self {
+            Operation::Read => "access",
+            Operation::Copy => "copy",
+            Operation::Write => "write to",
+            Operation::Delete => "delete",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Protection {
+    /// System locations that may be browsed but never modified or copied from.
+    Modify,
+    /// Locations holding credentials that may not be touched at all.
+    All,
+}
+
+#[derive(Debug, Clone)]
+struct DenyRoot {
+    components: Vec<String>,
+    protection: Protection,
+}
+
+#[derive(Debug, Clone)]
+pub struct PathPolicy {
+    deny: Vec<DenyRoot>,
+    allow: Vec<Vec<String>>,
+    case_insensitive: bool,
+}
+
+impl PathPolicy {
+    /// Loads the platform defaults merged with the user's extra roots from the settings
+    /// table. Falls back to the defaults alone while the database is not loaded yet.
+    pub async fn load(handle: &AppHandle) -> PathPolicy {
+        let home = handle.path().home_dir().ok();
+        let policy = PathPolicy::platform_default(home.as_deref());
+
+        let Ok(pool) = db::pool(handle).await else {
+            return policy;
+        };
+
+        let roots: Option<(Option<String>, Option<String>)> = sqlx::query_as(
+            "SELECT protected_paths, allowed_paths FROM settings ORDER BY id LIMIT 1",
+        )
+        .fetch_optional(&pool)
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("Failed to read path policy from settings: {}", e);
+            None
+        });
+
+        match roots {
+            Some((deny, allow)) => {
+                policy.with_user_roots(parse_roots(deny.as_deref()), parse_roots(allow.as_deref()))
+            }
+            None => policy,
+        }
+    }
+
+    pub fn platform_default(home: Option<&Path>) -> PathPolicy {
+        let mut policy = PathPolicy {
+            deny: Vec::new(),
+            allow: Vec::new(),
+            case_insensitive: cfg!(any(target_os = "windows", target_os = "macos")),
+        };
+
+        for root in default_system_roots() {
+            policy.deny(&root, Protection::Modify);
+        }
+        for root in default_allowed_roots() {
+            policy.allow(&root);
+        }
+
+        if let Some(home) = home {
+            // Home directories can live below a protected root (e.g. /var/home on
+            // Silverblue), so they are explicitly allowed.
+            policy.allow(home);
+
+            for secret in SECRET_HOME_DIRS {
+                policy.deny(&home.join(secret), Protection::All);
+            }
+        }
+
+        policy
+    }
+
+    pub fn with_user_roots(mut self, deny: Vec<PathBuf>, allow: Vec<PathBuf>) -> PathPolicy {
+        for root in deny {
+            self.deny(&root, Protection::Modify);
+        }
+        for root in allow {
+            self.allow(&root);
+        }
+        self
+    }
+
+    /// Resolves `path` and refuses it when the most specific matching root is a deny root.
+    /// Returns the resolved path so callers operate on exactly what was checked.
+    pub fn check(&self, path: &Path, operation: Operation) -> Result<PathBuf, String> {
+        self.check_resolved(resolve(path), operation)
+    }
+
+    /// Like `check`, but resolves only the parent of `path`. A symlink then stands for
+    /// itself instead of the entry it points to, which is what moving, renaming or
+    /// deleting it acts on.
+    pub fn check_entry(&self, path: &Path, operation: Operation) -> Result<PathBuf, String> {
+        match (path.parent(), path.file_name()) {
+            (Some(parent), Some(name)) => {
+                let parent = if parent.as_os_str().is_empty() {
+                    Path::new(".")
+                } else {
+                    parent
+                };
+                self.check_resolved(resolve(parent).join(name), operation)
+            }
+            _ => self.check(path, operation),
+        }
+    }
+
+    fn check_resolved(&self, resolved: PathBuf, operation: Operation) -> Result<PathBuf, String> {
+        let components = self.components(&resolved);
+
+        let denied_depth = self
+            .deny
+            .iter()
+            .filter(|root| operation != Operation::Read || root.protection == Protection::All)
+            .filter(|root| components.starts_with(&root.components))
+            .map(|root| root.components.len())
+            .max();
+
+        let Some(denied_depth) = denied_depth else {
+            return Ok(resolved);
+        };
+
+        let allowed_depth = self
+            .allow
+            .iter()
+            .filter(|root| components.starts_with(root))
+            .map(|root| root.len())
+            .max()
+            .unwrap_or(0);
+
+        if allowed_depth > denied_depth {
+            Ok(resolved)
+        } else {
+            Err(format!(
+                "Refusing to {} a protected system path.",
+                operation.verb()
+            ))
+        }
+    }
+
+    fn deny(&mut self, root: &Path, protection: Protection) {
+        let components = self.components(&resolve(root));
+        if !components.is_empty() {
+            self.deny.push(DenyRoot {
+                components,
+                protection,
+            });
+        }
+    }
+
+    fn allow(&mut self, root: &Path) {
+        let components = self.components(&resolve(root));
+        if !components.is_empty() {
+            self.allow.push(components);
+        }
+    }
+
+    /// Splits a path into comparable components. Verbatim prefixes are folded into their
+    /// plain form so `\\?\C:\Windows` and `C:\Windows` compare equal.
+    fn components(&self, path: &Path) -> Vec<String> {
+        path.components()
+            .filter_map(|component| {
+                let part = match component {
+                    Component::Prefix(prefix) => match prefix.kind() {
+                        Prefix::Disk(drive) | Prefix::VerbatimDisk(drive) => {
+                            format!("{}:", drive as char)
+                        }
+                        Prefix::UNC(server, share) | Prefix::VerbatimUNC(server, share) => {
+                            format!(
+                                "\\\\{}\\{}",
+                                server.to_string_lossy(),
+                                share.to_string_lossy()
+                            )
+                        }
+                        _ => prefix.as_os_str().to_string_lossy().to_string(),
+                    },
+                    Component::RootDir => "/".to_string(),
+                    Component::Normal(name) => name.to_string_lossy().to_string(),
+                    Component::CurDir | Component::ParentDir => return None,
+                };
+
+                Some(if self.case_insensitive {
+                    part.to_lowercase()
+                } else {
+                    part
+                })
+            })
+            .collect()
+    }
+}
+
+/// Canonicalizes the longest existing ancestor of `path` and appends the rest, so paths
+/// that are about to be created still resolve symlinks and `..` in their parents.
+fn resolve(path: &Path) -> PathBuf {
+    if let Ok(canonical) = path.canonicalize() {
+        return canonical;
+    }
+
+    let mut missing = Vec::new();
+    let mut ancestor = path;
+    while let Some(parent) = ancestor.parent() {
+        missing.extend(ancestor.components().next_back());
+        ancestor = parent;
+
+        if let Ok(mut canonical) = ancestor.canonicalize() {
+            for component in missing.iter().rev() {
+                match component {
+                    Component::Normal(name) => canonical.push(name),
+                    Component::ParentDir => {
+                        canonical.pop();
+                    }
+                    _ => {}
+                }
+            }
+            return canonical;
+        }
+    }
+
+    path.to_path_buf()
+}
+
+/// Settings store extra roots as a JSON array of paths.
+fn parse_roots(value: Option<&str>) -> Vec<PathBuf> {
+    let Some(value) = value.filter(|value| !value.trim().is_empty()) else {
+        return Vec::new();
+    };
+
+    serde_json::from_str::<Vec<String>>(value)
+        .map(|roots| roots.into_iter().map(PathBuf::from).collect())
+        .unwrap_or_else(|e| {
+            log::warn!("Ignoring malformed path policy roots: {}", e);
+            Vec::new()
+        })
+}
+
+const SECRET_HOME_DIRS: &[&str] = &[".ssh", ".gnupg", ".aws", ".kube"];
+
+#[cfg(target_os = "windows")]
+fn default_system_roots() -> Vec<PathBuf> {
+    let from_env = |name: &str, fallback: &str| {
+        std::env::var_os(name)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(fallback))
+    };
+
+    vec![
+        from_env("SystemRoot", "C:\\Windows"),
+        from_env("ProgramFiles", "C:\\Program Files"),
+        from_env("ProgramFiles(x86)", "C:\\Program Files (x86)"),
+        from_env("ProgramData", "C:\\ProgramData"),
+        PathBuf::from("C:\\Users\\All Users"),
+    ]
+}
+
+#[cfg(target_os = "macos")]
+fn default_system_roots() -> Vec<PathBuf> {
+    [
+        "/System", "/Library", "/bin", "/sbin", "/usr", "/etc", "/var", "/private", "/cores",
+    ]
+    .iter()
+    .map(PathBuf::from)
+    .collect()
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn default_system_roots() -> Vec<PathBuf> {
+    [
+        "/bin", "/boot", "/dev", "/etc", "/lib", "/lib32", "/lib64", "/libx32", "/proc", "/root",
+        "/run", "/sbin", "/sys", "/usr", "/var",
+    ]
+    .iter()
+    .map(PathBuf::from)
+    .collect()
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn default_allowed_roots() -> Vec<PathBuf> {
+    // udisks mounts removable drives below /run.
+    vec![PathBuf::from("/run/media")]
+}
+
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+fn default_allowed_roots() -> Vec<PathBuf> {
+    Vec::new()
+}
@@ -1,7 +0,0 @@
-pub const PROTECTED_PATHS: &[&str] = &[
-    "C:\\Windows",
-    "C:\\Program Files",
-    "C:\\Program Files (x86)",
-    "C:\\Users\\All Users",
-    "C:\\ProgramData",
-];
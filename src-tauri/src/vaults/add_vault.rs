@@ -0,0 +1,49 @@
+use std::path::Path;
+
+use crate::utils::db;
+use crate::utils::path_policy::{Operation, PathPolicy};
+use crate::vaults::cli::drive_letter;
+use crate::vaults::{self, Vault};
+
+/// Adds a VeraCrypt container to the configured vaults.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn add_vault(
+    handle: tauri::AppHandle,
+    name: String,
+    container_path: String,
+    mount_point: Option<String>,
+) -> Result<Vault, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Vault name cannot be empty".into());
+    }
+
+    let container = Path::new(&container_path);
+    if !container.is_file() {
+        return Err("Container file does not exist".into());
+    }
+    PathPolicy::load(&handle)
+        .await
+        .check(container, Operation::Read)?;
+
+    let mount_point = match mount_point.filter(|point| !point.trim().is_empty()) {
+        Some(point) if cfg!(target_os = "windows") => Some(drive_letter(&point)?),
+        Some(point) if !Path::new(&point).is_absolute() => {
+            return Err("Mount point must be an absolute path".into());
+        }
+        point => point,
+    };
+
+    let pool = db::pool(&handle).await?;
+    let id: i64 = sqlx::query_scalar(
+        "INSERT INTO vaults (name, container_path, mount_point) VALUES (?, ?, ?) RETURNING id",
+    )
+    .bind(name)
+    .bind(&container_path)
+    .bind(&mount_point)
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| format!("Failed to add vault: {}", e))?;
+
+    vaults::load_vault(&pool, id).await
+}
@@ -0,0 +1,384 @@
+use std::path::{Path, PathBuf};
+use std::process::Output;
+use std::time::Duration;
+use tauri::AppHandle;
+
+use crate::utils::{db, process};
+
+/// Mounting runs the key derivation, which takes a while with a high PIM.
+const MOUNT_TIMEOUT: Duration = Duration::from_secs(180);
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A volume reported by `veracrypt --list`.
+#[derive(Debug, Clone)]
+pub struct MountedVolume {
+    pub container_path: String,
+    pub mount_point: String,
+}
+
+/// Runs the VeraCrypt command line at `program`. Everything goes through this type, so
+/// pointing `veracrypt_path` at a stub script exercises the vault commands without
+/// VeraCrypt or administrator rights.
+pub struct VeraCrypt {
+    program: PathBuf,
+}
+
+impl VeraCrypt {
+    pub fn new(program: PathBuf) -> VeraCrypt {
+        VeraCrypt { program }
+    }
+
+    /// Uses `settings.veracrypt_path`, falling back to `veracrypt` on `PATH`.
+    pub async fn load(handle: &AppHandle) -> Result<VeraCrypt, String> {
+        let pool = db::pool(handle).await?;
+        let configured: Option<String> =
+            sqlx::query_scalar("SELECT veracrypt_path FROM settings ORDER BY id LIMIT 1")
+                .fetch_optional(&pool)
+                .await
+                .map_err(|e| format!("Failed to read VeraCrypt path: {}", e))?
+                .flatten()
+                .filter(|path: &String| !path.trim().is_empty());
+
+        let program = match configured {
+            Some(path) => {
+                let path = PathBuf::from(path);
+                if !path.is_file() {
+                    return Err(format!("VeraCrypt not found at {}", path.display()));
+                }
+                path
+            }
+            None => process::find_program("veracrypt")
+                .ok_or("Set the VeraCrypt path in the settings first")?,
+        };
+
+        Ok(VeraCrypt::new(program))
+    }
+
+    /// Mounts `container`. The password goes through stdin, except on Windows, where
+    /// VeraCrypt only takes it as an argument.
+    pub fn mount(
+        &self,
+        container: &Path,
+        mount_point: Option<&str>,
+        password: &str,
+        pim: Option<u32>,
+    ) -> Result<(), String> {
+        let output = if cfg!(target_os = "windows") {
+            let letter = mount_point.ok_or("Choose a drive letter for this vault")?;
+            let mut command = process::command(&self.program);
+            // VeraCrypt.exe has no way to read the password from stdin, so it is visible
+            // to other processes of the same user while the mount runs, for example in
+            // Task Manager's command line column. Unlike on Linux and macOS, this cannot
+            // be avoided without the GUI prompting for it.
+            command
+                .arg("/volume")
+                .arg(container)
+                .args(["/letter", drive_letter(letter)?.as_str()])
+                .args(["/password", password])
+                .args(["/quit", "/silent", "/nowaitdlg"]);
+            if let Some(pim) = pim {
+                command.args(["/pim", &pim.to_string()]);
+            }
+            process::output_with_timeout(&mut command, MOUNT_TIMEOUT)
+        } else {
+            let mut command = self.text_command();
+            command
+                .args(["--stdin", "--keyfiles=", "--protect-hidden=no"])
+                .arg(format!("--pim={}", pim.unwrap_or(0)))
+                .arg("--mount")
+                .arg(container);
+            if let Some(mount_point) = mount_point {
+                command.arg(mount_point);
+            }
+            process::output_with_input(
+                &mut command,
+                format!("{}\n", password).as_bytes(),
+                MOUNT_TIMEOUT,
+            )
+        };
+
+        check(output, "mount")
+    }
+
+    /// Dismounts by container path, or by drive letter on Windows.
+    pub fn dismount(&self, container: &Path, mount_point: &str, force: bool) -> Result<(), String> {
+        let output = if cfg!(target_os = "windows") {
+            let mut command = process::command(&self.program);
+            command
+                .args(["/dismount", drive_letter(mount_point)?.as_str()])
+                .args(["/quit", "/silent"]);
+            if force {
+                command.arg("/force");
+            }
+            process::output_with_timeout(&mut command, COMMAND_TIMEOUT)
+        } else {
+            let mut command = self.text_command();
+            if force {
+                command.arg("--force");
+            }
+            command.arg("--dismount").arg(container);
+            process::output_with_timeout(&mut command, COMMAND_TIMEOUT)
+        };
+
+        check(output, "dismount")
+    }
+
+    /// Volumes mounted right now. VeraCrypt has no listing on Windows, where callers
+    /// check the drive letter instead.
+    pub fn list(&self) -> Result<Vec<MountedVolume>, String> {
+        if cfg!(target_os = "windows") {
+            return Ok(Vec::new());
+        }
+
+        let mut command = self.text_command();
+        command.arg("--list");
+        let output = process::output_with_timeout(&mut command, COMMAND_TIMEOUT)
+            .map_err(|e| format!("Failed to run VeraCrypt: {}", e))?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if !output.status.success() {
+            // VeraCrypt treats an empty list as an error.
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if stderr.contains("No volumes mounted") || stdout.contains("No volumes mounted") {
+                return Ok(Vec::new());
+            }
+            return Err(format!("Failed to list volumes: {}", stderr.trim()));
+        }
+
+        Ok(stdout.lines().filter_map(parse_list_line).collect())
+    }
+
+    fn text_command(&self) -> std::process::Command {
+        let mut command = process::command(&self.program);
+        command.args(["--text", "--non-interactive"]);
+        command
+    }
+}
+
+/// Parses `1: /path/to/container.hc /dev/mapper/veracrypt1 /media/veracrypt1`. Paths
+/// may contain spaces, the device never does.
+fn parse_list_line(line: &str) -> Option<MountedVolume> {
+    let (_, rest) = line.split_once(": ")?;
+    let device_start = rest.find(" /dev/")?;
+    let container_path = rest[..device_start].trim();
+    let after_device = &rest[device_start + 1..];
+    let (_, mount_point) = after_device.split_once(' ')?;
+
+    Some(MountedVolume {
+        container_path: container_path.to_string(),
+        mount_point: mount_point.trim().to_string(),
+    })
+}
+
+/// Accepts `X`, `X:` or `X:\` and returns the bare letter.
+pub fn drive_letter(mount_point: &str) -> Result<String, String> {
+    let letter = mount_point
+        .trim()
+        .trim_end_matches(['\\', '/'])
+        .trim_end_matches(':');
+    match letter.chars().next() {
+        Some(c) if letter.len() == 1 && c.is_ascii_alphabetic() => {
+            Ok(c.to_ascii_uppercase().to_string())
+        }
+        _ => Err(format!("Invalid drive letter: {}", mount_point)),
+    }
+}
+
+fn check(output: std::io::Result<Output>, action: &str) -> Result<(), String> {
+    let output = output.map_err(|e| format!("Failed to run VeraCrypt: {}", e))?;
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let message = if stderr.trim().is_empty() {
+        stdout.trim()
+    } else {
+        stderr.trim()
+    };
+    Err(format!("Failed to {} vault: {}", action, message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_list_lines() {
+        let volume =
+            parse_list_line("1: /home/me/vault.hc /dev/mapper/veracrypt1 /media/veracrypt1")
+                .unwrap();
+        assert_eq!(volume.container_path, "/home/me/vault.hc");
+        assert_eq!(volume.mount_point, "/media/veracrypt1");
+    }
+
+    #[test]
+    fn keeps_spaces_in_list_paths() {
+        let volume = parse_list_line(
+            "2: /home/me/My Vaults/a b.hc /dev/mapper/veracrypt2 /media/me/Vault Two",
+        )
+        .unwrap();
+        assert_eq!(volume.container_path, "/home/me/My Vaults/a b.hc");
+        assert_eq!(volume.mount_point, "/media/me/Vault Two");
+    }
+
+    #[test]
+    fn skips_list_lines_without_device() {
+        assert!(parse_list_line("").is_none());
+        assert!(parse_list_line("No volumes mounted.").is_none());
+        assert!(parse_list_line("1: /home/me/vault.hc /media/veracrypt1").is_none());
+    }
+
+    #[test]
+    fn normalizes_drive_letters() {
+        for mount_point in ["x", "X", "X:", "x:\\", "X:/", " X: "] {
+            assert_eq!(drive_letter(mount_point).unwrap(), "X");
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_drive_letters() {
+        for mount_point in ["", ":", "XY", "1:", "C:\\Vault", "é"] {
+            assert!(drive_letter(mount_point).is_err(), "{:?}", mount_point);
+        }
+    }
+
+    /// Drives `VeraCrypt` against a shell script that records its arguments and stdin
+    /// next to itself.
+    #[cfg(unix)]
+    mod stub {
+        use super::super::VeraCrypt;
+        use std::fs;
+        use std::os::unix::fs::PermissionsExt;
+        use std::path::Path;
+        use tempfile::TempDir;
+
+        struct Stub {
+            dir: TempDir,
+        }
+
+        impl Stub {
+            fn new(body: &str) -> Stub {
+                let dir = tempfile::tempdir().unwrap();
+                let script = format!(
+                    "#!/bin/sh\nprintf '%s\\n' \"$@\" > '{dir}/args'\ncat > '{dir}/stdin'\n{body}\n",
+                    dir = dir.path().display(),
+                    body = body
+                );
+                let program = dir.path().join("veracrypt");
+                fs::write(&program, script).unwrap();
+                fs::set_permissions(&program, fs::Permissions::from_mode(0o755)).unwrap();
+
+                Stub { dir }
+            }
+
+            fn veracrypt(&self) -> VeraCrypt {
+                VeraCrypt::new(self.dir.path().join("veracrypt"))
+            }
+
+            fn args(&self) -> Vec<String> {
+                fs::read_to_string(self.dir.path().join("args"))
+                    .unwrap()
+                    .lines()
+                    .map(str::to_string)
+                    .collect()
+            }
+
+            fn stdin(&self) -> String {
+                fs::read_to_string(self.dir.path().join("stdin")).unwrap()
+            }
+        }
+
+        #[test]
+        fn mount_passes_the_password_on_stdin() {
+            let stub = Stub::new("exit 0");
+            stub.veracrypt()
+                .mount(
+                    Path::new("/vaults/a b.hc"),
+                    Some("/media/vault"),
+                    "secret",
+                    Some(7),
+                )
+                .unwrap();
+
+            let args = stub.args();
+            assert_eq!(
+                args,
+                [
+                    "--text",
+                    "--non-interactive",
+                    "--stdin",
+                    "--keyfiles=",
+                    "--protect-hidden=no",
+                    "--pim=7",
+                    "--mount",
+                    "/vaults/a b.hc",
+                    "/media/vault",
+                ]
+            );
+            assert!(!args.iter().any(|arg| arg.contains("secret")));
+            assert_eq!(stub.stdin(), "secret\n");
+        }
+
+        #[test]
+        fn mount_reports_the_veracrypt_error() {
+            let stub = Stub::new("echo 'Incorrect password' >&2\nexit 1");
+            let error = stub
+                .veracrypt()
+                .mount(Path::new("/vaults/a.hc"), None, "wrong", None)
+                .unwrap_err();
+
+            assert_eq!(error, "Failed to mount vault: Incorrect password");
+            assert!(stub.args().contains(&"--pim=0".to_string()));
+        }
+
+        #[test]
+        fn dismount_by_container() {
+            let stub = Stub::new("exit 0");
+            stub.veracrypt()
+                .dismount(Path::new("/vaults/a.hc"), "/media/vault", true)
+                .unwrap();
+
+            assert_eq!(
+                stub.args(),
+                [
+                    "--text",
+                    "--non-interactive",
+                    "--force",
+                    "--dismount",
+                    "/vaults/a.hc"
+                ]
+            );
+        }
+
+        #[test]
+        fn list_parses_mounted_volumes() {
+            let stub = Stub::new(
+                "echo '1: /vaults/a.hc /dev/mapper/veracrypt1 /media/veracrypt1'\n\
+                 echo '2: /vaults/b c.hc /dev/mapper/veracrypt2 /media/b c'",
+            );
+            let volumes = stub.veracrypt().list().unwrap();
+
+            assert_eq!(stub.args(), ["--text", "--non-interactive", "--list"]);
+            assert_eq!(volumes.len(), 2);
+            assert_eq!(volumes[1].container_path, "/vaults/b c.hc");
+            assert_eq!(volumes[1].mount_point, "/media/b c");
+        }
+
+        #[test]
+        fn list_treats_no_volumes_as_empty() {
+            let stub = Stub::new("echo 'Error: No volumes mounted.' >&2\nexit 1");
+            assert!(stub.veracrypt().list().unwrap().is_empty());
+        }
+
+        #[test]
+        fn list_reports_other_errors() {
+            let stub = Stub::new("echo 'Permission denied' >&2\nexit 1");
+            assert_eq!(
+                stub.veracrypt().list().unwrap_err(),
+                "Failed to list volumes: Permission denied"
+            );
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use std::path::Path;
+use tauri::Manager;
+
+use crate::library::index;
+use crate::library::watcher::LibraryWatcher;
+use crate::utils::db;
+use crate::vaults::cli::VeraCrypt;
+use crate::vaults::{self, Vault};
+
+/// Dismounts a vault. Its library root stays registered and its files are marked
+/// missing until the vault is mounted again. `force` dismounts even while files on
+/// the volume are open.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn dismount_vault(handle: tauri::AppHandle, id: i64, force: bool) -> Result<(), String> {
+    let pool = db::pool(&handle).await?;
+    let vault = vaults::load_vault(&pool, id).await?;
+
+    let cli = VeraCrypt::load(&handle).await?;
+    let mount_point = tauri::async_runtime::spawn_blocking(move || dismount(&cli, &vault, force))
+        .await
+        .map_err(|e| format!("Failed to dismount vault: {}", e))??;
+
+    if let Err(e) = handle
+        .state::<LibraryWatcher>()
+        .unwatch(Path::new(&mount_point))
+    {
+        log::warn!("{}", e);
+    }
+    index::mark_missing_under(&pool, &mount_point).await?;
+
+    Ok(())
+}
+
+fn dismount(cli: &VeraCrypt, vault: &Vault, force: bool) -> Result<String, String> {
+    let mount_point = vaults::mounted_at(vault, &cli.list()?).ok_or("Vault is not mounted")?;
+    cli.dismount(Path::new(&vault.container_path), &mount_point, force)?;
+    Ok(mount_point)
+}
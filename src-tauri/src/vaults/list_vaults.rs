@@ -0,0 +1,30 @@
+use crate::utils::db;
+use crate::vaults::cli::VeraCrypt;
+use crate::vaults::{self, VaultStatus};
+
+/// Lists the configured vaults with their current mount state. Without a usable
+/// VeraCrypt every vault is reported as not mounted.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn list_vaults(handle: tauri::AppHandle) -> Result<Vec<VaultStatus>, String> {
+    let pool = db::pool(&handle).await?;
+    let configured = vaults::load_vaults(&pool).await?;
+
+    let volumes = match VeraCrypt::load(&handle).await {
+        Ok(cli) => tauri::async_runtime::spawn_blocking(move || cli.list())
+            .await
+            .map_err(|e| format!("Failed to list volumes: {}", e))?
+            .unwrap_or_else(|e| {
+                log::warn!("{}", e);
+                Vec::new()
+            }),
+        Err(_) => Vec::new(),
+    };
+
+    Ok(configured
+        .into_iter()
+        .map(|vault| VaultStatus {
+            mounted_at: vaults::mounted_at(&vault, &volumes),
+            vault,
+        })
+        .collect())
+}
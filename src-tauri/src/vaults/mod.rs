@@ -0,0 +1,90 @@
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+use std::fs;
+use std::path::Path;
+
+use crate::vaults::cli::{MountedVolume, drive_letter};
+
+pub mod add_vault;
+pub mod cli;
+pub mod dismount_vault;
+pub mod list_vaults;
+pub mod mount_vault;
+pub mod remove_vault;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Vault {
+    pub id: i64,
+    pub name: String,
+    pub container_path: String,
+    /// Folder to mount into, or the drive letter on Windows. VeraCrypt picks one when
+    /// empty, except on Windows.
+    pub mount_point: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VaultStatus {
+    #[serde(flatten)]
+    pub vault: Vault,
+    /// Where the volume is mounted right now, if it is.
+    pub mounted_at: Option<String>,
+}
+
+type VaultRow = (i64, String, String, Option<String>, String);
+
+fn from_row((id, name, container_path, mount_point, created_at): VaultRow) -> Vault {
+    Vault {
+        id,
+        name,
+        container_path,
+        mount_point,
+        created_at,
+    }
+}
+
+pub async fn load_vault(pool: &Pool<Sqlite>, id: i64) -> Result<Vault, String> {
+    let row: Option<VaultRow> = sqlx::query_as(
+        "SELECT id, name, container_path, mount_point, created_at FROM vaults WHERE id = ?",
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("Failed to read vault: {}", e))?;
+
+    row.map(from_row)
+        .ok_or_else(|| "Vault not found".to_string())
+}
+
+pub async fn load_vaults(pool: &Pool<Sqlite>) -> Result<Vec<Vault>, String> {
+    let rows: Vec<VaultRow> = sqlx::query_as(
+        "SELECT id, name, container_path, mount_point, created_at FROM vaults ORDER BY name",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to read vaults: {}", e))?;
+
+    Ok(rows.into_iter().map(from_row).collect())
+}
+
+/// Finds where `vault` is mounted among `volumes`. On Windows, where VeraCrypt cannot
+/// list volumes, a present drive letter counts as mounted.
+pub fn mounted_at(vault: &Vault, volumes: &[MountedVolume]) -> Option<String> {
+    if cfg!(target_os = "windows") {
+        let letter = drive_letter(vault.mount_point.as_deref()?).ok()?;
+        let root = format!("{}:\\", letter);
+        return Path::new(&root).is_dir().then_some(root);
+    }
+
+    volumes
+        .iter()
+        .find(|volume| same_path(&volume.container_path, &vault.container_path))
+        .map(|volume| volume.mount_point.clone())
+}
+
+fn same_path(a: &str, b: &str) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
@@ -0,0 +1,81 @@
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+use crate::library::scan_library::scan_library;
+use crate::utils::db;
+use crate::utils::path_policy::{Operation, PathPolicy};
+use crate::vaults::cli::VeraCrypt;
+use crate::vaults::{self, Vault};
+
+#[derive(Serialize)]
+pub struct MountResult {
+    pub mount_point: String,
+    /// Scan indexing the mounted volume as a library root, reported through the
+    /// `library-scan-progress` events. `None` when the scan could not start.
+    pub scan_id: Option<String>,
+}
+
+/// Mounts a vault through the VeraCrypt command line and registers the mounted volume
+/// as a library root, which also starts indexing it.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn mount_vault(
+    handle: tauri::AppHandle,
+    id: i64,
+    password: String,
+    pim: Option<u32>,
+) -> Result<MountResult, String> {
+    let pool = db::pool(&handle).await?;
+    let vault = vaults::load_vault(&pool, id).await?;
+
+    let container = PathPolicy::load(&handle)
+        .await
+        .check(Path::new(&vault.container_path), Operation::Read)?;
+    if !container.is_file() {
+        return Err("Container file does not exist".into());
+    }
+
+    let cli = VeraCrypt::load(&handle).await?;
+    let mount_point = tauri::async_runtime::spawn_blocking(move || {
+        mount(&cli, &vault, &container, &password, pim)
+    })
+    .await
+    .map_err(|e| format!("Failed to mount vault: {}", e))??;
+
+    let scan_id = match scan_library(handle, mount_point.clone(), None).await {
+        Ok(scan_id) => Some(scan_id),
+        Err(e) => {
+            log::warn!("Failed to index mounted vault: {}", e);
+            None
+        }
+    };
+
+    Ok(MountResult {
+        mount_point,
+        scan_id,
+    })
+}
+
+fn mount(
+    cli: &VeraCrypt,
+    vault: &Vault,
+    container: &Path,
+    password: &str,
+    pim: Option<u32>,
+) -> Result<String, String> {
+    let volumes = cli.list()?;
+    if vaults::mounted_at(vault, &volumes).is_some() {
+        return Err("Vault is already mounted".into());
+    }
+
+    if let Some(point) = &vault.mount_point
+        && !cfg!(target_os = "windows")
+    {
+        fs::create_dir_all(point).map_err(|e| format!("Failed to create mount point: {}", e))?;
+    }
+
+    cli.mount(container, vault.mount_point.as_deref(), password, pim)?;
+
+    vaults::mounted_at(vault, &cli.list()?)
+        .ok_or_else(|| "VeraCrypt reported success but the volume is not mounted".to_string())
+}
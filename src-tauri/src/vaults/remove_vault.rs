@@ -0,0 +1,16 @@
+use crate::utils::db;
+
+/// Forgets a vault. The container file and any library root it was mounted as are
+/// left alone.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn remove_vault(handle: tauri::AppHandle, id: i64) -> Result<(), String> {
+    let pool = db::pool(&handle).await?;
+
+    sqlx::query("DELETE FROM vaults WHERE id = ?")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|e| format!("Failed to remove vault: {}", e))?;
+
+    Ok(())
+}
@@ -4,10 +4,13 @@ import { ORM, SQLiteDialect, TauriAdapter } from '@7otion/orm';
 
 import Layout from '@/layout';
 
+import { AppLock } from '@/components/app-lock';
+import { DatabaseUnlock } from '@/components/database-unlock';
 import { ErrorBoundary } from '@/components/error-boundary';
 import { Loading } from '@/components/loading';
 import { Toaster } from '@/components/sonner';
 
+import { DatabaseIPC } from '@/lib/services/database';
 import { StoreProvider, useObservable, useStore } from '@/lib/store';
 import { TabsStore } from '@/lib/store/tabs';
 
@@ -20,6 +23,7 @@ function App() {
 	const activeTab = tabs.find(t => t.isActive);
 
 	const [dbInitialized, setDbInitialized] = useState(false);
+	const [dbLocked, setDbLocked] = useState(false);
 
 	const initializeDatabase = async () => {
 		const tauriAdapter = new TauriAdapter({
@@ -37,10 +41,27 @@ function App() {
 
 	useEffect(() => {
 		(async () => {
+			// An encrypted database needs its key before the migrations can run.
+			const status = await DatabaseIPC.getDatabaseStatus();
+			if (!status.unlocked) {
+				setDbLocked(true);
+				return;
+			}
 			await initializeDatabase();
 		})();
 	}, []);
 
+	if (dbLocked) {
+		return (
+			<DatabaseUnlock
+				onUnlock={async () => {
+					setDbLocked(false);
+					await initializeDatabase();
+				}}
+			/>
+		);
+	}
+
 	if (!dbInitialized) {
 		return <Loading />;
 	}
@@ -56,18 +77,20 @@ function App() {
 					},
 				}}
 			/>
-			{activeTab && (
-				<StoreProvider stores={activeTab.stores}>
-					<DialogCoordinator />
-					<Layout>
-						<Suspense fallback={<Loading />}>
-							<Router>
-								<AppRouter />
-							</Router>
-						</Suspense>
-					</Layout>
-				</StoreProvider>
-			)}
+			<AppLock>
+				{activeTab && (
+					<StoreProvider stores={activeTab.stores}>
+						<DialogCoordinator />
+						<Layout>
+							<Suspense fallback={<Loading />}>
+								<Router>
+									<AppRouter />
+								</Router>
+							</Suspense>
+						</Layout>
+					</StoreProvider>
+				)}
+			</AppLock>
 		</ErrorBoundary>
 	);
 }
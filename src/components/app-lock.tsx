@@ -0,0 +1,129 @@
+import {
+	type FormEvent,
+	type ReactNode,
+	useEffect,
+	useRef,
+	useState,
+} from 'react';
+
+import { Button } from '@/components/button';
+import { Input } from '@/components/input';
+import { Loading } from '@/components/loading';
+
+import { type LockStatus, SecurityIPC } from '@/lib/services/security';
+
+/** Minimum time between two `record_activity` calls, well below the idle limit. */
+const ACTIVITY_INTERVAL_MS = 15_000;
+
+const ACTIVITY_EVENTS = [
+	'pointerdown',
+	'pointermove',
+	'keydown',
+	'wheel',
+] as const;
+
+export function AppLock({ children }: { children: ReactNode }) {
+	const [status, setStatus] = useState<LockStatus | null>(null);
+	const [password, setPassword] = useState('');
+	const [error, setError] = useState<string | null>(null);
+	const [pending, setPending] = useState(false);
+	const lastActivity = useRef(0);
+
+	const refresh = () =>
+		SecurityIPC.getLockStatus()
+			.then(setStatus)
+			.catch(e => setError(String(e)));
+
+	useEffect(() => {
+		// The backend refuses other commands until this has read the lock settings.
+		refresh();
+
+		const unlisteners = [
+			SecurityIPC.onLocked(refresh),
+			SecurityIPC.onUnlocked(refresh),
+		];
+		return () => {
+			unlisteners.forEach(unlisten => unlisten.then(fn => fn()));
+		};
+	}, []);
+
+	const locked = status?.locked ?? true;
+
+	useEffect(() => {
+		if (locked) {
+			return;
+		}
+
+		const onActivity = () => {
+			const now = Date.now();
+			if (now - lastActivity.current < ACTIVITY_INTERVAL_MS) {
+				return;
+			}
+			lastActivity.current = now;
+			SecurityIPC.recordActivity();
+		};
+
+		ACTIVITY_EVENTS.forEach(event =>
+			window.addEventListener(event, onActivity, { passive: true }),
+		);
+		return () => {
+			ACTIVITY_EVENTS.forEach(event =>
+				window.removeEventListener(event, onActivity),
+			);
+		};
+	}, [locked]);
+
+	const handleSubmit = async (event: FormEvent) => {
+		event.preventDefault();
+		setPending(true);
+		setError(null);
+
+		try {
+			const matches = await SecurityIPC.verifyLockPassword(password);
+			if (!matches) {
+				setError('Incorrect password');
+			}
+			await refresh();
+		} catch (e) {
+			setError(String(e));
+		} finally {
+			setPassword('');
+			setPending(false);
+		}
+	};
+
+	if (!status) {
+		return error ? (
+			<span className="text-sm text-red-500">{error}</span>
+		) : (
+			<Loading />
+		);
+	}
+
+	if (!locked) {
+		return children;
+	}
+
+	return (
+		<div className="flex min-h-screen w-full flex-col items-center justify-center bg-background text-foreground">
+			<form
+				onSubmit={handleSubmit}
+				className="flex w-full max-w-sm flex-col gap-3"
+			>
+				<span className="text-lg font-medium">App is locked</span>
+				<Input
+					type="password"
+					placeholder="Password"
+					value={password}
+					onChange={e => setPassword(e.target.value)}
+					aria-invalid={error !== null}
+					autoFocus
+				/>
+				{error && <span className="text-sm text-red-500">{error}</span>}
+				<Button type="submit" disabled={pending || password.length === 0}>
+					Unlock
+				</Button>
+			</form>
+		</div>
+	);
+}
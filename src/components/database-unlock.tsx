@@ -0,0 +1,51 @@
+import { type FormEvent, useState } from 'react';
+
+import { Button } from '@/components/button';
+import { Input } from '@/components/input';
+
+import { DatabaseIPC } from '@/lib/services/database';
+
+export function DatabaseUnlock({ onUnlock }: { onUnlock: () => void }) {
+	const [password, setPassword] = useState('');
+	const [error, setError] = useState<string | null>(null);
+	const [pending, setPending] = useState(false);
+
+	const handleSubmit = async (event: FormEvent) => {
+		event.preventDefault();
+		setPending(true);
+		setError(null);
+
+		try {
+			await DatabaseIPC.unlockDatabase(password);
+			onUnlock();
+		} catch (e) {
+			setError(String(e));
+			setPassword('');
+		} finally {
+			setPending(false);
+		}
+	};
+
+	return (
+		<div className="flex min-h-screen w-full flex-col items-center justify-center bg-background text-foreground">
+			<form
+				onSubmit={handleSubmit}
+				className="flex w-full max-w-sm flex-col gap-3"
+			>
+				<span className="text-lg font-medium">Database is encrypted</span>
+				<Input
+					type="password"
+					placeholder="Database password"
+					value={password}
+					onChange={e => setPassword(e.target.value)}
+					aria-invalid={error !== null}
+					autoFocus
+				/>
+				{error && <span className="text-sm text-red-500">{error}</span>}
+				<Button type="submit" disabled={pending || password.length === 0}>
+					Unlock
+				</Button>
+			</form>
+		</div>
+	);
+}
@@ -42,12 +42,12 @@ import {
 } from '@/components/accordion';
 
 import { Settings as SettingsModel } from '@/lib/models/settings';
+import { DatabaseIPC } from '@/lib/services/database';
 
 const settingsSchema = z.object({
 	lock_pwd: z.string().optional(),
 	video2x_path: z.string().optional(),
 	veracrypt_path: z.string().optional(),
-	is_db_encrypted: z.number().min(0).max(1),
 	db_encryption_method: z.string().optional(),
 	downloads_path: z.string().optional(),
 	download_speed_limit: z.number().min(0),
@@ -66,6 +66,7 @@ const encryptionMethods = [
 
 export function Settings() {
 	const [loading, setLoading] = useState(false);
+	const [dbEncrypted, setDbEncrypted] = useState(false);
 
 	const form = useForm<SettingsFormData>({
 		resolver: zodResolver(settingsSchema),
@@ -73,7 +74,6 @@ export function Settings() {
 			lock_pwd: '',
 			video2x_path: '',
 			veracrypt_path: '',
-			is_db_encrypted: 0,
 			db_encryption_method: 'aes-256',
 			downloads_path: '',
 			download_speed_limit: 0,
@@ -85,6 +85,13 @@ export function Settings() {
 
 	const { setValue, watch, reset, formState } = form;
 
+	useEffect(() => {
+		// The file header is the source of truth; the settings column is not.
+		DatabaseIPC.getDatabaseStatus().then(status =>
+			setDbEncrypted(status.encrypted),
+		);
+	}, []);
+
 	useEffect(() => {
 		setLoading(true);
 		SettingsModel.get()
@@ -93,7 +100,6 @@ export function Settings() {
 					lock_pwd: settings.lock_pwd ?? '',
 					video2x_path: settings.video2x_path ?? '',
 					veracrypt_path: settings.veracrypt_path ?? '',
-					is_db_encrypted: settings.is_db_encrypted ?? 0,
 					db_encryption_method:
 						settings.db_encryption_method ?? 'aes-256',
 					downloads_path: settings.downloads_path ?? '',
@@ -271,37 +277,16 @@ export function Settings() {
 										<AccordionContent>
 											<div className="space-y-2">
 												<div className="flex items-center justify-between">
-													<FormField
-														control={form.control}
-														name="is_db_encrypted"
-														render={({ field }) => (
-															<FormItem className="flex items-center justify-between w-full">
-																<FormLabel
-																	className="font-light"
-																	htmlFor="db-encryption"
-																>
-																	Enable
-																	Database
-																	Encryption
-																</FormLabel>
-																<FormControl>
-																	<Switch
-																		id="db-encryption"
-																		checked={
-																			!!field.value
-																		}
-																		onCheckedChange={checked =>
-																			field.onChange(
-																				checked
-																					? 1
-																					: 0,
-																			)
-																		}
-																	/>
-																</FormControl>
-																<FormMessage />
-															</FormItem>
-														)}
+													<Label
+														className="font-light"
+														htmlFor="db-encryption"
+													>
+														Database Encryption
+													</Label>
+													<Switch
+														id="db-encryption"
+														checked={dbEncrypted}
+														disabled
 													/>
 												</div>
 												<div className="flex items-center justify-between">
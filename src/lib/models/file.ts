@@ -21,6 +21,10 @@ export class File extends Model<File> {
 	scrape_url!: string | null;
 	download_url!: string | null;
 	is_directory!: number;
+	missing!: number;
+	extension_mismatch!: number;
+	content_hash!: string | null;
+	content_hash_mtime!: number | null;
 	created_at!: string;
 	updated_at!: string;
 
@@ -64,4 +68,12 @@ export class File extends Model<File> {
 	get isDirectory(): boolean {
 		return this.is_directory === 1;
 	}
+
+	get hasExtensionMismatch(): boolean {
+		return this.extension_mismatch === 1;
+	}
+
+	get isMissing(): boolean {
+		return this.missing === 1;
+	}
 }
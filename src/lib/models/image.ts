@@ -11,6 +11,7 @@ export class Image extends Model<Image> {
 	format!: string;
 	color_space!: string | null;
 	has_exif!: number;
+	perceptual_hash!: string | null;
 	created_at!: string;
 	updated_at!: string;
 
@@ -20,6 +20,12 @@ export class Settings extends Model<Settings> {
 	update_check_frequency!: string | null;
 	last_update_check!: string | null;
 	last_cache_clear!: string | null;
+	protected_paths!: string | null;
+	allowed_paths!: string | null;
+	thumbnail_cache_limit!: number;
+	auto_lock_minutes!: number;
+	lock_shortcut!: string | null;
+	upscale_concurrency!: number;
 	created_at!: string;
 	updated_at!: string;
 
@@ -47,6 +53,12 @@ export class Settings extends Model<Settings> {
 				update_check_frequency: 'daily',
 				last_update_check: null,
 				last_cache_clear: null,
+				protected_paths: null,
+				allowed_paths: null,
+				thumbnail_cache_limit: 512 * 1024 * 1024,
+				auto_lock_minutes: 0,
+				lock_shortcut: null,
+				upscale_concurrency: 1,
 			});
 		}
 
@@ -72,6 +84,18 @@ export class Settings extends Model<Settings> {
 		return this.download_speed_limit / (1024 * 1024);
 	}
 
+	get thumbnailCacheLimitMB(): number {
+		return this.thumbnail_cache_limit / (1024 * 1024);
+	}
+
+	get protectedPaths(): string[] {
+		return this.protected_paths ? JSON.parse(this.protected_paths) : [];
+	}
+
+	get allowedPaths(): string[] {
+		return this.allowed_paths ? JSON.parse(this.allowed_paths) : [];
+	}
+
 	get isLocked(): boolean {
 		return this.lock_pwd !== null && this.lock_pwd.length > 0;
 	}
@@ -0,0 +1,34 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export interface DatabaseStatus {
+	encrypted: boolean;
+	unlocked: boolean;
+}
+
+export class DatabaseIPC {
+	/* ──────────────────────────
+	 * Encryption
+	 * ────────────────────────── */
+
+	static getDatabaseStatus(): Promise<DatabaseStatus> {
+		return invoke('get_database_status');
+	}
+
+	static unlockDatabase(password: string): Promise<void> {
+		return invoke('unlock_database', { password });
+	}
+
+	static encryptDatabase(password: string): Promise<void> {
+		return invoke('encrypt_database', { password });
+	}
+
+	static rekeyDatabase(
+		currentPassword: string,
+		newPassword: string | null,
+	): Promise<void> {
+		return invoke('rekey_database', {
+			current_password: currentPassword,
+			new_password: newPassword,
+		});
+	}
+}
@@ -0,0 +1,19 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export interface DocumentExtract {
+	id: number | null;
+	content: string;
+	line_count: number;
+	word_count: number;
+	truncated: boolean;
+}
+
+export class DocumentIPC {
+	/* ──────────────────────────
+	 * Extraction
+	 * ────────────────────────── */
+
+	static extractDocument(path: string): Promise<DocumentExtract> {
+		return invoke('extract_document', { path });
+	}
+}
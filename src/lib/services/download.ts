@@ -0,0 +1,100 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+export type DownloadStatus =
+	| 'queued'
+	| 'downloading'
+	| 'paused'
+	| 'completed'
+	| 'failed'
+	| 'cancelled';
+
+export interface Download {
+	id: number;
+	url: string;
+	destination: string;
+	status: DownloadStatus;
+	total_bytes: number | null;
+	downloaded_bytes: number;
+	checksum: string | null;
+	error: string | null;
+	file_id: number | null;
+	created_at: string;
+}
+
+export interface DownloadProgress {
+	id: number;
+	status: DownloadStatus;
+	downloaded_bytes: number;
+	total_bytes: number | null;
+	bytes_per_second: number;
+}
+
+export interface AddDownloadOptions {
+	fileName?: string | null;
+	directory?: string | null;
+	/** `sha256:<hex>`, `md5:<hex>` or `blake3:<hex>`. */
+	checksum?: string | null;
+}
+
+export class DownloadIPC {
+	/* ──────────────────────────
+	 * Queue
+	 * ────────────────────────── */
+
+	static addDownload(
+		url: string,
+		options: AddDownloadOptions = {},
+	): Promise<Download> {
+		return invoke('add_download', {
+			url,
+			file_name: options.fileName ?? null,
+			directory: options.directory ?? null,
+			checksum: options.checksum ?? null,
+		});
+	}
+
+	static listDownloads(): Promise<Download[]> {
+		return invoke('list_downloads');
+	}
+
+	static removeDownload(id: number): Promise<void> {
+		return invoke('remove_download', { id });
+	}
+
+	/* ──────────────────────────
+	 * Control
+	 * ────────────────────────── */
+
+	static pauseDownload(id: number): Promise<Download> {
+		return invoke('pause_download', { id });
+	}
+
+	static resumeDownload(id: number): Promise<Download> {
+		return invoke('resume_download', { id });
+	}
+
+	static cancelDownload(id: number): Promise<Download> {
+		return invoke('cancel_download', { id });
+	}
+
+	/* ──────────────────────────
+	 * Events
+	 * ────────────────────────── */
+
+	static onDownloadProgress(
+		callback: (progress: DownloadProgress) => void,
+	): Promise<UnlistenFn> {
+		return listen<DownloadProgress>('download-progress', event =>
+			callback(event.payload),
+		);
+	}
+
+	static onDownloadFinished(
+		callback: (download: Download) => void,
+	): Promise<UnlistenFn> {
+		return listen<Download>('download-finished', event =>
+			callback(event.payload),
+		);
+	}
+}
@@ -1,4 +1,4 @@
-import { invoke } from '@tauri-apps/api/core';
+import { Channel, invoke } from '@tauri-apps/api/core';
 import { appConfigDir } from '@tauri-apps/api/path';
 
 import type { File } from '@/lib/models/file';
@@ -9,6 +9,84 @@ export interface FolderPage {
 	current_page: number;
 }
 
+export interface PathSegment {
+	name: string;
+	path: string;
+}
+
+export interface Disk {
+	mount_point: string;
+	label: string | null;
+	filesystem: string;
+	total_bytes: number;
+	free_bytes: number;
+	is_removable: boolean;
+}
+
+export type FetchSortKey = 'name' | 'size' | 'mtime' | 'type';
+
+export interface FetchOptions {
+	extensions?: string[];
+	sort?: FetchSortKey;
+	descending?: boolean;
+	page_size?: number;
+}
+
+export interface SkippedEntry {
+	path: string;
+	error: string;
+}
+
+export interface FetchPage {
+	files: File[];
+	skipped: SkippedEntry[];
+	done: boolean;
+}
+
+export interface FetchSummary {
+	total: number;
+	skipped: number;
+	cancelled: boolean;
+}
+
+export type PatternMode = 'substring' | 'glob' | 'regex';
+
+export interface SearchOptions {
+	mode?: PatternMode;
+	case_sensitive?: boolean;
+	match_path?: boolean;
+	include_hidden?: boolean;
+	include_directories?: boolean;
+	min_size?: number;
+	max_size?: number;
+	modified_after?: number;
+	modified_before?: number;
+	file_types?: string[];
+	max_depth?: number;
+	limit?: number;
+}
+
+export interface SearchHit {
+	path: string;
+	name: string;
+	size: number;
+	mtime: number;
+	file_type: string;
+	is_directory: number;
+}
+
+export interface SearchBatch {
+	hits: SearchHit[];
+	done: boolean;
+}
+
+export interface SearchSummary {
+	matched: number;
+	scanned: number;
+	truncated: boolean;
+	cancelled: boolean;
+}
+
 export class FileIPC {
 	/* ──────────────────────────
 	 * System / OS integration
@@ -22,7 +100,7 @@ export class FileIPC {
 		return invoke('highlight_path', { file_path: path });
 	}
 
-	static getAvailableDisks(): Promise<string[]> {
+	static getAvailableDisks(): Promise<Disk[]> {
 		return invoke('get_available_disks');
 	}
 
@@ -50,6 +128,14 @@ export class FileIPC {
 		return invoke('trash', { path });
 	}
 
+	static movePath(path: string, targetFolder: string): Promise<string> {
+		return invoke('move_path', { path, target_folder: targetFolder });
+	}
+
+	static renamePath(path: string, newName: string): Promise<string> {
+		return invoke('rename_path', { path, new_name: newName });
+	}
+
 	static getFileDetails(path: string): Promise<File> {
 		return invoke('file_details', { path });
 	}
@@ -59,15 +145,53 @@ export class FileIPC {
 	 * ────────────────────────── */
 
 	static fetchFiles(
+		fetchId: string,
 		folderPath: string,
-		extensionFilter?: string,
-	): Promise<File[]> {
+		onPage: (page: FetchPage) => void,
+		options?: FetchOptions,
+	): Promise<FetchSummary> {
+		const channel = new Channel<FetchPage>();
+		channel.onmessage = onPage;
+
 		return invoke('fetch_files', {
+			fetch_id: fetchId,
 			folder_path: folderPath,
-			extension_filter: extensionFilter,
+			options,
+			on_page: channel,
+		});
+	}
+
+	static cancelFetchFiles(fetchId: string): Promise<boolean> {
+		return invoke('cancel_fetch_files', { fetch_id: fetchId });
+	}
+
+	/* ──────────────────────────
+	 * Search
+	 * ────────────────────────── */
+
+	static searchFiles(
+		searchId: string,
+		root: string,
+		query: string,
+		onBatch: (batch: SearchBatch) => void,
+		options?: SearchOptions,
+	): Promise<SearchSummary> {
+		const channel = new Channel<SearchBatch>();
+		channel.onmessage = onBatch;
+
+		return invoke('search_files', {
+			search_id: searchId,
+			root,
+			query,
+			options,
+			on_batch: channel,
 		});
 	}
 
+	static cancelSearchFiles(searchId: string): Promise<boolean> {
+		return invoke('cancel_search_files', { search_id: searchId });
+	}
+
 	static getFolders(
 		path: string,
 		page: number = 1,
@@ -84,7 +208,7 @@ export class FileIPC {
 		return invoke('get_parent_path', { path });
 	}
 
-	static getPathSegments(path: string): Promise<string[]> {
+	static getPathSegments(path: string): Promise<PathSegment[]> {
 		return invoke('get_path_segments', { path });
 	}
 
@@ -0,0 +1,94 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+export type JobKind = 'copy' | 'move' | 'trash';
+export type JobState =
+	| 'queued'
+	| 'running'
+	| 'paused'
+	| 'cancelled'
+	| 'completed'
+	| 'failed';
+export type ConflictPolicy = 'skip' | 'overwrite' | 'rename';
+export type ItemStatus = 'done' | 'skipped' | 'failed' | 'cancelled';
+
+export interface JobRequest {
+	kind: JobKind;
+	sources: string[];
+	destination?: string;
+	conflict?: ConflictPolicy;
+}
+
+export interface JobProgress {
+	job_id: string;
+	kind: JobKind;
+	state: JobState;
+	total_items: number;
+	processed_items: number;
+	total_bytes: number;
+	processed_bytes: number;
+	current_path: string | null;
+}
+
+export interface ItemReport {
+	source: string;
+	destination: string | null;
+	status: ItemStatus;
+	error: string | null;
+}
+
+export interface JobReport extends JobProgress {
+	items: ItemReport[];
+}
+
+export class JobIPC {
+	/* ──────────────────────────
+	 * Queue
+	 * ────────────────────────── */
+
+	static startJob(request: JobRequest): Promise<string> {
+		return invoke('start_job', { request });
+	}
+
+	static listJobs(): Promise<JobProgress[]> {
+		return invoke('list_jobs');
+	}
+
+	static getJobReport(jobId: string): Promise<JobReport> {
+		return invoke('get_job_report', { job_id: jobId });
+	}
+
+	/* ──────────────────────────
+	 * Control
+	 * ────────────────────────── */
+
+	static pauseJob(jobId: string): Promise<void> {
+		return invoke('pause_job', { job_id: jobId });
+	}
+
+	static resumeJob(jobId: string): Promise<void> {
+		return invoke('resume_job', { job_id: jobId });
+	}
+
+	static cancelJob(jobId: string): Promise<void> {
+		return invoke('cancel_job', { job_id: jobId });
+	}
+
+	/* ──────────────────────────
+	 * Events
+	 * ────────────────────────── */
+
+	static onJobProgress(
+		callback: (progress: JobProgress) => void,
+	): Promise<UnlistenFn> {
+		return listen<JobProgress>('job-progress', event =>
+			callback(event.payload),
+		);
+	}
+
+	static onJobFinished(
+		callback: (report: JobReport) => void,
+	): Promise<UnlistenFn> {
+		return listen<JobReport>('job-finished', event => callback(event.payload));
+	}
+}
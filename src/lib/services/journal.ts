@@ -0,0 +1,29 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export type OperationKind = 'trash' | 'move' | 'rename' | 'copy';
+
+export interface OperationRecord {
+	id: number;
+	batch_id: string;
+	kind: OperationKind;
+	source_path: string;
+	target_path: string | null;
+	undone: number;
+	/** Set when the entry could never be undone and was skipped. */
+	undo_error: string | null;
+	created_at: string;
+}
+
+export class JournalIPC {
+	/* ──────────────────────────
+	 * Operations
+	 * ────────────────────────── */
+
+	static listOperations(limit?: number): Promise<OperationRecord[]> {
+		return invoke('list_operations', { limit });
+	}
+
+	static undoLastOperation(): Promise<OperationRecord[]> {
+		return invoke('undo_last_operation');
+	}
+}
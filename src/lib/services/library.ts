@@ -0,0 +1,129 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+export interface ScanOptions {
+	ignore_patterns?: string[];
+	follow_symlinks?: boolean;
+	max_depth?: number;
+}
+
+export interface ScanProgress {
+	scan_id: string;
+	root: string;
+	scanned: number;
+	added: number;
+	updated: number;
+	unchanged: number;
+	missing: number;
+	skipped: number;
+	current_path: string | null;
+	finished: boolean;
+	error: string | null;
+}
+
+export interface FileChange {
+	kind: 'indexed' | 'renamed' | 'removed';
+	path: string;
+	old_path: string | null;
+}
+
+export interface DuplicateProgress {
+	stage: 'scanning' | 'partial_hash' | 'full_hash' | 'finished';
+	processed: number;
+	total: number;
+	bytes_hashed: number;
+	skipped: number;
+	current_path: string | null;
+	groups: number;
+	finished: boolean;
+}
+
+export interface DuplicateFile {
+	path: string;
+	mtime: number;
+	in_content: boolean;
+}
+
+export interface DuplicateGroup {
+	hash: string;
+	size: number;
+	files: DuplicateFile[];
+	keeper: string;
+	reclaimable_bytes: number;
+}
+
+export interface SearchFilters {
+	content_type_id?: number;
+	category_id?: number;
+	favorite?: boolean;
+	limit?: number;
+	offset?: number;
+}
+
+export interface LibraryHit {
+	content_id: number;
+	content_type_id: number;
+	category_id: number;
+	name: string;
+	cover: string | null;
+	highlighted_name: string;
+	snippet: string;
+	rank: number;
+}
+
+export class LibraryIPC {
+	/* ──────────────────────────
+	 * Indexing
+	 * ────────────────────────── */
+
+	static scanLibrary(root: string, options?: ScanOptions): Promise<string> {
+		return invoke('scan_library', { root, options });
+	}
+
+	static removeLibraryRoot(root: string): Promise<void> {
+		return invoke('remove_library_root', { root });
+	}
+
+	static onScanProgress(
+		callback: (progress: ScanProgress) => void,
+	): Promise<UnlistenFn> {
+		return listen<ScanProgress>('library-scan-progress', event =>
+			callback(event.payload),
+		);
+	}
+
+	static onFileChanged(
+		callback: (change: FileChange) => void,
+	): Promise<UnlistenFn> {
+		return listen<FileChange>('library-file-changed', event =>
+			callback(event.payload),
+		);
+	}
+
+	/* ──────────────────────────
+	 * Duplicates
+	 * ────────────────────────── */
+
+	static findDuplicates(roots: string[]): Promise<DuplicateGroup[]> {
+		return invoke('find_duplicates', { roots });
+	}
+
+	static onDuplicatesProgress(
+		callback: (progress: DuplicateProgress) => void,
+	): Promise<UnlistenFn> {
+		return listen<DuplicateProgress>('library-duplicates-progress', event =>
+			callback(event.payload),
+		);
+	}
+
+	/* ──────────────────────────
+	 * Search
+	 * ────────────────────────── */
+
+	static searchLibrary(
+		query: string,
+		filters?: SearchFilters,
+	): Promise<LibraryHit[]> {
+		return invoke('search_library', { query, filters });
+	}
+}
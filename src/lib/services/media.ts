@@ -0,0 +1,83 @@
+import { invoke } from '@tauri-apps/api/core';
+
+import type { MediaQuality } from '@/lib/models/file';
+
+export interface ImageProbe {
+	id: number | null;
+	width: number;
+	height: number;
+	resolution: string;
+	quality: MediaQuality;
+	format: string;
+	color_space: string | null;
+	has_exif: number;
+}
+
+export interface ImageProbeResult {
+	path: string;
+	image: ImageProbe | null;
+	error: string | null;
+}
+
+export interface SimilarImage {
+	file_id: number;
+	path: string;
+	width: number;
+	height: number;
+	size: number;
+	distance: number;
+}
+
+export interface SimilarGroup {
+	images: SimilarImage[];
+}
+
+export interface VideoProbe {
+	id: number | null;
+	fps: number;
+	duration: number;
+	duration_display: string;
+	resolution: string;
+	quality: MediaQuality;
+	format: string;
+	codec: string;
+	audio_streams: string;
+	subtitles: string;
+	chapters: string;
+}
+
+export interface VideoProbeResult {
+	path: string;
+	video: VideoProbe | null;
+	error: string | null;
+}
+
+export class MediaIPC {
+	/* ──────────────────────────
+	 * Images
+	 * ────────────────────────── */
+
+	static probeImage(path: string): Promise<ImageProbe> {
+		return invoke('probe_image', { path });
+	}
+
+	static probeImages(paths: string[]): Promise<ImageProbeResult[]> {
+		return invoke('probe_images', { paths });
+	}
+
+	static findSimilarImages(threshold: number): Promise<SimilarGroup[]> {
+		return invoke('find_similar_images', { threshold });
+	}
+
+	/* ──────────────────────────
+	 * Videos
+	 * ────────────────────────── */
+
+	static probeVideo(path: string): Promise<VideoProbe> {
+		return invoke('probe_video', { path });
+	}
+
+	static probeVideos(paths: string[]): Promise<VideoProbeResult[]> {
+		return invoke('probe_videos', { paths });
+	}
+}
@@ -0,0 +1,60 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+export interface LockStatus {
+	locked: boolean;
+	has_password: boolean;
+	failed_attempts: number;
+	retry_after: number;
+}
+
+export class SecurityIPC {
+	/* ──────────────────────────
+	 * Lock
+	 * ────────────────────────── */
+
+	static getLockStatus(): Promise<LockStatus> {
+		return invoke('get_lock_status');
+	}
+
+	static lockApp(): Promise<void> {
+		return invoke('lock_app');
+	}
+
+	static verifyLockPassword(password: string): Promise<boolean> {
+		return invoke('verify_lock_password', { password });
+	}
+
+	static setLockPassword(
+		currentPassword: string | null,
+		newPassword: string | null,
+	): Promise<void> {
+		return invoke('set_lock_password', {
+			current_password: currentPassword,
+			new_password: newPassword,
+		});
+	}
+
+	static onLocked(callback: () => void): Promise<UnlistenFn> {
+		return listen('app-locked', () => callback());
+	}
+
+	static onUnlocked(callback: () => void): Promise<UnlistenFn> {
+		return listen('app-unlocked', () => callback());
+	}
+
+	/* ──────────────────────────
+	 * Auto-lock
+	 * ────────────────────────── */
+
+	static setAutoLock(
+		idleMinutes: number,
+		shortcut: string | null,
+	): Promise<void> {
+		return invoke('set_auto_lock', { idle_minutes: idleMinutes, shortcut });
+	}
+
+	static recordActivity(): Promise<void> {
+		return invoke('record_activity');
+	}
+}
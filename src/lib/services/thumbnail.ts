@@ -0,0 +1,19 @@
+import { convertFileSrc, invoke } from '@tauri-apps/api/core';
+
+export class ThumbnailIPC {
+	/* ──────────────────────────
+	 * Cache
+	 * ────────────────────────── */
+
+	static getThumbnail(path: string): Promise<string> {
+		return invoke('get_thumbnail', { path });
+	}
+
+	static async getThumbnailUrl(path: string): Promise<string> {
+		return convertFileSrc(await this.getThumbnail(path));
+	}
+
+	static clearThumbnailCache(): Promise<number> {
+		return invoke('clear_thumbnail_cache');
+	}
+}
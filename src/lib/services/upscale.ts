@@ -0,0 +1,72 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+export type UpscaleProcessor = 'realesrgan' | 'realcugan' | 'libplacebo';
+export type UpscaleOutputLocation = 'source' | 'downloads';
+export type UpscaleState =
+	| 'queued'
+	| 'running'
+	| 'completed'
+	| 'failed'
+	| 'cancelled';
+
+export interface UpscaleOptions {
+	processor?: UpscaleProcessor;
+	scale?: number;
+	model?: string | null;
+	device?: number | null;
+	output_location?: UpscaleOutputLocation;
+}
+
+export interface UpscaleProgress {
+	job_id: string;
+	state: UpscaleState;
+	source: string;
+	output: string | null;
+	processed_frames: number;
+	total_frames: number;
+	percent: number;
+	file_id: number | null;
+	error: string | null;
+}
+
+export class UpscaleIPC {
+	/* ──────────────────────────
+	 * Queue
+	 * ────────────────────────── */
+
+	static startUpscale(
+		source: string,
+		options: UpscaleOptions = {},
+	): Promise<string> {
+		return invoke('start_upscale', { source, options });
+	}
+
+	static listUpscales(): Promise<UpscaleProgress[]> {
+		return invoke('list_upscales');
+	}
+
+	static cancelUpscale(jobId: string): Promise<void> {
+		return invoke('cancel_upscale', { job_id: jobId });
+	}
+
+	/* ──────────────────────────
+	 * Events
+	 * ────────────────────────── */
+
+	static onUpscaleProgress(
+		callback: (progress: UpscaleProgress) => void,
+	): Promise<UnlistenFn> {
+		return listen<UpscaleProgress>('upscale-progress', event =>
+			callback(event.payload),
+		);
+	}
+
+	static onUpscaleFinished(
+		callback: (progress: UpscaleProgress) => void,
+	): Promise<UnlistenFn> {
+		return listen<UpscaleProgress>('upscale-finished', event =>
+			callback(event.payload),
+		);
+	}
+}
@@ -0,0 +1,60 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export interface Vault {
+	id: number;
+	name: string;
+	container_path: string;
+	mount_point: string | null;
+	created_at: string;
+}
+
+export interface VaultStatus extends Vault {
+	mounted_at: string | null;
+}
+
+export interface MountResult {
+	mount_point: string;
+	scan_id: string | null;
+}
+
+export class VaultIPC {
+	/* ──────────────────────────
+	 * Vaults
+	 * ────────────────────────── */
+
+	static listVaults(): Promise<VaultStatus[]> {
+		return invoke('list_vaults');
+	}
+
+	static addVault(
+		name: string,
+		containerPath: string,
+		mountPoint?: string | null,
+	): Promise<Vault> {
+		return invoke('add_vault', {
+			name,
+			container_path: containerPath,
+			mount_point: mountPoint ?? null,
+		});
+	}
+
+	static removeVault(id: number): Promise<void> {
+		return invoke('remove_vault', { id });
+	}
+
+	/* ──────────────────────────
+	 * Mounting
+	 * ────────────────────────── */
+
+	static mountVault(
+		id: number,
+		password: string,
+		pim?: number,
+	): Promise<MountResult> {
+		return invoke('mount_vault', { id, password, pim });
+	}
+
+	static dismountVault(id: number, force = false): Promise<void> {
+		return invoke('dismount_vault', { id, force });
+	}
+}